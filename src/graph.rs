@@ -0,0 +1,484 @@
+use crate::parser::ue::project::UnrealProject;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DependencyKind {
+    Public,
+    Private,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub from: usize,
+    pub to: usize,
+    pub kind: DependencyKind,
+}
+
+/// Modules and their `Build.cs` dependencies. Modules that aren't part of the project, like
+/// engine modules, are added as external nodes.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ModuleGraph {
+    pub modules: Vec<String>,
+    /// Number of modules at the start of `modules` that belong to the project
+    pub project_modules: usize,
+    pub dependencies: Vec<Dependency>,
+}
+
+impl ModuleGraph {
+    pub fn from_project(project: &UnrealProject) -> ModuleGraph {
+        let mut graph = ModuleGraph {
+            modules: project.modules.iter().map(|m| m.name.clone()).collect(),
+            project_modules: project.modules.len(),
+            dependencies: vec![],
+        };
+
+        for (from, module) in project.modules.iter().enumerate() {
+            let Some(rules) = &module.rules else {
+                continue;
+            };
+            let public = rules
+                .public_dependencies
+                .iter()
+                .map(|name| (name, DependencyKind::Public));
+            let private = rules
+                .private_dependencies
+                .iter()
+                .map(|name| (name, DependencyKind::Private));
+            for (name, kind) in public.chain(private) {
+                let to = graph.module_index(name);
+                // a module listed as public and private is a public dependency
+                if !graph
+                    .dependencies
+                    .iter()
+                    .any(|d| d.from == from && d.to == to)
+                {
+                    graph.dependencies.push(Dependency { from, to, kind });
+                }
+            }
+        }
+
+        graph
+    }
+
+    fn module_index(&mut self, name: &str) -> usize {
+        match self.modules.iter().position(|module| module == name) {
+            Some(index) => index,
+            None => {
+                self.modules.push(name.to_string());
+                self.modules.len() - 1
+            }
+        }
+    }
+
+    fn is_external(&self, module: usize) -> bool {
+        module >= self.project_modules
+    }
+
+    /// Modules depending on each other directly or indirectly, each sorted by name
+    pub fn cycles(&self) -> Vec<Vec<&str>> {
+        let successors = self.successors();
+        let mut cycles = strongly_connected_components(&successors)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || successors[component[0]].contains(&component[0])
+            })
+            .map(|component| {
+                let mut names = component
+                    .into_iter()
+                    .map(|module| self.modules[module].as_str())
+                    .collect::<Vec<_>>();
+                names.sort();
+                names
+            })
+            .collect::<Vec<_>>();
+        cycles.sort();
+
+        cycles
+    }
+
+    /// Dependencies of every module by index
+    fn successors(&self) -> Vec<Vec<usize>> {
+        let mut successors = vec![vec![]; self.modules.len()];
+        for dependency in &self.dependencies {
+            successors[dependency.from].push(dependency.to);
+        }
+
+        successors
+    }
+
+    /// Component index of every module, modules in the same component form a cycle
+    fn component_of(&self) -> Vec<usize> {
+        let mut component_of = vec![0; self.modules.len()];
+        for (index, component) in strongly_connected_components(&self.successors())
+            .iter()
+            .enumerate()
+        {
+            for module in component {
+                component_of[*module] = index;
+            }
+        }
+
+        component_of
+    }
+
+    fn in_cycle(&self, dependency: &Dependency, component_of: &[usize]) -> bool {
+        component_of[dependency.from] == component_of[dependency.to]
+    }
+
+    /// Graphviz DOT. Private dependencies are dashed, dependencies within a cycle are red and
+    /// external modules are grey.
+    pub fn to_dot(&self) -> String {
+        let component_of = self.component_of();
+        let cycle_modules = self.cycle_modules(&component_of);
+
+        let mut dot = String::from("digraph modules {\n    rankdir=LR;\n    node [shape=box];\n");
+        for (index, module) in self.modules.iter().enumerate() {
+            let style = match (self.is_external(index), cycle_modules.contains(&index)) {
+                (_, true) => " [color=red, fontcolor=red]",
+                (true, false) => " [style=filled, fillcolor=lightgrey, color=grey]",
+                (false, false) => "",
+            };
+            let _ = writeln!(dot, "    \"{module}\"{style};");
+        }
+        for dependency in &self.dependencies {
+            let mut attributes = vec![];
+            if dependency.kind == DependencyKind::Private {
+                attributes.push("style=dashed");
+            }
+            if self.in_cycle(dependency, &component_of) {
+                attributes.push("color=red");
+            }
+            let attributes = match attributes.is_empty() {
+                true => String::new(),
+                false => format!(" [{}]", attributes.join(", ")),
+            };
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\"{attributes};",
+                self.modules[dependency.from], self.modules[dependency.to]
+            );
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+
+    /// Mermaid flowchart with the same highlighting as [`ModuleGraph::to_dot`]
+    pub fn to_mermaid(&self) -> String {
+        let component_of = self.component_of();
+        let cycle_modules = self.cycle_modules(&component_of);
+        let ids = mermaid_ids(&self.modules);
+
+        let mut mermaid = String::from("flowchart LR\n");
+        for (id, module) in ids.iter().zip(&self.modules) {
+            let _ = writeln!(mermaid, "    {id}[\"{module}\"]");
+        }
+        for dependency in &self.dependencies {
+            let arrow = match dependency.kind {
+                DependencyKind::Public => "-->",
+                DependencyKind::Private => "-.->",
+            };
+            let _ = writeln!(
+                mermaid,
+                "    {} {arrow} {}",
+                ids[dependency.from], ids[dependency.to]
+            );
+        }
+
+        mermaid.push_str("    classDef external fill:#eee,stroke:#999,color:#666\n");
+        mermaid.push_str("    classDef cycle stroke:#d00,color:#d00\n");
+        let external = (self.project_modules..self.modules.len())
+            .filter(|module| !cycle_modules.contains(module))
+            .map(|module| ids[module].as_str())
+            .collect::<Vec<_>>();
+        if !external.is_empty() {
+            let _ = writeln!(mermaid, "    class {} external", external.join(","));
+        }
+        if !cycle_modules.is_empty() {
+            let cycle = cycle_modules
+                .iter()
+                .map(|module| ids[*module].as_str())
+                .collect::<Vec<_>>();
+            let _ = writeln!(mermaid, "    class {} cycle", cycle.join(","));
+        }
+        let cycle_edges = self
+            .dependencies
+            .iter()
+            .enumerate()
+            .filter(|(_, dependency)| self.in_cycle(dependency, &component_of))
+            .map(|(index, _)| index.to_string())
+            .collect::<Vec<_>>();
+        if !cycle_edges.is_empty() {
+            let _ = writeln!(
+                mermaid,
+                "    linkStyle {} stroke:#d00",
+                cycle_edges.join(",")
+            );
+        }
+
+        mermaid
+    }
+
+    fn cycle_modules(&self, component_of: &[usize]) -> BTreeSet<usize> {
+        self.dependencies
+            .iter()
+            .filter(|dependency| self.in_cycle(dependency, component_of))
+            .flat_map(|dependency| [dependency.from, dependency.to])
+            .collect()
+    }
+}
+
+/// Mermaid ids can't contain dots or dashes, which appear in plugin module names, and keywords
+/// like `end` break the chart. Names that only differ in those characters get a suffix.
+fn mermaid_ids(modules: &[String]) -> Vec<String> {
+    const KEYWORDS: [&str; 9] = [
+        "end",
+        "graph",
+        "flowchart",
+        "subgraph",
+        "direction",
+        "style",
+        "class",
+        "classdef",
+        "linkstyle",
+    ];
+
+    let mut ids: Vec<String> = vec![];
+    for module in modules {
+        let mut id = module
+            .chars()
+            .map(|c| match c.is_alphanumeric() {
+                true => c,
+                false => '_',
+            })
+            .collect::<String>();
+        if KEYWORDS.contains(&id.to_lowercase().as_str()) {
+            id.push('_');
+        }
+        let base = id.clone();
+        let mut suffix = 1;
+        while ids.contains(&id) {
+            suffix += 1;
+            id = format!("{base}_{suffix}");
+        }
+        ids.push(id);
+    }
+
+    ids
+}
+
+/// Strongly connected components of the graph with the nodes `0..successors.len()`, using
+/// Tarjan's algorithm. Every node is in exactly one component, a node that isn't part of a cycle
+/// is a component of its own. Components come in reverse topological order.
+pub fn strongly_connected_components(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut next_index = 0;
+    let mut index: Vec<Option<usize>> = vec![None; successors.len()];
+    let mut low_link = vec![0; successors.len()];
+    let mut on_stack = vec![false; successors.len()];
+    let mut stack = vec![];
+    let mut components = vec![];
+    // nodes being visited with the position of their next successor, an explicit call stack so
+    // long include chains can't overflow the thread's stack
+    let mut visits: Vec<(usize, usize)> = vec![];
+
+    for root in 0..successors.len() {
+        if index[root].is_some() {
+            continue;
+        }
+        visits.push((root, 0));
+
+        while let Some((node, position)) = visits.pop() {
+            if index[node].is_none() {
+                index[node] = Some(next_index);
+                low_link[node] = next_index;
+                next_index += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+
+            if let Some(&next) = successors[node].get(position) {
+                visits.push((node, position + 1));
+                match index[next] {
+                    None => visits.push((next, 0)),
+                    Some(visited) if on_stack[next] => {
+                        low_link[node] = low_link[node].min(visited);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            if Some(low_link[node]) == index[node] {
+                let mut component = vec![];
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+            // back in the node that visited this one
+            if let Some(&(parent, _)) = visits.last() {
+                low_link[parent] = low_link[parent].min(low_link[node]);
+            }
+        }
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{DependencyKind, ModuleGraph, mermaid_ids, strongly_connected_components};
+    use crate::parser::ue::module_rules::ModuleRules;
+    use crate::parser::ue::project::{LoadingPhase, ModuleType, UnrealModule, UnrealProject};
+    use std::path::PathBuf;
+
+    fn module(name: &str, public: &[&str], private: &[&str]) -> UnrealModule {
+        UnrealModule {
+            name: name.to_string(),
+            kind: ModuleType::Runtime,
+            loading_phase: LoadingPhase::Default,
+            plugin: None,
+            source_dir: PathBuf::from("Source").join(name),
+            public_dir: None,
+            private_dir: None,
+            classes_dir: None,
+            rules: Some(ModuleRules {
+                name: name.to_string(),
+                public_dependencies: public.iter().map(|s| s.to_string()).collect(),
+                private_dependencies: private.iter().map(|s| s.to_string()).collect(),
+                ..Default::default()
+            }),
+            rules_diagnostic: None,
+        }
+    }
+
+    fn project() -> UnrealProject {
+        UnrealProject {
+            name: "Shooter".to_string(),
+            root: PathBuf::new(),
+            modules: vec![
+                module("Shooter", &["Core", "Loot"], &["Core"]),
+                module("Loot", &["Core"], &["Inventory"]),
+                module("Inventory", &[], &["Loot"]),
+            ],
+        }
+    }
+
+    #[test]
+    fn dependencies_and_cycles() {
+        let graph = ModuleGraph::from_project(&project());
+
+        assert_eq!(graph.modules, vec!["Shooter", "Loot", "Inventory", "Core"]);
+        assert_eq!(
+            graph
+                .dependencies
+                .iter()
+                .map(|d| (
+                    graph.modules[d.from].as_str(),
+                    graph.modules[d.to].as_str(),
+                    d.kind
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Shooter", "Core", DependencyKind::Public),
+                ("Shooter", "Loot", DependencyKind::Public),
+                ("Loot", "Core", DependencyKind::Public),
+                ("Loot", "Inventory", DependencyKind::Private),
+                ("Inventory", "Loot", DependencyKind::Private),
+            ]
+        );
+        assert_eq!(graph.cycles(), vec![vec!["Inventory", "Loot"]]);
+    }
+
+    #[test]
+    fn dot_output() {
+        let graph = ModuleGraph::from_project(&project());
+
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph modules {
+    rankdir=LR;
+    node [shape=box];
+    "Shooter";
+    "Loot" [color=red, fontcolor=red];
+    "Inventory" [color=red, fontcolor=red];
+    "Core" [style=filled, fillcolor=lightgrey, color=grey];
+    "Shooter" -> "Core";
+    "Shooter" -> "Loot";
+    "Loot" -> "Core";
+    "Loot" -> "Inventory" [style=dashed, color=red];
+    "Inventory" -> "Loot" [style=dashed, color=red];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn mermaid_output() {
+        let graph = ModuleGraph::from_project(&project());
+
+        assert_eq!(
+            graph.to_mermaid(),
+            r#"flowchart LR
+    Shooter["Shooter"]
+    Loot["Loot"]
+    Inventory["Inventory"]
+    Core["Core"]
+    Shooter --> Core
+    Shooter --> Loot
+    Loot --> Core
+    Loot -.-> Inventory
+    Inventory -.-> Loot
+    classDef external fill:#eee,stroke:#999,color:#666
+    classDef cycle stroke:#d00,color:#d00
+    class Core external
+    class Loot,Inventory cycle
+    linkStyle 3,4 stroke:#d00
+"#
+        );
+    }
+
+    #[test]
+    fn unique_mermaid_ids() {
+        let modules = ["Loot-Core", "Loot_Core", "Loot.Core", "end", "End", "end_"];
+        let modules = modules.map(String::from);
+
+        assert_eq!(
+            mermaid_ids(&modules),
+            vec![
+                "Loot_Core",
+                "Loot_Core_2",
+                "Loot_Core_3",
+                "end_",
+                "End_",
+                "end__2"
+            ]
+        );
+    }
+
+    #[test]
+    fn components() {
+        let successors = vec![vec![1], vec![2], vec![0, 3], vec![3], vec![]];
+
+        assert_eq!(
+            strongly_connected_components(&successors),
+            vec![vec![3], vec![2, 1, 0], vec![4]]
+        );
+    }
+
+    #[test]
+    fn components_of_long_chains() {
+        let count = 200_000;
+        let mut successors = (1..=count).map(|next| vec![next]).collect::<Vec<_>>();
+        successors[count - 1] = vec![0];
+
+        let components = strongly_connected_components(&successors);
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), count);
+    }
+}
@@ -1,6 +1,124 @@
+mod graph;
 mod parser;
 mod types;
 
-fn main() {
-    println!("Hello, world!");
+use crate::graph::ModuleGraph;
+use crate::parser::compile_commands::{CompilationDatabase, CompileCommand};
+use crate::parser::include::IncludeGraph;
+use crate::parser::ue::project::UnrealProject;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: uedoc graph modules <project.uproject> [--out <directory>]
+       uedoc includes <compile_commands.json>";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["graph", "modules", uproject, rest @ ..] => {
+            let out_dir = match rest {
+                [] => PathBuf::from("."),
+                ["--out", dir] => PathBuf::from(dir),
+                _ => return usage(),
+            };
+            match graph_modules(Path::new(uproject), &out_dir) {
+                Ok(()) => ExitCode::SUCCESS,
+                Err(message) => {
+                    eprintln!("error: {message}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        ["includes", compile_commands] => match includes(Path::new(compile_commands)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(message) => {
+                eprintln!("error: {message}");
+                ExitCode::FAILURE
+            }
+        },
+        _ => usage(),
+    }
+}
+
+fn usage() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::from(2)
+}
+
+/// Writes `modules.dot` and `modules.mmd` to `out_dir`, fails if modules depend on each other in
+/// a cycle
+fn graph_modules(uproject: &Path, out_dir: &Path) -> Result<(), String> {
+    let project = UnrealProject::load(uproject).map_err(|e| e.to_string())?;
+    for diagnostic in project
+        .modules
+        .iter()
+        .flat_map(|module| &module.rules_diagnostic)
+    {
+        eprintln!("warning: {diagnostic}");
+    }
+    let graph = ModuleGraph::from_project(&project);
+
+    std::fs::create_dir_all(out_dir).map_err(|e| format!("{}: {e}", out_dir.display()))?;
+    for (file, contents) in [
+        ("modules.dot", graph.to_dot()),
+        ("modules.mmd", graph.to_mermaid()),
+    ] {
+        let path = out_dir.join(file);
+        std::fs::write(&path, contents).map_err(|e| format!("{}: {e}", path.display()))?;
+        println!("wrote {}", path.display());
+    }
+
+    let cycles = graph.cycles();
+    for cycle in &cycles {
+        eprintln!("dependency cycle between {}", cycle.join(", "));
+    }
+
+    match cycles.len() {
+        0 => Ok(()),
+        1 => Err("found a dependency cycle".to_string()),
+        count => Err(format!("found {count} dependency cycles")),
+    }
+}
+
+/// Resolves the includes of every header the translation units of `compile_commands` reach, with
+/// the include directories and defines of their compile command, and reports the ones that fail
+fn includes(compile_commands: &Path) -> Result<(), String> {
+    let database = CompilationDatabase::load(compile_commands).map_err(|e| e.to_string())?;
+
+    let mut headers_by_command: BTreeMap<&Path, (&CompileCommand, Vec<PathBuf>)> = BTreeMap::new();
+    for (header, command) in database.headers() {
+        headers_by_command
+            .entry(&command.file)
+            .or_insert((command, vec![]))
+            .1
+            .push(header);
+    }
+
+    let mut count = 0;
+    for (command, headers) in headers_by_command.into_values() {
+        count += headers.len();
+        let graph = IncludeGraph::build(
+            headers,
+            &command.include_resolver(),
+            &command.preprocessor_config(),
+        );
+
+        print!("{}", graph.unresolved_report());
+        for diagnostic in graph.diagnostics() {
+            eprintln!("warning: {diagnostic}");
+        }
+        for cycle in graph.cycles() {
+            let files = cycle.iter().map(|file| file.display().to_string());
+            eprintln!(
+                "warning: include cycle between {}",
+                files.collect::<Vec<_>>().join(", ")
+            );
+        }
+    }
+    println!("checked {count} headers");
+
+    Ok(())
 }
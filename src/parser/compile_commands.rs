@@ -0,0 +1,311 @@
+use crate::parser::cpp::preprocessor::PreprocessorConfig;
+use crate::parser::include::{IncludeGraph, IncludeResolver, normalize};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// One entry of `compile_commands.json`, either `command` or `arguments` is set
+#[derive(Debug, Deserialize)]
+struct Entry {
+    directory: PathBuf,
+    file: PathBuf,
+    command: Option<String>,
+    arguments: Option<Vec<String>>,
+}
+
+/// Include directories and defines of one translation unit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileCommand {
+    pub file: PathBuf,
+    pub directory: PathBuf,
+    /// `-I`, `-isystem`, `-iquote` and `/I` in command line order, relative to `directory`
+    pub include_dirs: Vec<PathBuf>,
+    /// `-D` and `/D` as written, e.g. `WITH_EDITOR=1` or `UE_BUILD_SHIPPING`. `-U` and `/U`
+    /// remove earlier definitions of the name.
+    pub defines: Vec<String>,
+}
+
+impl CompileCommand {
+    fn from_arguments(directory: PathBuf, file: PathBuf, arguments: &[String]) -> CompileCommand {
+        let mut command = CompileCommand {
+            file: directory.join(file),
+            directory,
+            include_dirs: vec![],
+            defines: vec![],
+        };
+
+        let driver = arguments
+            .first()
+            .and_then(|driver| Path::new(driver).file_stem())
+            .map(|driver| driver.to_string_lossy().to_lowercase());
+        let msvc = matches!(driver.as_deref(), Some("cl" | "clang-cl"));
+
+        let mut arguments = arguments.iter().skip(1);
+        while let Some(argument) = arguments.next() {
+            // other drivers only take `/I` and `/D` if they aren't paths like `/Data/Player.cpp`
+            if argument.starts_with('/') && !msvc && command.directory.join(argument).exists() {
+                continue;
+            }
+            let mut value = |prefix: &str| match argument.strip_prefix(prefix)? {
+                "" => arguments.next().cloned(),
+                value => Some(value.to_string()),
+            };
+            let mut find = |prefixes: &[&str]| {
+                prefixes
+                    .iter()
+                    .find(|prefix| argument.starts_with(*prefix))
+                    .and_then(|prefix| value(prefix))
+            };
+
+            if let Some(dir) = find(&["-isystem", "-iquote", "-I", "/I"]) {
+                command.include_dirs.push(command.directory.join(dir));
+            } else if let Some(define) = find(&["-D", "/D"]) {
+                command.defines.push(define);
+            } else if let Some(name) = find(&["-U", "/U"]) {
+                command
+                    .defines
+                    .retain(|define| define.split('=').next() != Some(name.as_str()));
+            }
+        }
+
+        command
+    }
+
+    pub fn include_resolver(&self) -> IncludeResolver {
+        self.include_dirs
+            .iter()
+            .fold(IncludeResolver::default(), |resolver, dir| {
+                resolver.search_dir(dir)
+            })
+    }
+
+    pub fn preprocessor_config(&self) -> PreprocessorConfig {
+        PreprocessorConfig::from_definitions(self.defines.iter().map(String::as_str))
+    }
+}
+
+#[derive(Debug)]
+pub enum CompilationDatabaseError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for CompilationDatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompilationDatabaseError::Io(e) => write!(f, "cannot read compilation database: {e}"),
+            CompilationDatabaseError::Json(e) => write!(f, "invalid compilation database: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CompilationDatabaseError {}
+
+/// The contents of a `compile_commands.json`, see https://clang.llvm.org/docs/JSONCompilationDatabase.html
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CompilationDatabase {
+    commands: Vec<CompileCommand>,
+}
+
+impl CompilationDatabase {
+    pub fn load(path: &Path) -> Result<CompilationDatabase, CompilationDatabaseError> {
+        let json = std::fs::read_to_string(path).map_err(CompilationDatabaseError::Io)?;
+
+        CompilationDatabase::parse(&json)
+    }
+
+    /// Response files like `@Module.rsp`, which Unreal uses for the compiler arguments, are read
+    /// relative to the entry's `directory`
+    pub fn parse(json: &str) -> Result<CompilationDatabase, CompilationDatabaseError> {
+        let entries: Vec<Entry> =
+            serde_json::from_str(json).map_err(CompilationDatabaseError::Json)?;
+
+        let commands = entries
+            .into_iter()
+            .map(|entry| {
+                let arguments = match (entry.arguments, entry.command) {
+                    (Some(arguments), _) => arguments,
+                    (None, Some(command)) => split_command_line(&command),
+                    (None, None) => vec![],
+                };
+                let arguments = expand_response_files(&entry.directory, arguments);
+
+                CompileCommand::from_arguments(entry.directory, entry.file, &arguments)
+            })
+            .collect();
+
+        Ok(CompilationDatabase { commands })
+    }
+
+    pub fn commands(&self) -> &[CompileCommand] {
+        &self.commands
+    }
+
+    pub fn command_for(&self, file: &Path) -> Option<&CompileCommand> {
+        self.commands.iter().find(|command| command.file == file)
+    }
+
+    /// Every header a translation unit includes directly or indirectly, with the command of the
+    /// first translation unit that reaches it. Use its include directories and defines to resolve
+    /// and preprocess the header.
+    pub fn headers(&self) -> BTreeMap<PathBuf, &CompileCommand> {
+        let mut headers = BTreeMap::new();
+        for command in &self.commands {
+            let unit = normalize(&command.file);
+            let graph = IncludeGraph::build(
+                [unit.clone()],
+                &command.include_resolver(),
+                &command.preprocessor_config(),
+            );
+            for header in graph.files().filter(|file| *file != unit) {
+                headers.entry(header.to_path_buf()).or_insert(command);
+            }
+        }
+
+        headers
+    }
+}
+
+/// Splits at whitespace outside of double quotes. A backslash only escapes a quote or another
+/// backslash, so Windows paths survive.
+fn split_command_line(command: &str) -> Vec<String> {
+    let mut arguments = vec![];
+    let mut current: Option<String> = None;
+    let mut quoted = false;
+    let mut chars = command.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('"' | '\\')) => {
+                current
+                    .get_or_insert_default()
+                    .push(chars.next().unwrap_or(c));
+            }
+            '"' => {
+                quoted = !quoted;
+                current.get_or_insert_default();
+            }
+            _ if c.is_whitespace() && !quoted => arguments.extend(current.take()),
+            _ => current.get_or_insert_default().push(c),
+        }
+    }
+    arguments.extend(current);
+
+    arguments
+}
+
+fn expand_response_files(directory: &Path, arguments: Vec<String>) -> Vec<String> {
+    arguments
+        .into_iter()
+        .flat_map(|argument| {
+            let contents = argument
+                .strip_prefix('@')
+                .and_then(|file| std::fs::read_to_string(directory.join(file)).ok());
+            match contents {
+                Some(contents) => split_command_line(&contents),
+                None => vec![argument],
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::compile_commands::{CompilationDatabase, split_command_line};
+    use crate::parser::cpp::preprocessor::preprocess;
+    use crate::parser::temp_project::TempProject;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn command_lines() {
+        assert_eq!(
+            split_command_line(
+                r#"clang++ -I "C:\Program Files\Include" -DNAME=\"Game\" /DWIN32 "" x.cpp"#
+            ),
+            vec![
+                "clang++",
+                "-I",
+                r"C:\Program Files\Include",
+                "-DNAME=\"Game\"",
+                "/DWIN32",
+                "",
+                "x.cpp"
+            ]
+        );
+    }
+
+    #[test]
+    fn include_dirs_and_defines() {
+        let json = r#"[
+            {
+                "directory": "/work/Game",
+                "file": "Source/Game/Player.cpp",
+                "command": "clang++ -c -ISource/Game/Public -I /work/Engine/Core/Public -isystem ThirdParty -DWITH_EDITOR=1 -D UE_BUILD_DEBUG -DWITH_TESTS -UWITH_TESTS /DPLATFORM_LINUX -o Player.o Source/Game/Player.cpp"
+            },
+            {
+                "directory": "/work/Game",
+                "file": "/work/Game/Source/Game/Item.cpp",
+                "arguments": ["C:/MSVC/bin/CL.exe", "/ISource/Game/Classes", "/DWITH_EDITOR=0", "/DUE_BUILD_DEBUG", "/U", "UE_BUILD_DEBUG", "/c", "Source/Game/Item.cpp"]
+            }
+        ]"#;
+        let database = CompilationDatabase::parse(json).unwrap();
+
+        let player = database
+            .command_for(Path::new("/work/Game/Source/Game/Player.cpp"))
+            .unwrap();
+        assert_eq!(
+            player.include_dirs,
+            vec![
+                PathBuf::from("/work/Game/Source/Game/Public"),
+                PathBuf::from("/work/Engine/Core/Public"),
+                PathBuf::from("/work/Game/ThirdParty"),
+            ]
+        );
+        assert_eq!(
+            player.defines,
+            vec!["WITH_EDITOR=1", "UE_BUILD_DEBUG", "PLATFORM_LINUX"]
+        );
+
+        let item = &database.commands()[1];
+        assert_eq!(
+            item.include_dirs,
+            vec![PathBuf::from("/work/Game/Source/Game/Classes")]
+        );
+        assert_eq!(item.defines, vec!["WITH_EDITOR=0"]);
+        let source = "#if WITH_EDITOR\nint32 EditorOnly;\n#endif";
+        let preprocessed = preprocess(source, &item.preprocessor_config());
+        assert!(!preprocessed.text.contains("EditorOnly"));
+
+        assert!(CompilationDatabase::parse("{}").is_err());
+    }
+
+    #[test]
+    fn headers_of_translation_units() {
+        let root = TempProject::new(
+            "compdb",
+            &[
+                ("Private/Player.cpp", "#include \"Player.h\""),
+                ("Public/Player.h", "#include \"Inventory.h\""),
+                ("Public/Inventory.h", ""),
+                ("Player.rsp", "-IPublic\n-DWITH_INVENTORY=1"),
+            ],
+        );
+
+        let json = format!(
+            r#"[{{"directory": {:?}, "file": "Private/Player.cpp", "command": "clang++ @Player.rsp Private/Player.cpp"}}]"#,
+            root.display().to_string()
+        );
+        let database = CompilationDatabase::parse(&json).unwrap();
+        let headers = database.headers();
+
+        assert_eq!(
+            headers.keys().collect::<Vec<_>>(),
+            vec![
+                &root.join("Public/Inventory.h"),
+                &root.join("Public/Player.h")
+            ]
+        );
+        let command = headers[&root.join("Public/Player.h")];
+        assert_eq!(command.defines, vec!["WITH_INVENTORY=1"]);
+    }
+}
@@ -1,38 +1,215 @@
-﻿use crate::parser::cpp::ctype::{CType, parse_cpp_type};
+﻿use crate::parser::cpp::class::CppClass;
+use crate::parser::cpp::ctype::{CType, parse_cpp_type};
+use crate::parser::cpp::method::parse_method_params;
 use crate::parser::cpp::template::parse_template;
-use crate::parser::{parse_str, ws};
+use crate::parser::generic::class::parse_class;
+use crate::parser::generic::ignore::IgnoreRules;
+use crate::parser::generic::member::{
+    array_extents, array_type, declarator_indirections, indirect_type, strip_indirections,
+};
+use crate::parser::{keyword, parse_str, ws};
 use crate::types::Parsable;
 use nom::IResult;
 use nom::Parser;
+use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{char, multispace0, multispace1};
-use nom::combinator::opt;
-use nom::sequence::preceded;
+use nom::combinator::{map, map_opt, opt, peek};
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, preceded, terminated};
 use nom_language::error::VerboseError;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum AliasKind {
+    /// `using Name = Type;`
+    #[default]
+    Using,
+    /// `typedef Type Name;`
+    Typedef,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct CppAlias<'a> {
-    name: &'a str,
-    ctype: CType<'a>,
+    pub name: &'a str,
+    pub ctype: CType<'a>,
+    pub kind: AliasKind,
+    /// Struct, class or union that is defined inline by a typedef like `typedef struct Foo {...} Foo;`
+    pub class: Option<CppClass<'a>>,
 }
 
 impl<'a> Parsable<'a> for CppAlias<'a> {
     fn parse(input: &'a str) -> IResult<&'a str, Self, VerboseError<&'a str>> {
-        let (input, _) = opt(parse_template).parse(input)?;
-        let (input, _) = tag("using")(input)?;
-        let (input, _) = multispace1(input)?;
-        let (input, name) = parse_str(input)?;
-        let (input, _) = ws(char('=')).parse(input)?;
-        let (input, ctype) = parse_cpp_type(input)?;
-        let (input, _) = preceded(multispace0, char(';')).parse(input)?;
-
-        Ok((input, CppAlias { name, ctype }))
+        map_opt(
+            |i| parse_aliases(i, &IgnoreRules::default()),
+            |mut aliases| match aliases.len() {
+                1 => aliases.pop(),
+                _ => None,
+            },
+        )
+        .parse(input)
     }
 }
 
+/// Parses a `using` alias or a typedef that may declare several names at once, e.g.
+/// `typedef int Int, *IntPtr;`. Every declarator becomes its own alias. Statements matching
+/// `ignore_rules` are skipped in the body of a struct defined by a typedef.
+pub fn parse_aliases<'a>(
+    input: &'a str,
+    ignore_rules: &IgnoreRules,
+) -> IResult<&'a str, Vec<CppAlias<'a>>, VerboseError<&'a str>> {
+    alt((map(parse_using_alias, |alias| vec![alias]), |i| {
+        parse_typedef(i, ignore_rules)
+    }))
+    .parse(input)
+}
+
+fn parse_using_alias(input: &str) -> IResult<&str, CppAlias<'_>, VerboseError<&str>> {
+    let (input, _) = opt(parse_template).parse(input)?;
+    let (input, _) = tag("using")(input)?;
+    let (input, _) = multispace1(input)?;
+    let (input, name) = parse_str(input)?;
+    let (input, _) = ws(char('=')).parse(input)?;
+    let (input, ctype) = parse_cpp_type(input)?;
+    let (input, _) = preceded(multispace0, char(';')).parse(input)?;
+
+    Ok((
+        input,
+        CppAlias {
+            name,
+            ctype,
+            kind: AliasKind::Using,
+            class: None,
+        },
+    ))
+}
+
+fn parse_typedef<'a>(
+    input: &'a str,
+    ignore_rules: &IgnoreRules,
+) -> IResult<&'a str, Vec<CppAlias<'a>>, VerboseError<&'a str>> {
+    let (input, _) = keyword("typedef").parse(input)?;
+    let (input, _) = multispace1(input)?;
+    let (input, aliases) = alt((
+        |i| typedef_inline_class(i, ignore_rules),
+        map(typedef_function_pointer, |alias| vec![alias]),
+        typedef_type,
+    ))
+    .parse(input)?;
+    let (input, _) = preceded(multispace0, char(';')).parse(input)?;
+
+    Ok((input, aliases))
+}
+
+/// `typedef struct Foo { ... } Foo, *FooPtr;`
+fn typedef_inline_class<'a>(
+    input: &'a str,
+    ignore_rules: &IgnoreRules,
+) -> IResult<&'a str, Vec<CppAlias<'a>>, VerboseError<&'a str>> {
+    let (input, _) = peek((
+        alt((keyword("struct"), keyword("class"), keyword("union"))),
+        multispace0,
+        opt(parse_str),
+        multispace0,
+        char('{'),
+    ))
+    .parse(input)?;
+    let (input, class) = parse_class::<CppClass>(input, ignore_rules)?;
+    let (input, declarators) = typedef_declarators(input)?;
+
+    // An anonymous struct is named by its first typedef, e.g. `typedef struct { ... } Foo;`
+    let class_name = match class.name {
+        "" => declarators[0].1,
+        class_name => class_name,
+    };
+
+    Ok((
+        input,
+        typedef_aliases(CType::Path(vec![class_name]), declarators, Some(class)),
+    ))
+}
+
+/// `typedef void (*Callback)(int);`
+fn typedef_function_pointer(input: &str) -> IResult<&str, CppAlias<'_>, VerboseError<&str>> {
+    let (input, return_type) = parse_cpp_type(input)?;
+    let (input, name) = delimited(
+        (multispace0, char('('), multispace0, char('*'), multispace0),
+        parse_str,
+        (multispace0, char(')'), multispace0),
+    )
+    .parse(input)?;
+    let (input, params) = parse_method_params(input)?;
+
+    let params = params.into_iter().map(|param| param.ctype).collect();
+
+    Ok((
+        input,
+        CppAlias {
+            name,
+            ctype: CType::Pointer(Box::new(CType::Function(Box::new(return_type), params))),
+            kind: AliasKind::Typedef,
+            class: None,
+        },
+    ))
+}
+
+/// `typedef unsigned int uint;`, `typedef struct Foo Foo;` or `typedef int Int, *IntPtr;`
+fn typedef_type(input: &str) -> IResult<&str, Vec<CppAlias<'_>>, VerboseError<&str>> {
+    // elaborated type specifier as used by C headers
+    let (input, _) = opt(terminated(
+        alt((
+            keyword("struct"),
+            keyword("class"),
+            keyword("union"),
+            keyword("enum"),
+        )),
+        multispace1,
+    ))
+    .parse(input)?;
+    let (input, ctype) = parse_cpp_type(input)?;
+
+    // `*` and `&` belong to the first declarator, `typedef int *IntPtr, Int;` declares a plain `Int`
+    let (ctype, indirections) = strip_indirections(ctype);
+    let (input, mut declarators) = typedef_declarators(input)?;
+    declarators[0].0.splice(0..0, indirections);
+
+    Ok((input, typedef_aliases(ctype, declarators, None)))
+}
+
+type TypedefDeclarator<'a> = (Vec<char>, &'a str, Vec<Option<&'a str>>);
+
+/// `Foo, *FooPtr, Table[4]` after the type of a typedef
+fn typedef_declarators(
+    input: &str,
+) -> IResult<&str, Vec<TypedefDeclarator<'_>>, VerboseError<&str>> {
+    separated_list1(
+        char(','),
+        (declarator_indirections, ws(parse_str), array_extents),
+    )
+    .parse(input)
+}
+
+fn typedef_aliases<'a>(
+    ctype: CType<'a>,
+    declarators: Vec<TypedefDeclarator<'a>>,
+    class: Option<CppClass<'a>>,
+) -> Vec<CppAlias<'a>> {
+    declarators
+        .into_iter()
+        .map(|(indirections, name, extents)| CppAlias {
+            name,
+            ctype: array_type(indirect_type(ctype.clone(), indirections), extents),
+            kind: AliasKind::Typedef,
+            class: class.clone(),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::cpp::member::CppMember;
+    use crate::parser::generic::class::{ClassKind, InheritanceVisibility};
+    use std::collections::HashMap;
 
     #[test]
     fn parse_alias_with_template() {
@@ -54,7 +231,8 @@ mod tests {
                                 vec![CType::Path(vec!["T"])]
                             )
                         ]
-                    )
+                    ),
+                    ..Default::default()
                 }
             ))
         )
@@ -71,8 +249,145 @@ mod tests {
                 CppAlias {
                     name: "myNumber",
                     ctype: CType::Path(vec!["path", "subpath", "value"]),
+                    ..Default::default()
                 }
             ))
         )
     }
+
+    #[test]
+    fn parse_typedef_fundamental_type() {
+        let input = "typedef unsigned int uint;";
+        let result = CppAlias::parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppAlias {
+                    name: "uint",
+                    ctype: CType::Path(vec!["unsigned int"]),
+                    kind: AliasKind::Typedef,
+                    class: None,
+                }
+            ))
+        )
+    }
+
+    #[test]
+    fn parse_typedef_function_pointer() {
+        let input = "typedef void (*Callback)(int, const char*);";
+        let result = CppAlias::parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppAlias {
+                    name: "Callback",
+                    ctype: CType::Pointer(Box::new(CType::Function(
+                        Box::new(CType::Path(vec!["void"])),
+                        vec![
+                            CType::Path(vec!["int"]),
+                            CType::Pointer(Box::new(CType::Const(Box::new(CType::Path(
+                                vec!["char"]
+                            )))))
+                        ]
+                    ))),
+                    kind: AliasKind::Typedef,
+                    class: None,
+                }
+            ))
+        )
+    }
+
+    #[test]
+    fn parse_typedef_struct() {
+        let input = r#"typedef struct Foo {
+            int X;
+        } Foo;"#;
+        let result = CppAlias::parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppAlias {
+                    name: "Foo",
+                    ctype: CType::Path(vec!["Foo"]),
+                    kind: AliasKind::Typedef,
+                    class: Some(CppClass {
+                        name: "Foo",
+                        kind: ClassKind::Struct,
+                        members: HashMap::from([(
+                            InheritanceVisibility::Public,
+                            vec![CppMember {
+                                name: "X",
+                                ctype: CType::Path(vec!["int"]),
+                                ..Default::default()
+                            }]
+                        )]),
+                        ..Default::default()
+                    }),
+                }
+            ))
+        )
+    }
+
+    #[test]
+    fn parse_typedef_elaborated_type() {
+        let input = "typedef struct Foo Foo;";
+        let result = CppAlias::parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppAlias {
+                    name: "Foo",
+                    ctype: CType::Path(vec!["Foo"]),
+                    kind: AliasKind::Typedef,
+                    class: None,
+                }
+            ))
+        )
+    }
+
+    #[test]
+    fn parse_typedef_multiple_declarators() {
+        let input = "typedef int *IntPtr, Int, Table[4];";
+        let (rest, aliases) = parse_aliases(input, &IgnoreRules::default()).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(
+            aliases
+                .iter()
+                .map(|alias| (alias.name, alias.ctype.to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("IntPtr", "int*".to_string()),
+                ("Int", "int".to_string()),
+                ("Table", "int[4]".to_string())
+            ]
+        );
+        assert!(CppAlias::parse(input).is_err());
+
+        let (_, aliases) = parse_aliases(
+            "typedef struct { int X; } Point, *PointPtr;",
+            &IgnoreRules::default(),
+        )
+        .unwrap();
+        assert_eq!(aliases[0].ctype, CType::Path(vec!["Point"]));
+        assert_eq!(
+            aliases[1].ctype,
+            CType::Pointer(Box::new(CType::Path(vec!["Point"])))
+        );
+        assert!(aliases.iter().all(|alias| alias.class.is_some()));
+    }
+
+    #[test]
+    fn parse_typedef_struct_with_ignore_rules() {
+        let input = "typedef struct FPoint { GENERATED_BODY() int X; } FPoint;";
+        assert!(parse_aliases(input, &IgnoreRules::default()).is_err());
+
+        let (rest, aliases) = parse_aliases(input, &IgnoreRules::unreal()).unwrap();
+        assert_eq!(rest, "");
+        let class = aliases[0].class.as_ref().unwrap();
+        assert_eq!(class.members[&InheritanceVisibility::Public][0].name, "X");
+    }
 }
@@ -0,0 +1,239 @@
+﻿use crate::parser::keyword;
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::{char, multispace0, multispace1};
+use nom::combinator::{map, opt, recognize};
+use nom::error::{ErrorKind, ParseError};
+use nom::multi::{many0, separated_list0, separated_list1};
+use nom::sequence::{delimited, preceded, terminated};
+use nom::{IResult, Parser};
+use nom_language::error::VerboseError;
+
+/// Attribute like `[[nodiscard]]`, `[[deprecated("Use Bar")]]` or `alignas(16)`. The vendor
+/// forms `__declspec(deprecated)` and `__attribute__((deprecated))` are stored the same way.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct CppAttribute<'a> {
+    /// Name including its namespace, e.g. `gnu::always_inline`. `alignas` is stored as an
+    /// attribute named `alignas`.
+    pub name: &'a str,
+    /// Argument clause without the outer parentheses, as written in the source
+    pub arguments: Option<&'a str>,
+}
+
+impl<'a> CppAttribute<'a> {
+    /// `deprecated` in any namespace, e.g. `[[gnu::deprecated]]`
+    pub fn is_deprecated(&self) -> bool {
+        matches!(
+            self.name.rsplit("::").next(),
+            Some("deprecated" | "__deprecated__")
+        )
+    }
+
+    /// The reason given in `[[deprecated("...")]]` without quotes
+    pub fn deprecation_message(&self) -> Option<&'a str> {
+        match self.is_deprecated() {
+            true => self
+                .arguments
+                .map(|arguments| arguments.trim().trim_matches('"'))
+                .filter(|message| !message.is_empty()),
+            false => None,
+        }
+    }
+}
+
+/// Entities that can carry attributes
+pub trait Attributed<'a> {
+    fn attributes(&self) -> &[CppAttribute<'a>];
+
+    fn is_deprecated(&self) -> bool {
+        self.attributes().iter().any(CppAttribute::is_deprecated)
+    }
+
+    fn deprecation_message(&self) -> Option<&'a str> {
+        self.attributes()
+            .iter()
+            .find_map(CppAttribute::deprecation_message)
+    }
+}
+
+/// Parses an attribute-specifier-sequence, i.e. any number of `[[...]]`, `alignas(...)`,
+/// `__declspec(...)` and `__attribute__((...))` specifiers including trailing whitespace.
+/// Succeeds with an empty list if there is none.
+pub fn parse_attributes(input: &str) -> IResult<&str, Vec<CppAttribute<'_>>, VerboseError<&str>> {
+    map(
+        many0(terminated(
+            alt((
+                attribute_list,
+                map(alignas, |alignas| vec![alignas]),
+                declspec,
+                gnu_attribute_list,
+            )),
+            multispace0,
+        )),
+        |lists| lists.into_iter().flatten().collect(),
+    )
+    .parse(input)
+}
+
+/// `[[using gnu: always_inline, hot]]` or `[[nodiscard, deprecated("Use Bar")]]`
+fn attribute_list(input: &str) -> IResult<&str, Vec<CppAttribute<'_>>, VerboseError<&str>> {
+    let (input, _) = (tag("[["), multispace0).parse(input)?;
+    let (input, _) = opt((
+        keyword("using"),
+        multispace1,
+        attribute_name,
+        multispace0,
+        char(':'),
+    ))
+    .parse(input)?;
+    let (input, attributes) =
+        separated_list0(char(','), delimited(multispace0, attribute, multispace0)).parse(input)?;
+    let (input, _) = tag("]]")(input)?;
+
+    Ok((input, attributes))
+}
+
+/// `__declspec(dllexport deprecated("Use Bar"))`, several attributes are separated by spaces
+fn declspec(input: &str) -> IResult<&str, Vec<CppAttribute<'_>>, VerboseError<&str>> {
+    delimited(
+        (keyword("__declspec"), multispace0, char('('), multispace0),
+        many0(terminated(attribute, multispace0)),
+        char(')'),
+    )
+    .parse(input)
+}
+
+/// `__attribute__((deprecated("Use Bar"), aligned(16)))`
+fn gnu_attribute_list(input: &str) -> IResult<&str, Vec<CppAttribute<'_>>, VerboseError<&str>> {
+    delimited(
+        (keyword("__attribute__"), multispace0, tag("(("), multispace0),
+        separated_list0(char(','), delimited(multispace0, attribute, multispace0)),
+        tag("))"),
+    )
+    .parse(input)
+}
+
+fn attribute(input: &str) -> IResult<&str, CppAttribute<'_>, VerboseError<&str>> {
+    let (input, name) = attribute_name(input)?;
+    let (input, arguments) = opt(preceded(multispace0, parenthesized)).parse(input)?;
+
+    Ok((input, CppAttribute { name, arguments }))
+}
+
+fn attribute_name(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    recognize(separated_list1(
+        tag("::"),
+        take_while1(|c: char| c.is_alphanumeric() || c == '_'),
+    ))
+    .parse(input)
+}
+
+fn alignas(input: &str) -> IResult<&str, CppAttribute<'_>, VerboseError<&str>> {
+    let (input, name) = keyword("alignas").parse(input)?;
+    let (input, arguments) = preceded(multispace0, parenthesized).parse(input)?;
+
+    Ok((
+        input,
+        CppAttribute {
+            name,
+            arguments: Some(arguments),
+        },
+    ))
+}
+
+/// Content of a balanced pair of parentheses. Parentheses inside string and character literals
+/// are ignored.
+pub(crate) fn parenthesized(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    let (rest, _) = char('(')(input)?;
+
+    let mut depth = 1;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in rest.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok((&rest[i + 1..], rest[..i].trim()));
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+
+    Err(nom::Err::Error(VerboseError::from_error_kind(
+        input,
+        ErrorKind::Char,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::cpp::attribute::{CppAttribute, parse_attributes};
+
+    #[test]
+    fn no_attributes() {
+        assert_eq!(parse_attributes("int X"), Ok(("int X", vec![])));
+    }
+
+    #[test]
+    fn attribute_list_with_arguments() {
+        let input = r#"[[nodiscard, deprecated("Use Bar() instead")]] alignas(16) int"#;
+        let (input, attributes) = parse_attributes(input).unwrap();
+
+        assert_eq!(input, "int");
+        assert_eq!(
+            attributes,
+            vec![
+                CppAttribute {
+                    name: "nodiscard",
+                    arguments: None
+                },
+                CppAttribute {
+                    name: "deprecated",
+                    arguments: Some(r#""Use Bar() instead""#)
+                },
+                CppAttribute {
+                    name: "alignas",
+                    arguments: Some("16")
+                },
+            ]
+        );
+        assert_eq!(attributes[1].deprecation_message(), Some("Use Bar() instead"));
+    }
+
+    #[test]
+    fn vendor_deprecations() {
+        for input in [
+            "[[gnu::deprecated]]",
+            r#"__declspec(dllexport deprecated("Use Bar"))"#,
+            r#"__attribute__((deprecated("Use Bar"), aligned(16)))"#,
+        ] {
+            let (rest, attributes) = parse_attributes(input).unwrap();
+
+            assert_eq!(rest, "");
+            assert!(attributes.iter().any(CppAttribute::is_deprecated), "{input}");
+        }
+
+        let (_, attributes) = parse_attributes(r#"__declspec(deprecated("Use Bar"))"#).unwrap();
+        assert_eq!(attributes[0].deprecation_message(), Some("Use Bar"));
+    }
+
+    #[test]
+    fn attribute_using_prefix() {
+        let (_, attributes) = parse_attributes("[[using gnu: hot, always_inline]]").unwrap();
+
+        assert_eq!(
+            attributes.iter().map(|a| a.name).collect::<Vec<_>>(),
+            vec!["hot", "always_inline"]
+        );
+    }
+}
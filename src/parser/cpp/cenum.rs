@@ -1,26 +1,114 @@
 ﻿use nom::branch::alt;
+use nom::error::ParseError;
 use nom::{
     IResult, Parser,
     bytes::complete::{tag, take_while, take_while1},
-    character::complete::{char, digit1, multispace0, multispace1},
-    combinator::{map_res, opt, recognize},
-    multi::separated_list0,
+    character::complete::{char, digit1, multispace0, multispace1, none_of, one_of, space0},
+    combinator::{consumed, eof, map, map_res, opt, peek, recognize},
+    multi::many0,
     sequence::{delimited, pair, preceded, terminated},
 };
 use nom_language::error::VerboseError;
+use std::collections::HashMap;
+use crate::parser::cpp::attribute::{Attributed, CppAttribute, parse_attributes};
+use crate::parser::cpp::comment::CppComment;
 use crate::parser::cpp::ctype::{parse_cpp_type, CType};
+use crate::parser::generic::comment::parse_comment;
+use crate::parser::keyword;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct CppEnum<'a> {
     pub name: Option<String>,
-    pub variants: Vec<EnumVariant>,
+    /// The `enum` keyword as a slice of the parsed text, locates unnamed enums too
+    pub keyword: &'a str,
+    pub variants: Vec<EnumVariant<'a>>,
     pub ctype: Option<CType<'a>>,
+    /// `enum class` and `enum struct` introduce a scoped enumeration
+    pub scoped: bool,
+    pub comment: Option<CppComment>,
+    pub attributes: Vec<CppAttribute<'a>>,
 }
 
-#[derive(Debug, PartialEq)]
-pub struct EnumVariant {
+impl<'a> Attributed<'a> for CppEnum<'a> {
+    fn attributes(&self) -> &[CppAttribute<'a>] {
+        &self.attributes
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct EnumVariant<'a> {
     pub name: String,
+    /// Initializer as written in the source, e.g. `1 << 3` or `A | B`
+    pub expression: Option<String>,
+    /// Evaluated value, `None` if the initializer refers to something outside the enum
     pub value: Option<i64>,
+    /// Attributes of a single enumerator like `Old [[deprecated]] = 1`
+    pub attributes: Vec<CppAttribute<'a>>,
+    /// Comment before the enumerator, or after it on the same line like `A, // Doc`
+    pub comment: Option<CppComment>,
+}
+
+impl<'a> Attributed<'a> for EnumVariant<'a> {
+    fn attributes(&self) -> &[CppAttribute<'a>] {
+        &self.attributes
+    }
+}
+
+/// Constant expression of an enumerator initializer
+#[derive(Debug, PartialEq, Clone)]
+enum ConstExpr<'a> {
+    Literal(i64),
+    /// Enumerator as written, optionally qualified like `EFlags::A`
+    Name(&'a str),
+    Unary(char, Box<ConstExpr<'a>>),
+    Binary(&'static str, Box<ConstExpr<'a>>, Box<ConstExpr<'a>>),
+}
+
+impl ConstExpr<'_> {
+    /// Evaluates the expression inside the enum `scope`, names qualified by any other scope like
+    /// `EOther::A` are unknown
+    fn evaluate(&self, known: &HashMap<String, i64>, scope: Option<&str>) -> Option<i64> {
+        match self {
+            ConstExpr::Literal(value) => Some(*value),
+            ConstExpr::Name(name) => {
+                let name = match name.rsplit_once("::") {
+                    Some((qualifier, name)) => {
+                        let qualifier = qualifier.rsplit("::").next();
+                        (qualifier == scope).then_some(name)?
+                    }
+                    None => name,
+                };
+                known.get(name).copied()
+            }
+            ConstExpr::Unary(op, expr) => {
+                let value = expr.evaluate(known, scope)?;
+                match op {
+                    '-' => value.checked_neg(),
+                    '+' => Some(value),
+                    '~' => Some(!value),
+                    '!' => Some((value == 0) as i64),
+                    _ => None,
+                }
+            }
+            ConstExpr::Binary(op, lhs, rhs) => {
+                let lhs = lhs.evaluate(known, scope)?;
+                let rhs = rhs.evaluate(known, scope)?;
+                match *op {
+                    "*" => lhs.checked_mul(rhs),
+                    "/" => lhs.checked_div(rhs),
+                    "%" => lhs.checked_rem(rhs),
+                    "+" => lhs.checked_add(rhs),
+                    "-" => lhs.checked_sub(rhs),
+                    "<<" => lhs.checked_shl(u32::try_from(rhs).ok()?),
+                    ">>" => lhs.checked_shr(u32::try_from(rhs).ok()?),
+                    "&" => Some(lhs & rhs),
+                    "^" => Some(lhs ^ rhs),
+                    "|" => Some(lhs | rhs),
+                    _ => None,
+                }
+            }
+        }
+    }
 }
 
 // Parse C++ identifier: start with alpha or '_', continue alphanumeric or '_'
@@ -31,59 +119,292 @@ fn identifier(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
     recognize(pair(take_while1(first_char), take_while(other_char))).parse(input)
 }
 
-// Parse optional integer literal (decimal only for simplicity)
+// Converts the spelling of an integer literal (hex, octal, binary, decimal with digit
+// separators and suffixes) into its value
+pub(crate) fn integer_value(literal: &str) -> Result<i64, std::num::ParseIntError> {
+    let digits: String = literal
+        .trim_end_matches(['u', 'U', 'l', 'L'])
+        .chars()
+        .filter(|c| *c != '\'')
+        .collect();
+
+    let (digits, radix) = if let Some(hex) = digits.strip_prefix("0x").or(digits.strip_prefix("0X")) {
+        (hex, 16)
+    } else if let Some(bin) = digits.strip_prefix("0b").or(digits.strip_prefix("0B")) {
+        (bin, 2)
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        (&digits[1..], 8)
+    } else {
+        (digits.as_str(), 10)
+    };
+
+    u64::from_str_radix(digits, radix).map(|value| value as i64)
+}
+
+// Parse integer literal like `42`, `-45`, `0x10`, `0b1010`, `017` or `1'000'000ULL`
 fn int_literal(input: &str) -> IResult<&str, i64, VerboseError<&str>> {
-    map_res(recognize(pair(opt(char('-')), digit1)), |s: &str| {
-        s.parse::<i64>()
-    })
+    let (input, negative) = opt(char('-')).parse(input)?;
+    let (input, value) = map_res(
+        recognize(pair(
+            digit1,
+            take_while(|c: char| c.is_ascii_alphanumeric() || c == '\''),
+        )),
+        integer_value,
+    )
+    .parse(input)?;
+
+    // `-0x8000000000000000` is `i64::MIN`
+    Ok((input, if negative.is_some() { value.wrapping_neg() } else { value }))
+}
+
+// Parse character literal like `'A'`
+fn char_literal(input: &str) -> IResult<&str, i64, VerboseError<&str>> {
+    map(
+        delimited(char('\''), none_of("\\'"), char('\'')),
+        |c| c as i64,
+    )
+    .parse(input)
+}
+
+// Reference to another enumerator, either unqualified or qualified like `EFlags::A`
+fn enumerator_reference(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    recognize(pair(identifier, many0(preceded(tag("::"), identifier)))).parse(input)
+}
+
+fn primary_expr(input: &str) -> IResult<&str, ConstExpr<'_>, VerboseError<&str>> {
+    preceded(
+        multispace0,
+        alt((
+            map(int_literal, ConstExpr::Literal),
+            map(char_literal, ConstExpr::Literal),
+            map(enumerator_reference, ConstExpr::Name),
+            delimited(char('('), const_expr, preceded(multispace0, char(')'))),
+            map(
+                (one_of("-+~!"), primary_expr),
+                |(op, expr)| ConstExpr::Unary(op, Box::new(expr)),
+            ),
+        )),
+    )
+    .parse(input)
+}
+
+// Parses a left associative chain of `operand (op operand)*` for one precedence level
+fn binary_expr<'a>(
+    input: &'a str,
+    operators: &[&'static str],
+    operand: fn(&'a str) -> IResult<&'a str, ConstExpr<'a>, VerboseError<&'a str>>,
+) -> IResult<&'a str, ConstExpr<'a>, VerboseError<&'a str>> {
+    let (mut input, mut lhs) = operand(input)?;
+
+    loop {
+        let rest = input.trim_start();
+        // `&` and `|` must not match the logical operators `&&` and `||`
+        let operator = operators.iter().find(|op| {
+            rest.starts_with(**op) && !(op.len() == 1 && rest[1..].starts_with(**op))
+        });
+
+        let Some(operator) = operator else {
+            return Ok((input, lhs));
+        };
+
+        let (next, rhs) = operand(&rest[operator.len()..])?;
+        lhs = ConstExpr::Binary(operator, Box::new(lhs), Box::new(rhs));
+        input = next;
+    }
+}
+
+fn multiplicative_expr(input: &str) -> IResult<&str, ConstExpr<'_>, VerboseError<&str>> {
+    binary_expr(input, &["*", "/", "%"], primary_expr)
+}
+
+fn additive_expr(input: &str) -> IResult<&str, ConstExpr<'_>, VerboseError<&str>> {
+    binary_expr(input, &["+", "-"], multiplicative_expr)
+}
+
+fn shift_expr(input: &str) -> IResult<&str, ConstExpr<'_>, VerboseError<&str>> {
+    binary_expr(input, &["<<", ">>"], additive_expr)
+}
+
+fn bitand_expr(input: &str) -> IResult<&str, ConstExpr<'_>, VerboseError<&str>> {
+    binary_expr(input, &["&"], shift_expr)
+}
+
+fn bitxor_expr(input: &str) -> IResult<&str, ConstExpr<'_>, VerboseError<&str>> {
+    binary_expr(input, &["^"], bitand_expr)
+}
+
+fn const_expr(input: &str) -> IResult<&str, ConstExpr<'_>, VerboseError<&str>> {
+    binary_expr(input, &["|"], bitxor_expr)
+}
+
+// Any other initializer up to the next `,` or `}` outside of parentheses, e.g. `sizeof(int)`
+fn raw_expression(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    let mut depth = 0usize;
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            ',' | '}' | ')' if depth == 0 => {
+                return match i {
+                    0 => Err(nom::Err::Error(VerboseError::from_error_kind(
+                        input,
+                        nom::error::ErrorKind::TakeUntil,
+                    ))),
+                    _ => Ok((&input[i..], &input[..i])),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    match input.trim().is_empty() {
+        true => Err(nom::Err::Error(VerboseError::from_error_kind(
+            input,
+            nom::error::ErrorKind::TakeUntil,
+        ))),
+        false => Ok(("", input)),
+    }
+}
+
+// Parse an enumerator initializer, returns the source text and the expression if it could be parsed
+fn enum_initializer(
+    input: &str,
+) -> IResult<&str, (&str, Option<ConstExpr<'_>>), VerboseError<&str>> {
+    alt((
+        map(
+            terminated(
+                consumed(const_expr),
+                peek(preceded(
+                    multispace0,
+                    alt((recognize(one_of(",}")), tag("//"), tag("/*"), eof)),
+                )),
+            ),
+            |(text, expr)| (text, Some(expr)),
+        ),
+        map(raw_expression, |text| (text, None)),
+    ))
     .parse(input)
 }
 
-// Parse one enum variant: identifier [= int_literal]
-fn enum_variant(input: &str) -> IResult<&str, EnumVariant, VerboseError<&str>> {
+// Parse one enum variant: identifier [= expression], the parsed initializer is kept for
+// `evaluate_variants` which computes the value
+fn enum_variant(
+    input: &str,
+) -> IResult<&str, (EnumVariant<'_>, Option<ConstExpr<'_>>), VerboseError<&str>> {
     let (input, name) = identifier(input)?;
-    let (input, value) = opt(preceded(
+    let (input, attributes) = preceded(multispace0, parse_attributes).parse(input)?;
+    let (input, initializer) = opt(preceded(
         delimited(multispace0, char('='), multispace0),
-        int_literal,
+        enum_initializer,
     ))
     .parse(input)?;
 
+    let (expression, expr) = match initializer {
+        Some((text, expr)) => (Some(text.trim().to_string()), expr),
+        None => (None, None),
+    };
+
     Ok((
         input,
-        EnumVariant {
-            name: name.to_string(),
-            value,
-        },
+        (
+            EnumVariant {
+                name: name.to_string(),
+                expression,
+                value: None,
+                attributes,
+                comment: None,
+            },
+            expr,
+        ),
     ))
 }
 
-// Parse comma separated list of variants (allow trailing comma)
-fn enum_variants(input: &str) -> IResult<&str, Vec<EnumVariant>, VerboseError<&str>> {
-    let (input, variants) =
-        separated_list0(delimited(multispace0, char(','), multispace0), enum_variant)
-            .parse(input)?;
+// Evaluates every variant of the enum `scope` in declaration order. Initializers may refer to
+// previous variants, variants without initializer continue counting from their predecessor.
+fn evaluate_variants<'a>(
+    variants: Vec<(EnumVariant<'a>, Option<ConstExpr<'a>>)>,
+    scope: Option<&str>,
+) -> Vec<EnumVariant<'a>> {
+    let mut known: HashMap<String, i64> = HashMap::new();
+    let mut next = Some(0);
+    let mut evaluated = Vec::with_capacity(variants.len());
+
+    for (mut variant, expr) in variants {
+        variant.value = match (&variant.expression, expr) {
+            (Some(_), Some(expr)) => expr.evaluate(&known, scope),
+            (Some(_), None) => None,
+            (None, _) => next,
+        };
+
+        if let Some(value) = variant.value {
+            known.insert(variant.name.clone(), value);
+        }
+        next = variant.value.and_then(|value| value.checked_add(1));
+        evaluated.push(variant);
+    }
+
+    evaluated
+}
 
-    let (input, _) = opt(char(',')).parse(input)?; // optional trailing comma
+// Comments separated by whitespace, joined like consecutive line comments
+fn comments(input: &str) -> IResult<&str, Option<CppComment>, VerboseError<&str>> {
+    let (input, comments) = many0(preceded(multispace0, parse_comment::<String>)).parse(input)?;
+    let comment = (!comments.is_empty()).then(|| CppComment::from(comments.join("\n")));
 
-    Ok((input, variants))
+    Ok((input, comment))
 }
 
-// Parse the full enum
-pub fn cpp_enum(input: &str) -> IResult<&str, CppEnum, VerboseError<&str>> {
-    let (input, _) = (tag("enum"), multispace1).parse(input)?;
-    let (input, _) = opt(delimited(
+// Parse comma separated list of variants of the enum `scope` (allow trailing comma). Comments
+// before a variant document it, a comment after it on the same line does if there is none before.
+fn enum_variants<'a>(
+    input: &'a str,
+    scope: Option<&str>,
+) -> IResult<&'a str, Vec<EnumVariant<'a>>, VerboseError<&'a str>> {
+    let mut variants = vec![];
+    let mut input = input;
+
+    loop {
+        let (rest, comment) = comments(input)?;
+        let Ok((rest, (mut variant, expr))) = preceded(multispace0, enum_variant).parse(rest)
+        else {
+            break;
+        };
+        let (rest, separator) = opt(preceded(multispace0, char(','))).parse(rest)?;
+        let (rest, trailing) = opt(preceded(space0, parse_comment::<CppComment>)).parse(rest)?;
+
+        variant.comment = comment.or(trailing);
+        variants.push((variant, expr));
+        input = rest;
+        if separator.is_none() {
+            break;
+        }
+    }
+
+    // comments after the last variant
+    let (input, _) = comments(input)?;
+
+    Ok((input, evaluate_variants(variants, scope)))
+}
+
+// Parse the full enum, opaque declarations like `enum class EFoo : uint8;` have no variants
+pub fn cpp_enum(input: &str) -> IResult<&str, CppEnum<'_>, VerboseError<&str>> {
+    let (input, comment) = opt(parse_comment::<CppComment>).parse(input)?;
+    let (input, _) = multispace0(input)?;
+    let (input, enum_keyword) = terminated(keyword("enum"), multispace1).parse(input)?;
+    let (input, scoped) = opt(delimited(
         multispace0,
-        alt((tag("struct"), tag("class"))),
+        alt((keyword("struct"), keyword("class"))),
         multispace0,
     ))
     .parse(input)?;
+    let (input, attributes) = parse_attributes(input)?;
     let (input, name) = opt(terminated(identifier, multispace0)).parse(input)?;
     let (input, ctype) = opt(delimited((char(':'), multispace0), parse_cpp_type, multispace0)).parse(input)?;
-    let (input, variants) = delimited(
+    let (input, variants) = opt(delimited(
         char('{'),
-        delimited(multispace0, enum_variants, multispace0),
+        delimited(multispace0, |i| enum_variants(i, name), multispace0),
         char('}'),
-    )
+    ))
     .parse(input)?;
     let (input, _) = delimited(multispace0, char(';'), multispace0).parse(input)?;
 
@@ -91,8 +412,12 @@ pub fn cpp_enum(input: &str) -> IResult<&str, CppEnum, VerboseError<&str>> {
         input,
         CppEnum {
             name: name.map(|s| s.to_string()),
-            variants,
+            keyword: enum_keyword,
+            variants: variants.unwrap_or_default(),
             ctype,
+            scoped: scoped.is_some(),
+            comment,
+            attributes,
         },
     ))
 }
@@ -114,27 +439,85 @@ mod tests {
         assert_eq!(int_literal("123 "), Ok((" ", 123)));
         assert_eq!(int_literal("-45"), Ok(("", -45)));
         assert!(int_literal("abc").is_err());
+        assert_eq!(int_literal("-0x8000000000000000"), Ok(("", i64::MIN)));
+    }
+
+    #[test]
+    fn test_int_literal_forms() {
+        assert_eq!(int_literal("0x10"), Ok(("", 16)));
+        assert_eq!(int_literal("0XFFu"), Ok(("", 255)));
+        assert_eq!(int_literal("0b1010"), Ok(("", 10)));
+        assert_eq!(int_literal("017"), Ok(("", 15)));
+        assert_eq!(int_literal("0"), Ok(("", 0)));
+        assert_eq!(int_literal("1'000'000ULL"), Ok(("", 1_000_000)));
+        assert_eq!(int_literal("42l,"), Ok((",", 42)));
+    }
+
+    #[test]
+    fn test_enum_variants_with_expressions() {
+        let (input, variants) = enum_variants(
+            "None = 0, A = 1 << 0, B = 0x1 << 1, C = (A | B) + 1, Last, Max = Last + 1, All = EFlags::A | B | C, Ext = sizeof(int)",
+            Some("EFlags"),
+        )
+        .unwrap();
+
+        assert_eq!(input, "");
+        let values: Vec<(&str, Option<&str>, Option<i64>)> = variants
+            .iter()
+            .map(|v| (v.name.as_str(), v.expression.as_deref(), v.value))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("None", Some("0"), Some(0)),
+                ("A", Some("1 << 0"), Some(1)),
+                ("B", Some("0x1 << 1"), Some(2)),
+                ("C", Some("(A | B) + 1"), Some(4)),
+                ("Last", None, Some(5)),
+                ("Max", Some("Last + 1"), Some(6)),
+                ("All", Some("EFlags::A | B | C"), Some(7)),
+                ("Ext", Some("sizeof(int)"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_variants_qualified_by_other_enum() {
+        let (_, variants) =
+            enum_variants("A = 4, B = EOther::A, C = EFlags::A, D = Outer::EFlags::A", Some("EFlags"))
+                .unwrap();
+
+        assert_eq!(
+            variants.iter().map(|v| v.value).collect::<Vec<_>>(),
+            vec![Some(4), None, Some(4), Some(4)]
+        );
     }
 
     #[test]
     fn test_enum_variant() {
         assert_eq!(
-            enum_variant("Red"),
+            enum_variant("Red").map(|(input, (variant, _))| (input, variant)),
             Ok((
                 "",
                 EnumVariant {
                     name: "Red".to_string(),
-                    value: None
+                    expression: None,
+                    value: None,
+                    attributes: vec![],
+                    comment: None,
                 }
             ))
         );
         assert_eq!(
-            enum_variant("Green = 5"),
+            enum_variant("Green = 5").map(|(input, (variant, _))| (input, variant)),
             Ok((
                 "",
                 EnumVariant {
                     name: "Green".to_string(),
-                    value: Some(5)
+                    expression: Some("5".to_string()),
+                    value: None,
+                    attributes: vec![],
+                    comment: None,
                 }
             ))
         );
@@ -143,27 +526,68 @@ mod tests {
     #[test]
     fn test_enum_variants() {
         assert_eq!(
-            enum_variants("Red, Green=5 , Blue,"),
+            enum_variants("Red, Green=5 , Blue,", None),
             Ok((
                 "",
                 vec![
                     EnumVariant {
                         name: "Red".into(),
-                        value: None
+                        expression: None,
+                        value: Some(0),
+                        attributes: vec![],
+                        comment: None,
                     },
                     EnumVariant {
                         name: "Green".into(),
-                        value: Some(5)
+                        expression: Some("5".into()),
+                        value: Some(5),
+                        attributes: vec![],
+                        comment: None,
                     },
                     EnumVariant {
                         name: "Blue".into(),
-                        value: None
+                        expression: None,
+                        value: Some(6),
+                        attributes: vec![],
+                        comment: None,
                     },
                 ]
             ))
         );
     }
 
+    #[test]
+    fn test_enum_variants_with_comments() {
+        let src = r#"enum class EWeapon : uint8 {
+                /// Fires single shots
+                Rifle,
+                Shotgun = 2, // Spreads pellets
+                // Not released yet
+
+                // Explodes
+                Rocket = Shotgun + 1
+                // More to come
+            };"#;
+
+        let (input, cpp_enum) = cpp_enum(src).unwrap();
+        assert_eq!(input, "");
+        assert_eq!(
+            cpp_enum
+                .variants
+                .iter()
+                .map(|v| {
+                    let comment = v.comment.as_ref().map(|c| c.comment.as_str());
+                    (v.name.as_str(), v.value, comment)
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                ("Rifle", Some(0), Some("Fires single shots")),
+                ("Shotgun", Some(2), Some("Spreads pellets")),
+                ("Rocket", Some(3), Some("Not released yet\nExplodes")),
+            ]
+        );
+    }
+
     #[test]
     fn test_cpp_enum() {
         let src = r#"enum class Color : i8 {
@@ -175,21 +599,34 @@ mod tests {
 
         let expected = CppEnum {
             name: Some("Color".to_string()),
+            keyword: "enum",
             ctype: Some(Path(vec!["i8"])),
             variants: vec![
                 EnumVariant {
                     name: "Red".to_string(),
-                    value: None,
+                    expression: None,
+                    value: Some(0),
+                    attributes: vec![],
+                    comment: None,
                 },
                 EnumVariant {
                     name: "Green".to_string(),
+                    expression: Some("5".to_string()),
                     value: Some(5),
+                    attributes: vec![],
+                    comment: None,
                 },
                 EnumVariant {
                     name: "Blue".to_string(),
-                    value: None,
+                    expression: None,
+                    value: Some(6),
+                    attributes: vec![],
+                    comment: None,
                 },
             ],
+            scoped: true,
+            attributes: vec![],
+            comment: None,
         };
 
         assert_eq!(cpp_enum(src), Ok(("", expected)));
@@ -206,19 +643,62 @@ mod tests {
 
         let expected = CppEnum {
             name: None,
+            keyword: "enum",
             ctype: None,
             variants: vec![
                 EnumVariant {
                     name: "Foo".to_string(),
+                    expression: Some("10".to_string()),
                     value: Some(10),
+                    attributes: vec![],
+                    comment: None,
                 },
                 EnumVariant {
                     name: "Bar".to_string(),
-                    value: None,
+                    expression: None,
+                    value: Some(11),
+                    attributes: vec![],
+                    comment: None,
                 },
             ],
+            scoped: false,
+            attributes: vec![],
+            comment: None,
+        };
+
+        assert_eq!(cpp_enum(src), Ok(("", expected)));
+    }
+
+    #[test]
+    fn test_cpp_enum_opaque_declaration_with_comment() {
+        let src = "/// Movement modes\nenum class EMode : uint8;";
+
+        let expected = CppEnum {
+            name: Some("EMode".to_string()),
+            keyword: "enum",
+            ctype: Some(Path(vec!["uint8"])),
+            scoped: true,
+            attributes: vec![],
+            comment: Some(CppComment {
+                comment: "Movement modes".to_string(),
+            }),
+            ..Default::default()
         };
 
         assert_eq!(cpp_enum(src), Ok(("", expected)));
     }
+
+    #[test]
+    fn test_cpp_enum_with_attributes() {
+        let src = r#"enum class [[deprecated("Use EMode")]] EOldMode : uint8 { A, B [[deprecated]] };"#;
+        let (input, e) = cpp_enum(src).unwrap();
+
+        assert_eq!(input, "");
+        assert_eq!(e.name, Some("EOldMode".to_string()));
+        assert_eq!(e.variants.len(), 2);
+        assert!(e.is_deprecated());
+        assert_eq!(e.deprecation_message(), Some("Use EMode"));
+        assert!(!e.variants[0].is_deprecated());
+        assert!(e.variants[1].is_deprecated());
+    }
 }
@@ -1,36 +1,87 @@
-﻿use crate::parser::cpp::comment::CppComment;
+﻿use crate::parser::cpp::alias::CppAlias;
+use crate::parser::cpp::attribute::{Attributed, CppAttribute};
+use crate::parser::cpp::cenum::CppEnum;
+use crate::parser::cpp::comment::CppComment;
 use crate::parser::cpp::member::CppMember;
+use crate::parser::cpp::using::CppUsing;
 use crate::parser::cpp::method::CppFunction;
 
-use crate::parser::generic::class::{Class, CppParentClass, InheritanceVisibility, parse_class};
+use crate::parser::generic::class::{
+    Class, ClassDefinition, ClassKind, CppParentClass, InheritanceVisibility,
+};
 
-use crate::parser::cpp::ctype::CType::Path;
+use crate::parser::cpp::ctype::CType;
 use crate::parser::generic::annotation::NoAnnotation;
+use crate::parser::generic::method::CppStorageQualifier;
 use std::collections::HashMap;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct CppClass<'a> {
     pub name: &'a str,
+    pub kind: ClassKind,
     pub api: Option<&'a str>,
     pub parents: Vec<CppParentClass<'a>>,
     pub methods: HashMap<InheritanceVisibility, Vec<CppFunction<'a>>>,
     pub members: HashMap<InheritanceVisibility, Vec<CppMember<'a>>>,
     pub inner_classes: HashMap<InheritanceVisibility, Vec<CppClass<'a>>>,
+    /// Unnamed structs and unions whose members belong to the enclosing class
+    pub anonymous_aggregates: HashMap<InheritanceVisibility, Vec<CppClass<'a>>>,
+    pub aliases: HashMap<InheritanceVisibility, Vec<CppAlias<'a>>>,
+    pub enums: HashMap<InheritanceVisibility, Vec<CppEnum<'a>>>,
+    /// Using-declarations like `using Super::BeginPlay;` and using-directives
+    pub usings: HashMap<InheritanceVisibility, Vec<CppUsing<'a>>>,
+    /// Classes declared with `friend class`
+    pub friends: Vec<CType<'a>>,
+    pub attributes: Vec<CppAttribute<'a>>,
 }
 
 impl Default for CppClass<'_> {
     fn default() -> Self {
         Self {
             name: "",
+            kind: ClassKind::Class,
             api: None,
             parents: vec![],
             methods: HashMap::from([]),
             members: HashMap::from([]),
             inner_classes: HashMap::from([]),
+            anonymous_aggregates: HashMap::from([]),
+            aliases: HashMap::from([]),
+            enums: HashMap::from([]),
+            usings: HashMap::from([]),
+            friends: vec![],
+            attributes: vec![],
         }
     }
 }
 
+impl<'a> CppClass<'a> {
+    /// Operators declared as `friend` inside the class body or the body of a nested class. They
+    /// are not members even though they are defined in the class.
+    pub fn friend_operators(&self) -> Vec<&CppFunction<'a>> {
+        self.methods
+            .values()
+            .flatten()
+            .filter(|method| {
+                method.is_operator()
+                    && method.storage_qualifiers.contains(&CppStorageQualifier::Friend)
+            })
+            .chain(
+                self.inner_classes
+                    .values()
+                    .flatten()
+                    .flat_map(CppClass::friend_operators),
+            )
+            .collect()
+    }
+}
+
+impl<'a> Attributed<'a> for CppClass<'a> {
+    fn attributes(&self) -> &[CppAttribute<'a>] {
+        &self.attributes
+    }
+}
+
 impl<'a> Class<'a> for CppClass<'a> {
     type ClassAnnotation = NoAnnotation;
     type MemberAnnotation = NoAnnotation;
@@ -38,35 +89,47 @@ impl<'a> Class<'a> for CppClass<'a> {
     type Method = CppFunction<'a>;
     type Member = CppMember<'a>;
 
-    fn class(
-        name: &'a str,
-        api: Option<&'a str>,
-        parents: Vec<CppParentClass<'a>>,
-        methods: HashMap<InheritanceVisibility, Vec<Self::Method>>,
-        members: HashMap<InheritanceVisibility, Vec<Self::Member>>,
-        inner_classes: HashMap<InheritanceVisibility, Vec<Self>>,
-        _: Option<Vec<Self::ClassAnnotation>>,
-    ) -> Self
+    fn class(definition: ClassDefinition<'a, Self>) -> Self
     where
         Self: 'a + Sized,
     {
         Self {
-            name,
-            api,
-            parents,
-            methods,
-            members,
-            inner_classes,
+            name: definition.name,
+            kind: definition.kind,
+            api: definition.api,
+            parents: definition.parents,
+            methods: definition.methods,
+            members: definition.members,
+            inner_classes: definition.inner_classes,
+            anonymous_aggregates: definition.anonymous_aggregates,
+            aliases: definition.aliases,
+            enums: definition.enums,
+            usings: definition.usings,
+            friends: definition.friends,
+            attributes: definition.attributes,
         }
     }
+
+    fn name(&self) -> &'a str {
+        self.name
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::parser::cpp::alias::{AliasKind, CppAlias};
+    use crate::parser::cpp::attribute::CppAttribute;
     use crate::parser::cpp::class::{CppClass, CppParentClass, InheritanceVisibility};
-    use crate::parser::cpp::ctype::CType::Path;
-    use crate::parser::cpp::method::CppFunction;
-    use crate::parser::generic::class::parse_class;
+    use crate::parser::cpp::cenum::{CppEnum, EnumVariant};
+    use crate::parser::cpp::comment::CppComment;
+    use crate::parser::cpp::ctype::CType::{Array, Function, Generic, Path, Pointer};
+    use crate::parser::cpp::expression::CppExpression;
+    use crate::parser::cpp::member::CppMember;
+    use crate::parser::cpp::macro_table::MacroTable;
+    use crate::parser::cpp::method::{CppFunction, FunctionKind};
+    use crate::parser::cpp::using::CppUsing;
+    use crate::parser::generic::class::{ClassKind, parse_class};
+    use crate::parser::generic::ignore::IgnoreRules;
     use nom::Err::Error;
     use nom_language::error::VerboseError;
     use nom_language::error::VerboseErrorKind::Char;
@@ -91,7 +154,7 @@ mod tests {
             void hello(){};
             void invalid({};
         }"#;
-        let result = parse_class::<CppClass>(input, &vec![]);
+        let result = parse_class::<CppClass>(input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -101,12 +164,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fails_for_unterminated_class() {
+        let input = "class FTruncated {\n    int32 A;\n";
+        let result = parse_class::<CppClass>(input, &IgnoreRules::default());
+
+        assert_eq!(
+            result,
+            Err(Error(VerboseError {
+                errors: vec![("", Char('}'))]
+            }))
+        );
+    }
+
 
     #[test]
     fn test_parse_empty_cpp_with_inheritance_class() {
         for visibility in ["private", "protected", "public", ""] {
             let input = format!("class test : {visibility} a {{}};");
-            let result = parse_class(&input, &vec![]);
+            let result = parse_class(&input, &IgnoreRules::default());
 
             assert_eq!(
                 result,
@@ -129,13 +205,14 @@ mod tests {
     #[test]
     fn test_empty_struct() {
         let input = "struct Test {};";
-        let result = parse_class(&input, &vec![]);
+        let result = parse_class(input, &IgnoreRules::default());
         assert_eq!(
             result,
             Ok((
                 "",
                 CppClass {
                     name: "Test",
+                    kind: ClassKind::Struct,
                     ..CppClass::default()
                 }
             ))
@@ -147,7 +224,7 @@ mod tests {
         struct Test<int>
         {
         };"#;
-        let result = parse_class(&input, &vec![]);
+        let result = parse_class(input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -155,6 +232,7 @@ mod tests {
                 "",
                 CppClass {
                     name: "Test",
+                    kind: ClassKind::Struct,
                     ..CppClass::default()
                 }
             ))
@@ -164,7 +242,7 @@ mod tests {
     #[test]
     fn test_parse_empty_struct() {
         let input = "struct Test {};";
-        let result = parse_class(&input, &vec![]);
+        let result = parse_class(input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -172,6 +250,7 @@ mod tests {
                 "",
                 CppClass {
                     name: "Test",
+                    kind: ClassKind::Struct,
                     ..CppClass::default()
                 }
             ))
@@ -183,7 +262,7 @@ mod tests {
         let input = r#"struct Test {
             struct Inner {}
         };"#;
-        let result = parse_class(&input, &vec![]);
+        let result = parse_class(input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -191,10 +270,12 @@ mod tests {
                 "",
                 CppClass {
                     name: "Test",
+                    kind: ClassKind::Struct,
                     inner_classes: HashMap::from([(
-                        InheritanceVisibility::Private,
+                        InheritanceVisibility::Public,
                         vec![CppClass {
                             name: "Inner",
+                            kind: ClassKind::Struct,
                             ..CppClass::default()
                         }]
                     ),]),
@@ -210,7 +291,7 @@ mod tests {
         {
             Test(){};
         };"#;
-        let result = parse_class(&input, &vec![]);
+        let result = parse_class(input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -218,10 +299,13 @@ mod tests {
                 "",
                 CppClass {
                     name: "Test",
+                    kind: ClassKind::Struct,
                     methods: HashMap::from([(
-                        InheritanceVisibility::Private,
+                        InheritanceVisibility::Public,
                         vec![CppFunction {
                             name: "Test",
+                            body: Some("{}"),
+                            kind: FunctionKind::Constructor,
                             ..Default::default()
                         }]
                     ),]),
@@ -234,7 +318,7 @@ mod tests {
     #[test]
     fn test_parse_empty_templated_struct() {
         let input = "template<typename T>\nstruct Test {};";
-        let result = parse_class(&input, &vec![]);
+        let result = parse_class(input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -242,6 +326,7 @@ mod tests {
                 "",
                 CppClass {
                     name: "Test",
+                    kind: ClassKind::Struct,
                     ..CppClass::default()
                 }
             ))
@@ -251,7 +336,7 @@ mod tests {
     #[test]
     fn test_parse_empty_cpp_with_multiple_inheritance_classes() {
         let input = "class test : public a, private b {};";
-        let result = parse_class(&input, &vec![]);
+        let result = parse_class(input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -279,7 +364,7 @@ mod tests {
     #[test]
     fn test_parse_empty_cpp_with_namespaced_inheritance_class() {
         let input = "class test : public namespace::a {};";
-        let result = parse_class(&input, &vec![]);
+        let result = parse_class(input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -317,8 +402,11 @@ mod tests {
                 test();
         };"#;
 
-        for input in [input1, input2] {
-            let result = parse_class(&input, &vec![]);
+        let body = &input1[input1.find("{\n                    int j").unwrap()
+            ..input1.rfind("}\n        };").unwrap() + 1];
+
+        for (input, body) in [(input1, Some(body)), (input2, None)] {
+            let result = parse_class(input, &IgnoreRules::default());
 
             assert_eq!(
                 result,
@@ -330,6 +418,8 @@ mod tests {
                             InheritanceVisibility::Public,
                             vec![CppFunction {
                                 name: "test",
+                                body,
+                                kind: FunctionKind::Constructor,
                                 ..Default::default()
                             }]
                         ),]),
@@ -342,8 +432,8 @@ mod tests {
 
     #[test]
     fn test_parse_empty_cpp_with_api() {
-        let input = format!("class MY_API test {{}};");
-        let result = parse_class(&input, &vec![]);
+        let input = MacroTable::unreal().expand("class MY_API test {};");
+        let result = parse_class(&input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -367,7 +457,7 @@ mod tests {
                 random_whitespace_string(),
                 random_newline_string()
             );
-            let result = parse_class(&input, &vec![]);
+            let result = parse_class(&input, &IgnoreRules::default());
 
             assert_eq!(
                 result,
@@ -387,7 +477,7 @@ mod tests {
         let input = r#"class test {
                 void hello();
             };"#;
-        let result = parse_class(&input, &vec![]);
+        let result = parse_class(input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -408,10 +498,333 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_struct_with_multiple_declarators() {
+        let input = r#"struct FPoint {
+                int32 X, Y, Z;
+            };"#;
+        let result = parse_class(input, &IgnoreRules::default());
+
+        let member = |name| CppMember {
+            name,
+            ctype: Path(vec!["int32"]),
+            ..Default::default()
+        };
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppClass {
+                    name: "FPoint",
+                    kind: ClassKind::Struct,
+                    members: HashMap::from([(
+                        InheritanceVisibility::Public,
+                        vec![member("X"), member("Y"), member("Z")]
+                    ),]),
+                    ..CppClass::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_class_with_typedef() {
+        let input = r#"class test {
+            public:
+                typedef void (*Callback)(int);
+            };"#;
+        let result = parse_class(input, &IgnoreRules::default());
+
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppClass {
+                    name: "test",
+                    aliases: HashMap::from([(
+                        InheritanceVisibility::Public,
+                        vec![CppAlias {
+                            name: "Callback",
+                            ctype: Pointer(Box::new(Function(
+                                Box::new(Path(vec!["void"])),
+                                vec![Path(vec!["int"])]
+                            ))),
+                            kind: AliasKind::Typedef,
+                            class: None,
+                        }]
+                    ),]),
+                    ..CppClass::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_class_with_nested_enum_and_alias() {
+        let input = r#"class test {
+            public:
+                // Possible states
+                enum class EState : uint8 { Idle, Running };
+
+                using FStateArray = TArray<EState>;
+            };"#;
+        let result = parse_class(input, &IgnoreRules::default());
+
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppClass {
+                    name: "test",
+                    enums: HashMap::from([(
+                        InheritanceVisibility::Public,
+                        vec![CppEnum {
+                            name: Some("EState".to_string()),
+                            keyword: "enum",
+                            ctype: Some(Path(vec!["uint8"])),
+                            variants: vec![
+                                EnumVariant {
+                                    name: "Idle".to_string(),
+                                    expression: None,
+                                    value: Some(0),
+                                    attributes: vec![],
+                                    comment: None,
+                                },
+                                EnumVariant {
+                                    name: "Running".to_string(),
+                                    expression: None,
+                                    value: Some(1),
+                                    attributes: vec![],
+                                    comment: None,
+                                }
+                            ],
+                            scoped: true,
+                            attributes: vec![],
+                            comment: Some(CppComment {
+                                comment: "Possible states".to_string()
+                            }),
+                        }]
+                    ),]),
+                    aliases: HashMap::from([(
+                        InheritanceVisibility::Public,
+                        vec![CppAlias {
+                            name: "FStateArray",
+                            ctype: Generic(Box::new(Path(vec!["TArray"])), vec![Path(vec!["EState"])]),
+                            kind: AliasKind::Using,
+                            class: None,
+                        }]
+                    ),]),
+                    ..CppClass::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_struct_with_attributes() {
+        let input = r#"struct [[deprecated]] alignas(16) FAlignedVector {
+            [[maybe_unused]] float X;
+        };"#;
+        let result = parse_class(input, &IgnoreRules::default());
+
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppClass {
+                    name: "FAlignedVector",
+                    kind: ClassKind::Struct,
+                    members: HashMap::from([(
+                        InheritanceVisibility::Public,
+                        vec![CppMember {
+                            name: "X",
+                            ctype: Path(vec!["float"]),
+                            attributes: vec![CppAttribute {
+                                name: "maybe_unused",
+                                arguments: None,
+                            }],
+                            ..Default::default()
+                        }]
+                    ),]),
+                    attributes: vec![
+                        CppAttribute {
+                            name: "deprecated",
+                            arguments: None,
+                        },
+                        CppAttribute {
+                            name: "alignas",
+                            arguments: Some("16"),
+                        },
+                    ],
+                    ..CppClass::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_named_union() {
+        let input = "union FBits { uint32 Value; float Real; };";
+        let result = parse_class(input, &IgnoreRules::default());
+
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppClass {
+                    name: "FBits",
+                    kind: ClassKind::Union,
+                    members: HashMap::from([(
+                        InheritanceVisibility::Public,
+                        vec![
+                            CppMember {
+                                name: "Value",
+                                ctype: Path(vec!["uint32"]),
+                                ..Default::default()
+                            },
+                            CppMember {
+                                name: "Real",
+                                ctype: Path(vec!["float"]),
+                                ..Default::default()
+                            }
+                        ]
+                    ),]),
+                    ..CppClass::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_class_with_using_declarations() {
+        let input = r#"class FDerived : public FBase {
+        public:
+            using FBase::FBase;
+        protected:
+            using Super::BeginPlay;
+        };"#;
+        let result = parse_class(input, &IgnoreRules::default());
+
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppClass {
+                    name: "FDerived",
+                    parents: vec![CppParentClass {
+                        name: Path(vec!["FBase"]),
+                        visibility: InheritanceVisibility::Public,
+                    }],
+                    usings: HashMap::from([
+                        (
+                            InheritanceVisibility::Public,
+                            vec![CppUsing::Declaration(vec!["FBase", "FBase"])]
+                        ),
+                        (
+                            InheritanceVisibility::Protected,
+                            vec![CppUsing::Declaration(vec!["Super", "BeginPlay"])]
+                        ),
+                    ]),
+                    ..CppClass::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_struct_with_anonymous_union() {
+        let input = r#"struct FVector {
+            public:
+                union {
+                    struct { float X, Y, Z; };
+                    float XYZ[3];
+                };
+            };"#;
+        let result = parse_class(input, &IgnoreRules::default());
+
+        let float = |name| CppMember {
+            name,
+            ctype: Path(vec!["float"]),
+            ..Default::default()
+        };
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppClass {
+                    name: "FVector",
+                    kind: ClassKind::Struct,
+                    anonymous_aggregates: HashMap::from([(
+                        InheritanceVisibility::Public,
+                        vec![CppClass {
+                            kind: ClassKind::Union,
+                            anonymous_aggregates: HashMap::from([(
+                                InheritanceVisibility::Public,
+                                vec![CppClass {
+                                    kind: ClassKind::Struct,
+                                    members: HashMap::from([(
+                                        InheritanceVisibility::Public,
+                                        vec![float("X"), float("Y"), float("Z")]
+                                    ),]),
+                                    ..CppClass::default()
+                                }]
+                            ),]),
+                            members: HashMap::from([(
+                                InheritanceVisibility::Public,
+                                vec![CppMember {
+                                    name: "XYZ",
+                                    ctype: Array(Box::new(Path(vec!["float"])), Some("3")),
+                                    ..Default::default()
+                                }]
+                            ),]),
+                            ..CppClass::default()
+                        }]
+                    ),]),
+                    ..CppClass::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_struct_with_declared_aggregates() {
+        let input = r#"struct FPacket {
+                union { float Value; int32 Bits; } Data;
+                struct FHeader { int32 Size; } Header, History[2];
+            };"#;
+        let (rest, class) = parse_class::<CppClass>(input, &IgnoreRules::default()).unwrap();
+
+        assert_eq!(rest, "");
+        assert!(class.anonymous_aggregates.is_empty());
+        assert_eq!(
+            class.members[&InheritanceVisibility::Public],
+            vec![
+                CppMember {
+                    name: "Data",
+                    ctype: Path(vec![]),
+                    ..Default::default()
+                },
+                CppMember {
+                    name: "Header",
+                    ctype: Path(vec!["FHeader"]),
+                    ..Default::default()
+                },
+                CppMember {
+                    name: "History",
+                    ctype: Array(Box::new(Path(vec!["FHeader"])), Some("2")),
+                    ..Default::default()
+                }
+            ]
+        );
+        let inner_classes = &class.inner_classes[&InheritanceVisibility::Public];
+        assert_eq!(inner_classes[0].kind, ClassKind::Union);
+        assert_eq!(inner_classes[1].name, "FHeader");
+    }
+
     #[test]
     fn test_parse_class_with_multiple_methods() {
         let input = "class test {void hello();\nvoid goodbye();};";
-        let result = parse_class(&input, &vec![]);
+        let result = parse_class(input, &IgnoreRules::default());
 
         assert_eq!(
             result,
@@ -439,116 +852,119 @@ mod tests {
             ))
         );
     }
-}
 
-#[test]
-fn test_parse_class_with_multiple_mixed_methods() {
-    let input = format!("class test {{void hello();\nauto goodbye() -> int;}}");
-    let result = parse_class(&input, &vec![]);
-
-    assert_eq!(
-        result,
-        Ok((
-            "",
-            CppClass {
-                name: "test",
-                methods: HashMap::from([(
-                    InheritanceVisibility::Private,
-                    vec![
-                        CppFunction {
-                            name: "hello",
-                            ..Default::default()
-                        },
-                        CppFunction {
-                            name: "goodbye",
-                            return_type: Some(Path(vec!["int"])),
-                            ..Default::default()
-                        }
-                    ]
-                ),]),
-                ..CppClass::default()
-            }
-        ))
-    );
-}
+    #[test]
+    fn test_parse_class_with_multiple_mixed_methods() {
+        let input = "class test {void hello();\nauto goodbye() -> int;}".to_string();
+        let result = parse_class(&input, &IgnoreRules::default());
+
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppClass {
+                    name: "test",
+                    methods: HashMap::from([(
+                        InheritanceVisibility::Private,
+                        vec![
+                            CppFunction {
+                                name: "hello",
+                                ..Default::default()
+                            },
+                            CppFunction {
+                                name: "goodbye",
+                                return_type: Some(Path(vec!["int"])),
+                                ..Default::default()
+                            }
+                        ]
+                    ),]),
+                    ..CppClass::default()
+                }
+            ))
+        );
+    }
 
-#[test]
-fn test_simple_class() {
-    let input = r#"class TestClass {
-        private:
-            // says only hello to itself
-            auto helloPrivate() -> void;
+    #[test]
+    fn test_simple_class() {
+        let input = r#"class TestClass {
+            private:
+                // says only hello to itself
+                auto helloPrivate() -> void;
 
-        protected:
-            // says only hello to its relatives
-            auto helloProtected() -> void;
+            protected:
+                // says only hello to its relatives
+                auto helloProtected() -> void;
 
-        public:
-            /*
-             * says hello to everybody that listens
-             */
-            auto hello() -> void;
-
-        private:
-            /// internal counter on how many times others were greeted
-            int count{0};
-    };"#;
-
-    let result = parse_class(input, &vec![]);
-
-    assert_eq!(
-        result,
-        Ok((
-            "",
-            CppClass {
-                name: "TestClass",
-                methods: HashMap::from([
-                    (
+            public:
+                /*
+                 * says hello to everybody that listens
+                 */
+                auto hello() -> void;
+
+            private:
+                /// internal counter on how many times others were greeted
+                int count{0};
+        };"#;
+
+        let result = parse_class(input, &IgnoreRules::default());
+
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppClass {
+                    name: "TestClass",
+                    methods: HashMap::from([
+                        (
+                            InheritanceVisibility::Private,
+                            vec![CppFunction {
+                                name: "helloPrivate",
+                                comment: Some(CppComment {
+                                    comment: "says only hello to itself".to_string(),
+                                }),
+                                ..Default::default()
+                            }],
+                        ),
+                        (
+                            InheritanceVisibility::Protected,
+                            vec![CppFunction {
+                                name: "helloProtected",
+                                comment: Some(CppComment {
+                                    comment: "says only hello to its relatives".to_string(),
+                                }),
+                                ..Default::default()
+                            }],
+                        ),
+                        (
+                            InheritanceVisibility::Public,
+                            vec![CppFunction {
+                                name: "hello",
+                                comment: Some(CppComment {
+                                    comment: "says hello to everybody that listens".to_string(),
+                                }),
+                                ..Default::default()
+                            }],
+                        ),
+                    ]),
+                    members: HashMap::from([(
                         InheritanceVisibility::Private,
-                        vec![CppFunction {
-                            name: "helloPrivate",
+                        vec![CppMember {
+                            name: "count",
+                            ctype: Path(vec!["int"]),
+                            default_value: Some(CppExpression::InitList(
+                                None,
+                                vec![CppExpression::Literal("0")]
+                            )),
                             comment: Some(CppComment {
-                                comment: "says only hello to itself".to_string(),
+                                comment: "internal counter on how many times others were greeted"
+                                    .to_string(),
                             }),
                             ..Default::default()
                         }],
-                    ),
-                    (
-                        InheritanceVisibility::Protected,
-                        vec![CppFunction {
-                            name: "helloProtected",
-                            comment: Some(CppComment {
-                                comment: "says only hello to its relatives".to_string(),
-                            }),
-                            ..Default::default()
-                        }],
-                    ),
-                    (
-                        InheritanceVisibility::Public,
-                        vec![CppFunction {
-                            name: "hello",
-                            comment: Some(CppComment {
-                                comment: "says hello to everybody that listens".to_string(),
-                            }),
-                            ..Default::default()
-                        }],
-                    ),
-                ]),
-                members: HashMap::from([(
-                    InheritanceVisibility::Private,
-                    vec![CppMember {
-                        name: "count",
-                        ctype: Path(vec!["int"]),
-                        default_value: Some(Path(vec!["0"])),
-                        comment: Some(CppComment {
-                            comment: "internal counter on how many times others were greeted"
-                                .to_string(),
-                        }),
-                        ..Default::default()
-                    }],
-                ),]),
-                ..CppClass::default()
-            }
-        ))
-    );
+                    ),]),
+                    ..CppClass::default()
+                }
+            ))
+        );
+    }
 }
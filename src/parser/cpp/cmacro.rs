@@ -0,0 +1,237 @@
+﻿use crate::parser::cpp::comment::CppComment;
+use crate::parser::generic::comment::parse_comment;
+use crate::parser::{keyword, ws};
+use crate::types::Parsable;
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::{char, multispace0, space0, space1};
+use nom::combinator::{consumed, map_opt, opt, recognize};
+use nom::multi::separated_list0;
+use nom::sequence::{delimited, preceded};
+use nom::{IResult, Parser};
+use nom_language::error::VerboseError;
+
+/// A `#define` with the comment in front of it
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct CppMacro<'a> {
+    pub name: &'a str,
+    /// `None` for object-like macros, `Some(vec![])` for `#define F()`. Variadic macros end with
+    /// `...` or a named pack like `Args...`
+    pub params: Option<Vec<&'a str>>,
+    /// Replacement text with line continuations joined by a single space
+    pub body: String,
+    pub comment: Option<CppComment>,
+}
+
+impl CppMacro<'_> {
+    pub fn is_function_like(&self) -> bool {
+        self.params.is_some()
+    }
+
+    pub fn is_variadic(&self) -> bool {
+        self.params
+            .as_ref()
+            .and_then(|params| params.last())
+            .is_some_and(|param| param.ends_with("..."))
+    }
+}
+
+impl<'a> Parsable<'a> for CppMacro<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self, VerboseError<&'a str>> {
+        let (input, comment) = opt(map_opt(
+            (consumed(parse_comment::<CppComment>), multispace0),
+            |((text, comment), gap): ((&str, CppComment), &str)| {
+                // a comment separated by a blank line doesn't document the macro
+                let line_breaks = text.ends_with('\n') as usize + gap.matches('\n').count();
+                (line_breaks <= 1).then_some(comment)
+            },
+        ))
+        .parse(input)?;
+        let (input, _) =
+            (multispace0, char('#'), space0, keyword("define"), space1).parse(input)?;
+        let (input, name) = identifier(input)?;
+        // a parameter list has to follow the name directly, `#define A (1)` is object-like
+        let (input, params) = opt(delimited(
+            char('('),
+            separated_list0(char(','), ws(macro_param)),
+            preceded(multispace0, char(')')),
+        ))
+        .parse(input)?;
+        let (input, body) = macro_body(input)?;
+
+        Ok((
+            input,
+            CppMacro {
+                name,
+                params,
+                body,
+                comment,
+            },
+        ))
+    }
+}
+
+fn identifier(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)
+}
+
+fn macro_param(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    alt((tag("..."), recognize((identifier, opt(tag("...")))))).parse(input)
+}
+
+/// Rest of the line including lines continued with a trailing `\`, without comments
+fn macro_body(input: &str) -> IResult<&str, String, VerboseError<&str>> {
+    let mut end = input.len();
+    let mut search = 0;
+    while let Some(newline) = input[search..].find('\n') {
+        let line_end = search + newline;
+        if !input[..line_end].trim_end().ends_with('\\') {
+            end = line_end;
+            break;
+        }
+        search = line_end + 1;
+    }
+
+    let body = strip_comments(&input[..end])
+        .lines()
+        .map(|line| line.trim_end().trim_end_matches('\\').trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Ok((&input[end..], body))
+}
+
+/// Replaces `/* */` comments with a space and drops `//` comments up to the end of their line.
+/// Comment markers in string and character literals are kept.
+fn strip_comments(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut quote = None;
+    let mut escaped = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match (c, chars.peek()) {
+                ('"' | '\'', _) => quote = Some(c),
+                ('/', Some('/')) => {
+                    while chars.next_if(|next| *next != '\n').is_some() {}
+                    continue;
+                }
+                ('/', Some('*')) => {
+                    chars.next();
+                    let mut previous = ' ';
+                    for next in chars.by_ref() {
+                        if previous == '*' && next == '/' {
+                            break;
+                        }
+                        previous = next;
+                    }
+                    result.push(' ');
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::cpp::cmacro::CppMacro;
+    use crate::parser::cpp::comment::CppComment;
+    use crate::types::Parsable;
+
+    #[test]
+    fn object_like_macro() {
+        assert_eq!(
+            CppMacro::parse("#define MAX_PLAYERS (4)\nint32 A;"),
+            Ok((
+                "\nint32 A;",
+                CppMacro {
+                    name: "MAX_PLAYERS",
+                    body: "(4)".to_string(),
+                    ..Default::default()
+                }
+            ))
+        );
+        assert_eq!(
+            CppMacro::parse("#  define WITH_FEATURE"),
+            Ok((
+                "",
+                CppMacro {
+                    name: "WITH_FEATURE",
+                    ..Default::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn function_like_macro_with_continuations() {
+        let input = r#"/// Logs a message for the given category
+#define MY_LOG(Category, Format, ...) \
+    UE_LOG(Category, Log, Format, ##__VA_ARGS__); \
+    FlushLog()
+"#;
+        let (rest, result) = CppMacro::parse(input).unwrap();
+
+        assert_eq!(rest, "\n");
+        assert_eq!(
+            result,
+            CppMacro {
+                name: "MY_LOG",
+                params: Some(vec!["Category", "Format", "..."]),
+                body: "UE_LOG(Category, Log, Format, ##__VA_ARGS__); FlushLog()".to_string(),
+                comment: Some(CppComment {
+                    comment: "Logs a message for the given category".to_string()
+                }),
+            }
+        );
+        assert!(result.is_function_like());
+        assert!(result.is_variadic());
+    }
+
+    #[test]
+    fn comments_are_not_part_of_the_body() {
+        let (rest, result) = CppMacro::parse("#define A 1 // trailing\nint32 B;").unwrap();
+        assert_eq!(rest, "\nint32 B;");
+        assert_eq!(result.body, "1");
+
+        let input = "#define PATH TEXT(\"//Game/*\") /* root */ \\\n    + Suffix // end";
+        let (_, result) = CppMacro::parse(input).unwrap();
+        assert_eq!(result.body, r#"TEXT("//Game/*") + Suffix"#);
+    }
+
+    #[test]
+    fn only_adjacent_comments_document_the_macro() {
+        let (_, result) = CppMacro::parse("// Max\n#define MAX 4").unwrap();
+        assert_eq!(
+            result.comment,
+            Some(CppComment {
+                comment: "Max".to_string()
+            })
+        );
+
+        assert!(CppMacro::parse("// Section\n\n#define MAX 4").is_err());
+        assert!(CppMacro::parse("/* Section */\n\n#define MAX 4").is_err());
+    }
+
+    #[test]
+    fn named_variadic_and_empty_params() {
+        let (_, result) = CppMacro::parse("#define FORWARD(Args...) Call(Args)").unwrap();
+        assert_eq!(result.params, Some(vec!["Args..."]));
+        assert!(result.is_variadic());
+
+        let (_, result) = CppMacro::parse("#define NOOP() ").unwrap();
+        assert_eq!(result.params, Some(vec![]));
+        assert_eq!(result.body, "");
+        assert!(!result.is_variadic());
+    }
+}
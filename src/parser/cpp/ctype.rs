@@ -1,15 +1,17 @@
-﻿use nom::combinator::map;
-use nom::multi::separated_list0;
+﻿use crate::parser::keyword;
+use nom::combinator::map;
+use nom::multi::{separated_list0, separated_list1};
 use nom::{
     IResult, Parser,
     branch::alt,
     bytes::complete::{tag, take_while1},
-    character::complete::{char, multispace0},
-    combinator::opt,
+    character::complete::{char, multispace0, multispace1, one_of},
+    combinator::{not, opt, recognize},
     multi::many0,
-    sequence::{delimited, preceded},
+    sequence::{delimited, preceded, terminated},
 };
 use nom_language::error::VerboseError;
+use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum CType<'a> {
@@ -21,6 +23,48 @@ pub enum CType<'a> {
     Reference(Box<CType<'a>>),
     MemberAccess(Box<CType<'a>>, &'a str),
     Const(Box<CType<'a>>),
+    /// Array with its extent as written, e.g. `3` or `MAX_ITEMS`. The extent is omitted in `int A[]`
+    Array(Box<CType<'a>>, Option<&'a str>),
+}
+
+impl<'a> CType<'a> {
+    /// Name of the class the type refers to once qualifiers, pointers, references and template
+    /// arguments are stripped, e.g. `FVector` for `const UE::FVector&`
+    pub fn base_name(&self) -> Option<&'a str> {
+        match self {
+            CType::Path(path) => path.last().copied(),
+            CType::Generic(base, _)
+            | CType::Pointer(base)
+            | CType::Reference(base)
+            | CType::Const(base)
+            | CType::Array(base, _) => base.base_name(),
+            CType::Auto | CType::Function(_, _) | CType::MemberAccess(_, _) => None,
+        }
+    }
+}
+
+impl Display for CType<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn join(types: &[CType]) -> String {
+            types
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        match self {
+            CType::Auto => write!(f, "auto"),
+            CType::Path(path) => write!(f, "{}", path.join("::")),
+            CType::Generic(base, args) => write!(f, "{}<{}>", base, join(args)),
+            CType::Function(ret, params) => write!(f, "{}({})", ret, join(params)),
+            CType::Pointer(ty) => write!(f, "{}*", ty),
+            CType::Reference(ty) => write!(f, "{}&", ty),
+            CType::MemberAccess(ty, member) => write!(f, "{}::{}", ty, member),
+            CType::Const(ty) => write!(f, "const {}", ty),
+            CType::Array(ty, extent) => write!(f, "{}[{}]", ty, extent.unwrap_or_default()),
+        }
+    }
 }
 
 impl Default for CType<'static> {
@@ -75,7 +119,11 @@ fn parse_function<'a>(
     ty: CType<'a>,
 ) -> IResult<&'a str, CType<'a>, VerboseError<&'a str>> {
     opt(delimited(
-        preceded(multispace0, char('(')),
+        // `(*` and `(&` start a pointer or reference declarator like `void (*Callback)(int)`
+        terminated(
+            preceded(multispace0, char('(')),
+            not(preceded(multispace0, one_of("*&"))),
+        ),
         separated_list0(
             preceded(multispace0, char(',')),
             map(
@@ -114,7 +162,7 @@ fn parse_ptrs_refs<'a>(
     Ok((input, ty))
 }
 
-fn parse_type(input: &str) -> IResult<&str, CType, VerboseError<&str>> {
+fn parse_type(input: &str) -> IResult<&str, CType<'_>, VerboseError<&str>> {
     // Parse optional leading const
     let (input, leading_const) = opt(preceded(multispace0, tag("const"))).parse(input)?;
 
@@ -147,7 +195,31 @@ fn cpp_ident(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
     )
     .parse(input)
 }
-fn parse_type_atom_inner(input: &str) -> IResult<&str, CType, VerboseError<&str>> {
+/// Recognizes builtin types spelled with several keywords like `unsigned int` or `long long`.
+fn fundamental_type(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    preceded(
+        multispace0,
+        recognize(separated_list1(
+            multispace1,
+            alt((
+                keyword("unsigned"),
+                keyword("signed"),
+                keyword("short"),
+                keyword("long"),
+                keyword("int"),
+                keyword("char"),
+                keyword("double"),
+            )),
+        )),
+    )
+    .parse(input)
+}
+
+fn parse_type_atom_inner(input: &str) -> IResult<&str, CType<'_>, VerboseError<&str>> {
+    if let Ok((rest, fundamental)) = fundamental_type(input) {
+        return Ok((rest, CType::Path(vec![fundamental])));
+    }
+
     map(separated_list0(tag("::"), cpp_ident), |segments| {
         if segments.len() == 1 && segments[0] == "auto" {
             CType::Auto
@@ -158,7 +230,7 @@ fn parse_type_atom_inner(input: &str) -> IResult<&str, CType, VerboseError<&str>
     .parse(input)
 }
 
-fn parse_type_atom(input: &str) -> IResult<&str, CType, VerboseError<&str>> {
+fn parse_type_atom(input: &str) -> IResult<&str, CType<'_>, VerboseError<&str>> {
     let (input, is_const_before) = opt(preceded(multispace0, tag("const"))).parse(input)?;
     let (input, base) = parse_type_atom_inner(input)?;
     let (input, is_const_after) = opt(preceded(multispace0, tag("const"))).parse(input)?;
@@ -173,7 +245,7 @@ fn parse_type_atom(input: &str) -> IResult<&str, CType, VerboseError<&str>> {
     Ok((input, ty))
 }
 
-pub fn parse_cpp_type(input: &str) -> IResult<&str, CType, VerboseError<&str>> {
+pub fn parse_cpp_type(input: &str) -> IResult<&str, CType<'_>, VerboseError<&str>> {
     parse_type(input)
 }
 
@@ -318,4 +390,22 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_fundamental_multi_keyword_type() {
+        assert_eq!(
+            parse_cpp_type("unsigned int"),
+            Ok(("", Path(vec!["unsigned int"])))
+        );
+        assert_eq!(
+            parse_cpp_type("const unsigned long long*"),
+            Ok((
+                "",
+                CType::Pointer(Box::new(CType::Const(Box::new(Path(vec![
+                    "unsigned long long"
+                ])))))
+            ))
+        );
+        assert_eq!(parse_cpp_type("int32"), Ok(("", Path(vec!["int32"]))));
+    }
 }
@@ -0,0 +1,830 @@
+use crate::parser::cpp::cenum::integer_value;
+use crate::parser::cpp::ctype::{CType, parse_cpp_type};
+use crate::parser::keyword;
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while, take_while1};
+use nom::character::complete::{char, digit1, multispace0, none_of, one_of, satisfy};
+use nom::combinator::{map, not, opt, peek, recognize, value, verify};
+use nom::error::{ErrorKind, ParseError};
+use nom::multi::{many0, separated_list0, separated_list1};
+use nom::sequence::{delimited, preceded, terminated};
+use nom::{IResult, Parser};
+use nom_language::error::VerboseError;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Expression as used in default arguments and member initializers, e.g.
+/// `FVector::ZeroVector`, `TEXT("Hi")` or `EMode::A | EMode::B`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CppExpression<'a> {
+    /// Number, string, character, boolean or `nullptr` literal exactly as written
+    Literal(&'a str),
+    /// Possibly qualified name like `FVector::ZeroVector`
+    Name(CType<'a>),
+    /// Function call, functional cast or macro invocation like `TEXT("Hi")`
+    Call(CType<'a>, Vec<CppExpression<'a>>),
+    /// Braced initializer like `{1, 2, 3}`, optionally with a type as in `FIntPoint{1, 2}`
+    InitList(Option<CType<'a>>, Vec<CppExpression<'a>>),
+    Unary(&'a str, Box<CppExpression<'a>>),
+    Binary(&'a str, Box<CppExpression<'a>>, Box<CppExpression<'a>>),
+    /// `Condition ? A : B`
+    Conditional(
+        Box<CppExpression<'a>>,
+        Box<CppExpression<'a>>,
+        Box<CppExpression<'a>>,
+    ),
+    Cast(CastKind, CType<'a>, Box<CppExpression<'a>>),
+    /// Kept to print the expression the way it was written
+    Parenthesized(Box<CppExpression<'a>>),
+}
+
+/// Value of a literal expression
+#[derive(Debug, PartialEq, Clone)]
+pub enum Literal {
+    Int(i128),
+    Flt(f64),
+    Char(char),
+    Bool(bool),
+    /// String with escape sequences resolved. Raw strings and `TEXT("...")` are unwrapped.
+    Str(String),
+    Nullptr,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CastKind {
+    /// `(float)X`
+    CStyle,
+    Static,
+    Dynamic,
+    Const,
+    Reinterpret,
+}
+
+impl CppExpression<'_> {
+    /// The value if the expression is a literal like `-1`, `'A'`, `R"(raw)"` or `TEXT("Hi")`
+    pub fn literal(&self) -> Option<Literal> {
+        match self {
+            CppExpression::Literal(text) => literal_value(text),
+            CppExpression::Unary("-", operand) => match operand.literal()? {
+                Literal::Int(value) => Some(Literal::Int(-value)),
+                Literal::Flt(value) => Some(Literal::Flt(-value)),
+                _ => None,
+            },
+            CppExpression::Call(CType::Path(path), args) if path[..] == ["TEXT"] => match &args[..]
+            {
+                [text] => text
+                    .literal()
+                    .filter(|value| matches!(value, Literal::Str(_))),
+                _ => None,
+            },
+            CppExpression::Parenthesized(inner) => inner.literal(),
+            _ => None,
+        }
+    }
+}
+
+fn literal_value(text: &str) -> Option<Literal> {
+    match text {
+        "true" => return Some(Literal::Bool(true)),
+        "false" => return Some(Literal::Bool(false)),
+        "nullptr" => return Some(Literal::Nullptr),
+        _ => {}
+    }
+
+    if text.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return number_value(text);
+    }
+
+    // strip encoding prefixes like `L`, `u8` or `U`
+    let unprefixed = text.trim_start_matches(['L', 'u', 'U', '8']);
+    if let Some(raw) = unprefixed.strip_prefix('R') {
+        // R"delimiter(content)delimiter"
+        let raw = raw.strip_prefix('"')?.strip_suffix('"')?;
+        let open = raw.find('(')?;
+        let close = raw.rfind(')')?;
+        return Some(Literal::Str(raw[open + 1..close].to_string()));
+    }
+    if let Some(string) = unprefixed.strip_prefix('"') {
+        return Some(Literal::Str(unescape(string.strip_suffix('"')?)));
+    }
+    if let Some(character) = unprefixed.strip_prefix('\'') {
+        let mut chars = unescape(character.strip_suffix('\'')?)
+            .chars()
+            .collect::<Vec<_>>();
+        return match chars.len() {
+            1 => chars.pop().map(Literal::Char),
+            _ => None,
+        };
+    }
+
+    None
+}
+
+fn number_value(text: &str) -> Option<Literal> {
+    let is_hex = text.starts_with("0x") || text.starts_with("0X");
+    if !is_hex && text.contains(['.', 'e', 'E']) {
+        let digits: String = text
+            .trim_end_matches(['f', 'F', 'l', 'L'])
+            .chars()
+            .filter(|c| *c != '\'')
+            .collect();
+        return digits.parse().ok().map(Literal::Flt);
+    }
+
+    integer_value(text)
+        .ok()
+        .map(|value| Literal::Int(value as i128))
+}
+
+/// Resolves simple, octal (`\101`), hexadecimal (`\x41`) and universal character name
+/// (`\u00E9`, `\U0001F600`) escape sequences. Code points that are no valid `char` are dropped.
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some('n') => Some('\n'),
+            Some('r') => Some('\r'),
+            Some('t') => Some('\t'),
+            Some('a') => Some('\x07'),
+            Some('b') => Some('\x08'),
+            Some('f') => Some('\x0C'),
+            Some('v') => Some('\x0B'),
+            Some('x') => code_point(&mut chars, 16, String::new(), usize::MAX),
+            Some('u') => code_point(&mut chars, 16, String::new(), 4),
+            Some('U') => code_point(&mut chars, 16, String::new(), 8),
+            Some(digit @ '0'..='7') => code_point(&mut chars, 8, digit.to_string(), 3),
+            other => other,
+        };
+        result.extend(escaped);
+    }
+    result
+}
+
+/// Takes up to `max_digits` digits of an escape sequence from `chars`
+fn code_point(
+    chars: &mut Peekable<Chars>,
+    radix: u32,
+    mut digits: String,
+    max_digits: usize,
+) -> Option<char> {
+    while digits.len() < max_digits
+        && let Some(digit) = chars.next_if(|digit| digit.is_digit(radix))
+    {
+        digits.push(digit);
+    }
+    u32::from_str_radix(&digits, radix)
+        .ok()
+        .and_then(char::from_u32)
+}
+
+impl Display for CppExpression<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn join(expressions: &[CppExpression]) -> String {
+            expressions
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        match self {
+            CppExpression::Literal(literal) => write!(f, "{}", literal),
+            CppExpression::Name(name) => write!(f, "{}", name),
+            CppExpression::Call(callee, args) => write!(f, "{}({})", callee, join(args)),
+            CppExpression::InitList(Some(ctype), items) => {
+                write!(f, "{}{{{}}}", ctype, join(items))
+            }
+            CppExpression::InitList(None, items) => write!(f, "{{{}}}", join(items)),
+            CppExpression::Unary(op, operand) => {
+                let operand = operand.to_string();
+                // `-(-1)` must not be printed as the decrement `--1`
+                let merges = op.len() == 1 && operand.starts_with(*op);
+                match merges || self.operand_needs_parentheses() {
+                    true => write!(f, "{}({})", op, operand),
+                    false => write!(f, "{}{}", op, operand),
+                }
+            }
+            CppExpression::Binary(op, lhs, rhs) => {
+                let level = self.binding_level();
+                let wrap = |expression: &CppExpression, weakest: usize| {
+                    if expression.binding_level() > weakest {
+                        format!("({})", expression)
+                    } else {
+                        expression.to_string()
+                    }
+                };
+                // binary operators are left associative, `A - (B - C)` keeps its parentheses
+                write!(f, "{} {} {}", wrap(lhs, level), op, wrap(rhs, level - 1))
+            }
+            CppExpression::Conditional(condition, then, otherwise) => {
+                // the conditional operator is right associative, `(A ? B : C) ? D : E` keeps
+                // its parentheses
+                match condition.binding_level() >= self.binding_level() {
+                    true => write!(f, "({}) ? {} : {}", condition, then, otherwise),
+                    false => write!(f, "{} ? {} : {}", condition, then, otherwise),
+                }
+            }
+            CppExpression::Cast(CastKind::CStyle, ctype, operand) => {
+                match self.operand_needs_parentheses() {
+                    true => write!(f, "({})({})", ctype, operand),
+                    false => write!(f, "({}){}", ctype, operand),
+                }
+            }
+            CppExpression::Cast(kind, ctype, operand) => {
+                let cast = match kind {
+                    CastKind::Static => "static_cast",
+                    CastKind::Dynamic => "dynamic_cast",
+                    CastKind::Const => "const_cast",
+                    CastKind::Reinterpret => "reinterpret_cast",
+                    CastKind::CStyle => unreachable!(),
+                };
+                write!(f, "{}<{}>({})", cast, ctype, operand)
+            }
+            CppExpression::Parenthesized(inner) => write!(f, "({})", inner),
+        }
+    }
+}
+
+impl CppExpression<'_> {
+    /// 0 for primary expressions, 1 for unary operators and casts, binary operators follow with
+    /// increasing numbers for weaker binding levels and the conditional operator binds weakest
+    fn binding_level(&self) -> usize {
+        match self {
+            CppExpression::Binary(op, _, _) => binary_level(op) + 2,
+            CppExpression::Conditional(_, _, _) => BINARY_OPERATORS.len() + 2,
+            CppExpression::Unary(_, _) | CppExpression::Cast(CastKind::CStyle, _, _) => 1,
+            _ => 0,
+        }
+    }
+
+    /// Whether the operand of a unary operator or C-style cast is a binary expression that has to
+    /// be put in parentheses when printed
+    fn operand_needs_parentheses(&self) -> bool {
+        match self {
+            CppExpression::Unary(_, operand)
+            | CppExpression::Cast(CastKind::CStyle, _, operand) => operand.binding_level() > 1,
+            _ => false,
+        }
+    }
+}
+
+fn binary_level(op: &str) -> usize {
+    BINARY_OPERATORS
+        .iter()
+        .position(|level| level.contains(&op))
+        .unwrap_or(BINARY_OPERATORS.len())
+}
+
+/// Binary operators from the strongest to the weakest binding level. Operators of one level that
+/// share a prefix are ordered longest first.
+const BINARY_OPERATORS: &[&[&str]] = &[
+    &["*", "/", "%"],
+    &["+", "-"],
+    &["<<", ">>"],
+    &["<=", ">=", "<", ">"],
+    &["==", "!="],
+    &["&"],
+    &["^"],
+    &["|"],
+    &["&&"],
+    &["||"],
+];
+
+pub fn parse_expression(input: &str) -> IResult<&str, CppExpression<'_>, VerboseError<&str>> {
+    conditional_expression(input)
+}
+
+/// `Condition ? A : B`, the condition is a binary expression and the last operand may be another
+/// conditional expression
+fn conditional_expression(input: &str) -> IResult<&str, CppExpression<'_>, VerboseError<&str>> {
+    let (input, condition) = binary_expression(input, BINARY_OPERATORS.len() - 1)?;
+    let (input, branches) = opt((
+        delimited(
+            (multispace0, char('?'), multispace0),
+            parse_expression,
+            multispace0,
+        ),
+        preceded((char(':'), multispace0), conditional_expression),
+    ))
+    .parse(input)?;
+
+    let expression = match branches {
+        Some((then, otherwise)) => {
+            CppExpression::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise))
+        }
+        None => condition,
+    };
+
+    Ok((input, expression))
+}
+
+fn binary_expression(
+    input: &str,
+    level: usize,
+) -> IResult<&str, CppExpression<'_>, VerboseError<&str>> {
+    let operand = |i| match level {
+        0 => unary_expression(i),
+        _ => binary_expression(i, level - 1),
+    };
+
+    let (mut input, mut lhs) = operand(input)?;
+    while let Ok((rest, op)) = preceded(multispace0, |i| binary_operator(i, level)).parse(input) {
+        let (rest, rhs) = preceded(multispace0, operand).parse(rest)?;
+        lhs = CppExpression::Binary(op, Box::new(lhs), Box::new(rhs));
+        input = rest;
+    }
+
+    Ok((input, lhs))
+}
+
+fn binary_operator(input: &str, level: usize) -> IResult<&str, &str, VerboseError<&str>> {
+    for op in BINARY_OPERATORS[level] {
+        let result: IResult<&str, &str, VerboseError<&str>> = match *op {
+            // `&` and `|` must not match the first half of `&&` and `||`
+            "&" | "|" => terminated(tag(*op), not(one_of("&|"))).parse(input),
+            "<" | ">" => terminated(tag(*op), not(one_of("<>="))).parse(input),
+            _ => tag(*op)(input),
+        };
+        if result.is_ok() {
+            return result;
+        }
+    }
+
+    Err(nom::Err::Error(VerboseError::from_error_kind(
+        input,
+        ErrorKind::Tag,
+    )))
+}
+
+fn unary_expression(input: &str) -> IResult<&str, CppExpression<'_>, VerboseError<&str>> {
+    alt((
+        map(
+            (
+                alt((tag("-"), tag("+"), tag("!"), tag("~"), tag("&"), tag("*"))),
+                preceded(multispace0, unary_expression),
+            ),
+            |(op, operand)| CppExpression::Unary(op, Box::new(operand)),
+        ),
+        c_style_cast,
+        primary_expression,
+    ))
+    .parse(input)
+}
+
+fn primary_expression(input: &str) -> IResult<&str, CppExpression<'_>, VerboseError<&str>> {
+    alt((
+        map(literal, CppExpression::Literal),
+        named_cast,
+        map(init_list, |items| CppExpression::InitList(None, items)),
+        map(
+            delimited(
+                (char('('), multispace0),
+                parse_expression,
+                (multispace0, char(')')),
+            ),
+            |inner| CppExpression::Parenthesized(Box::new(inner)),
+        ),
+        name_expression,
+    ))
+    .parse(input)
+}
+
+/// `(float)X`, the operand has to follow directly so `(A) + B` stays a parenthesized name
+fn c_style_cast(input: &str) -> IResult<&str, CppExpression<'_>, VerboseError<&str>> {
+    let (input, ctype) = delimited(
+        (
+            char('('),
+            multispace0,
+            peek(satisfy(|c| c.is_alphabetic() || c == '_')),
+        ),
+        parse_cpp_type,
+        (multispace0, char(')'), multispace0),
+    )
+    .parse(input)?;
+    let (input, _) = peek(satisfy(|c: char| {
+        c.is_alphanumeric() || "_(\"'{.".contains(c)
+    }))
+    .parse(input)?;
+    let (input, operand) = unary_expression(input)?;
+
+    Ok((
+        input,
+        CppExpression::Cast(CastKind::CStyle, ctype, Box::new(operand)),
+    ))
+}
+
+/// `static_cast<int32>(X)`
+fn named_cast(input: &str) -> IResult<&str, CppExpression<'_>, VerboseError<&str>> {
+    let (input, kind) = alt((
+        value(CastKind::Static, keyword("static_cast")),
+        value(CastKind::Dynamic, keyword("dynamic_cast")),
+        value(CastKind::Const, keyword("const_cast")),
+        value(CastKind::Reinterpret, keyword("reinterpret_cast")),
+    ))
+    .parse(input)?;
+    let (input, ctype) = delimited(
+        (multispace0, char('<'), multispace0),
+        parse_cpp_type,
+        (multispace0, char('>'), multispace0),
+    )
+    .parse(input)?;
+    let (input, operand) = delimited(
+        (char('('), multispace0),
+        parse_expression,
+        (multispace0, char(')')),
+    )
+    .parse(input)?;
+
+    Ok((input, CppExpression::Cast(kind, ctype, Box::new(operand))))
+}
+
+/// Names, calls like `FVector(0.f)` or `GetDefault<UFoo>()` and typed braced initializers
+fn name_expression(input: &str) -> IResult<&str, CppExpression<'_>, VerboseError<&str>> {
+    let (input, name) = qualified_name(input)?;
+    let (input, args) = opt(preceded(multispace0, call_arguments)).parse(input)?;
+    if let Some(args) = args {
+        return Ok((input, CppExpression::Call(name, args)));
+    }
+    let (input, items) = opt(preceded(multispace0, init_list)).parse(input)?;
+
+    let expression = match items {
+        Some(items) => CppExpression::InitList(Some(name), items),
+        None => CppExpression::Name(name),
+    };
+
+    Ok((input, expression))
+}
+
+/// `A::B`, `::GlobalName` or `TNumericLimits<float>::Max`. Template arguments are only taken if
+/// they are followed by `(`, `{` or `::`, otherwise `A < B` would be read as a template.
+pub(crate) fn qualified_name(input: &str) -> IResult<&str, CType<'_>, VerboseError<&str>> {
+    let (input, _) = opt(tag("::")).parse(input)?;
+    let (input, path) = separated_list1(tag("::"), identifier).parse(input)?;
+    let mut name = CType::Path(path);
+
+    let (input, args) = opt(terminated(
+        delimited(
+            (multispace0, char('<')),
+            separated_list0(
+                char(','),
+                delimited(multispace0, parse_cpp_type, multispace0),
+            ),
+            char('>'),
+        ),
+        peek(preceded(multispace0, alt((tag("("), tag("{"), tag("::"))))),
+    ))
+    .parse(input)?;
+    if let Some(args) = args {
+        name = CType::Generic(Box::new(name), args);
+    }
+
+    let (input, members) = many0(preceded(tag("::"), identifier)).parse(input)?;
+    for member in members {
+        name = CType::MemberAccess(Box::new(name), member);
+    }
+
+    Ok((input, name))
+}
+
+pub(crate) fn call_arguments(
+    input: &str,
+) -> IResult<&str, Vec<CppExpression<'_>>, VerboseError<&str>> {
+    delimited(
+        (char('('), multispace0),
+        separated_list0(
+            char(','),
+            delimited(multispace0, parse_expression, multispace0),
+        ),
+        char(')'),
+    )
+    .parse(input)
+}
+
+/// `{1, 2, 3}`, a trailing comma is allowed
+pub(crate) fn init_list(input: &str) -> IResult<&str, Vec<CppExpression<'_>>, VerboseError<&str>> {
+    delimited(
+        (char('{'), multispace0),
+        terminated(
+            separated_list0(
+                char(','),
+                delimited(multispace0, parse_expression, multispace0),
+            ),
+            opt(char(',')),
+        ),
+        (multispace0, char('}')),
+    )
+    .parse(input)
+}
+
+fn identifier(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    verify(
+        take_while1(|c: char| c.is_alphanumeric() || c == '_'),
+        |s: &str| !s.starts_with(|c: char| c.is_ascii_digit()),
+    )
+    .parse(input)
+}
+
+fn literal(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    alt((
+        number_literal,
+        string_literal,
+        char_literal,
+        keyword("nullptr"),
+        keyword("true"),
+        keyword("false"),
+    ))
+    .parse(input)
+}
+
+/// `1`, `1.5f`, `.5`, `0x1F`, `1'000`, `1e-5`
+fn number_literal(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    recognize((
+        alt((digit1, recognize((char('.'), digit1)))),
+        many0(alt((
+            recognize((one_of("eEpP"), one_of("+-"))),
+            take_while1(|c: char| c.is_alphanumeric() || c == '.' || c == '\''),
+        ))),
+    ))
+    .parse(input)
+}
+
+/// `"Hi"`, `L"Hi"`, `u8"Hi"` or a raw string like `R"json({"a": 1})json"`
+fn string_literal(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    recognize((
+        opt(alt((tag("u8"), tag("u"), tag("U"), tag("L")))),
+        alt((raw_string, quoted('"'))),
+    ))
+    .parse(input)
+}
+
+fn raw_string(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    let (rest, delimiter) = delimited(
+        tag("R\""),
+        take_while(|c: char| c != '(' && c != '"' && !c.is_whitespace()),
+        char('('),
+    )
+    .parse(input)?;
+
+    let end = format!("){}\"", delimiter);
+    match rest.find(&end) {
+        Some(position) => {
+            let length = input.len() - rest.len() + position + end.len();
+            Ok((&input[length..], &input[..length]))
+        }
+        None => Err(nom::Err::Error(VerboseError::from_error_kind(
+            input,
+            ErrorKind::TakeUntil,
+        ))),
+    }
+}
+
+/// `'a'`, `'\n'`
+fn char_literal(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    recognize((
+        opt(alt((tag("u8"), tag("u"), tag("U"), tag("L")))),
+        quoted('\''),
+    ))
+    .parse(input)
+}
+
+fn quoted<'a>(
+    quote: char,
+) -> impl Parser<&'a str, Output = &'a str, Error = VerboseError<&'a str>> {
+    recognize((
+        char(quote),
+        many0(alt((
+            recognize((char('\\'), satisfy(|_| true))),
+            recognize(none_of(if quote == '"' { "\"\\" } else { "'\\" })),
+        ))),
+        char(quote),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::cpp::ctype::CType;
+    use crate::parser::cpp::expression::{CastKind, CppExpression, Literal, parse_expression};
+
+    fn roundtrip(input: &str) -> String {
+        let (rest, expression) = parse_expression(input).unwrap();
+        assert_eq!(rest, "");
+        expression.to_string()
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(
+            parse_expression("nullptr"),
+            Ok(("", CppExpression::Literal("nullptr")))
+        );
+        assert_eq!(
+            parse_expression("1.5f"),
+            Ok(("", CppExpression::Literal("1.5f")))
+        );
+        assert_eq!(
+            parse_expression("1e-5"),
+            Ok(("", CppExpression::Literal("1e-5")))
+        );
+        assert_eq!(
+            parse_expression(r#""Say \"Hi\"""#),
+            Ok(("", CppExpression::Literal(r#""Say \"Hi\"""#)))
+        );
+        assert_eq!(
+            parse_expression("'\\n'"),
+            Ok(("", CppExpression::Literal("'\\n'")))
+        );
+    }
+
+    #[test]
+    fn qualified_name() {
+        assert_eq!(
+            parse_expression("FVector::ZeroVector"),
+            Ok((
+                "",
+                CppExpression::Name(CType::Path(vec!["FVector", "ZeroVector"]))
+            ))
+        );
+    }
+
+    #[test]
+    fn macro_call() {
+        assert_eq!(
+            parse_expression(r#"TEXT("Hi")"#),
+            Ok((
+                "",
+                CppExpression::Call(
+                    CType::Path(vec!["TEXT"]),
+                    vec![CppExpression::Literal(r#""Hi""#)]
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn braced_init_list() {
+        assert_eq!(
+            parse_expression("{1, 2, 3}"),
+            Ok((
+                "",
+                CppExpression::InitList(
+                    None,
+                    vec![
+                        CppExpression::Literal("1"),
+                        CppExpression::Literal("2"),
+                        CppExpression::Literal("3")
+                    ]
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn binary_operators_bind_by_precedence() {
+        assert_eq!(
+            parse_expression("EMode::A | EMode::B & Mask"),
+            Ok((
+                "",
+                CppExpression::Binary(
+                    "|",
+                    Box::new(CppExpression::Name(CType::Path(vec!["EMode", "A"]))),
+                    Box::new(CppExpression::Binary(
+                        "&",
+                        Box::new(CppExpression::Name(CType::Path(vec!["EMode", "B"]))),
+                        Box::new(CppExpression::Name(CType::Path(vec!["Mask"])))
+                    ))
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn casts() {
+        assert_eq!(
+            parse_expression("static_cast<uint8>(-1)"),
+            Ok((
+                "",
+                CppExpression::Cast(
+                    CastKind::Static,
+                    CType::Path(vec!["uint8"]),
+                    Box::new(CppExpression::Unary(
+                        "-",
+                        Box::new(CppExpression::Literal("1"))
+                    ))
+                )
+            ))
+        );
+        assert_eq!(
+            parse_expression("(float)Count"),
+            Ok((
+                "",
+                CppExpression::Cast(
+                    CastKind::CStyle,
+                    CType::Path(vec!["float"]),
+                    Box::new(CppExpression::Name(CType::Path(vec!["Count"])))
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn stops_at_argument_separator() {
+        assert_eq!(
+            parse_expression("A < B, C)"),
+            Ok((
+                ", C)",
+                CppExpression::Binary(
+                    "<",
+                    Box::new(CppExpression::Name(CType::Path(vec!["A"]))),
+                    Box::new(CppExpression::Name(CType::Path(vec!["B"])))
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn literal_escape_sequences() {
+        let value = |input| parse_expression(input).unwrap().1.literal();
+
+        assert_eq!(
+            value(r#""\x41\101\u00E9\U0001F600\0""#),
+            Some(Literal::Str("AA\u{e9}\u{1f600}\0".to_string()))
+        );
+        assert_eq!(value(r"'\x7f'"), Some(Literal::Char('\x7f')));
+        assert_eq!(value(r"'\''"), Some(Literal::Char('\'')));
+        assert_eq!(value("1.0000000001"), Some(Literal::Flt(1.0000000001)));
+    }
+
+    #[test]
+    fn prints_source_text() {
+        assert_eq!(
+            roundtrip("FVector(0.f, 1.f, 2.f)"),
+            "FVector(0.f, 1.f, 2.f)"
+        );
+        assert_eq!(roundtrip("FIntPoint{1,2}"), "FIntPoint{1, 2}");
+        assert_eq!(roundtrip("(A + B) * -C"), "(A + B) * -C");
+        assert_eq!(
+            roundtrip("GetDefault<UMyObject>()"),
+            "GetDefault<UMyObject>()"
+        );
+        assert_eq!(
+            roundtrip("TNumericLimits<float>::Max()"),
+            "TNumericLimits<float>::Max()"
+        );
+        assert_eq!(roundtrip("static_cast<int32>(X)"), "static_cast<int32>(X)");
+        assert_eq!(roundtrip("-(-1)"), "-(-1)");
+        assert_eq!(roundtrip("bFlag ? 1 : 2"), "bFlag ? 1 : 2");
+        assert_eq!(roundtrip("X > 0 ? X : 0"), "X > 0 ? X : 0");
+        assert_eq!(roundtrip("A ? B : C ? D : E"), "A ? B : C ? D : E");
+        assert_eq!(roundtrip("(A ? B : C) ? D : E"), "(A ? B : C) ? D : E");
+        assert_eq!(roundtrip("-(A ? B : C)"), "-(A ? B : C)");
+    }
+
+    #[test]
+    fn conditional_operator_binds_weakest() {
+        let name = |name| Box::new(CppExpression::Name(CType::Path(vec![name])));
+
+        assert_eq!(
+            parse_expression("A || B ? C : D, E"),
+            Ok((
+                ", E",
+                CppExpression::Conditional(
+                    Box::new(CppExpression::Binary("||", name("A"), name("B"))),
+                    name("C"),
+                    name("D")
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn prints_parentheses_by_precedence() {
+        let name = |name| Box::new(CppExpression::Name(CType::Path(vec![name])));
+
+        let negated = CppExpression::Unary("-", Box::new(CppExpression::Unary("-", name("A"))));
+        assert_eq!(negated.to_string(), "-(-A)");
+
+        let sum = CppExpression::Binary("+", name("A"), name("B"));
+        assert_eq!(
+            CppExpression::Binary("*", Box::new(sum.clone()), name("C")).to_string(),
+            "(A + B) * C"
+        );
+        assert_eq!(
+            CppExpression::Binary("-", name("C"), Box::new(sum.clone())).to_string(),
+            "C - (A + B)"
+        );
+        assert_eq!(
+            CppExpression::Binary("+", Box::new(sum.clone()), name("C")).to_string(),
+            "A + B + C"
+        );
+        assert_eq!(
+            CppExpression::Unary("!", Box::new(sum)).to_string(),
+            "!(A + B)"
+        );
+    }
+}
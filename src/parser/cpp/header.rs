@@ -1,54 +1,87 @@
-﻿use crate::parser::cpp::alias::CppAlias;
+﻿use crate::parser::cpp::alias::{CppAlias, parse_aliases};
+use crate::parser::cpp::cenum::{CppEnum, cpp_enum};
 use crate::parser::cpp::class::CppClass;
+use crate::parser::cpp::cmacro::CppMacro;
 use crate::parser::cpp::comment::CppComment;
+use crate::parser::cpp::linkage::{CppLinkageBlock, parse_linkage_block};
 use crate::parser::cpp::member::CppMember;
 use crate::parser::cpp::method::CppFunction;
-use crate::parser::cpp::namespace::CppNamespace;
-use crate::parser::generic::class::parse_class;
+use crate::parser::cpp::namespace::{CppNamespace, operators_for};
+use crate::parser::cpp::using::CppUsing;
+use crate::parser::generic::class::{Class, parse_class};
 use crate::parser::generic::comment::parse_comment;
-use crate::parser::generic::member::parse_member;
+use crate::parser::generic::ignore::IgnoreRules;
+use crate::parser::generic::member::parse_members;
 use crate::parser::generic::method::parse_method;
 use crate::parser::generic::namespace::parse_namespace;
+use crate::parser::include::IncludeStyle;
 use crate::types::Parsable;
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take_till};
 use nom::bytes::take_until;
-use nom::character::complete::{char, multispace0, multispace1};
-use nom::combinator::map;
+use nom::character::complete::{char, multispace0, space0};
+use nom::combinator::{map, opt};
 use nom::sequence::{delimited, preceded, terminated};
 use nom::{IResult, Parser};
 use nom_language::error::VerboseError;
 
+/// The declarations of a header. File scope classes are parsed as `ClassType`, e.g. `UClass` to
+/// keep the reflection specifiers of Unreal classes. Classes in namespaces are always `CppClass`.
 #[derive(Debug, Default, PartialEq)]
-pub struct CppHeader<'a> {
+pub struct CppHeader<'a, ClassType = CppClass<'a>> {
     comments: Vec<CppComment>,
     includes: Vec<&'a str>,
     aliases: Vec<CppAlias<'a>>,
+    enums: Vec<CppEnum<'a>>,
     functions: Vec<CppFunction<'a>>,
     declarations: Vec<CppMember<'a>>,
-    classes: Vec<CppClass<'a>>,
+    classes: Vec<ClassType>,
     namespaces: Vec<CppNamespace<'a>>,
+    linkage_blocks: Vec<CppLinkageBlock<'a>>,
+    usings: Vec<CppUsing<'a>>,
+    macros: Vec<CppMacro<'a>>,
 }
 
 impl<'a> Parsable<'a> for CppHeader<'a> {
     fn parse(input: &'a str) -> IResult<&'a str, CppHeader<'a>, VerboseError<&'a str>> {
+        CppHeader::parse_with_rules(input, &IgnoreRules::default())
+    }
+}
+
+impl<'a, ClassType> CppHeader<'a, ClassType>
+where
+    ClassType: Class<'a> + Default + 'a,
+{
+    /// Parses a header skipping statements that match `ignore_rules` at file scope, in namespaces
+    /// and in class bodies
+    pub fn parse_with_rules(
+        input: &'a str,
+        ignore_rules: &IgnoreRules,
+    ) -> IResult<&'a str, CppHeader<'a, ClassType>, VerboseError<&'a str>> {
         let mut header = CppHeader::default();
 
         let mut input = input;
         loop {
-            match parse_header_item(input) {
+            if input.trim_start().is_empty() {
+                return Ok(("", header));
+            }
+
+            match parse_header_item(input, ignore_rules) {
                 Ok((new_rest, item)) => {
                     match item {
                         CppHeaderItem::Ignore => {}
                         CppHeaderItem::Preprocessor(_) => {}
                         CppHeaderItem::Include(inc) => header.includes.push(inc),
-                        CppHeaderItem::Define(_) => {}
+                        CppHeaderItem::Define(define) => header.macros.push(define),
                         CppHeaderItem::Comment(comment) => header.comments.push(comment),
-                        CppHeaderItem::Alias(alias) => header.aliases.push(alias),
+                        CppHeaderItem::Aliases(alias) => header.aliases.extend(alias),
+                        CppHeaderItem::Enum(e) => header.enums.push(e),
                         CppHeaderItem::Function(func) => header.functions.push(func),
                         CppHeaderItem::Class(class) => header.classes.push(class),
                         CppHeaderItem::Namespace(ns) => header.namespaces.push(ns),
-                        CppHeaderItem::Declaration(var) => header.declarations.push(var),
+                        CppHeaderItem::Linkage(block) => header.linkage_blocks.push(block),
+                        CppHeaderItem::Using(using) => header.usings.push(using),
+                        CppHeaderItem::Declarations(vars) => header.declarations.extend(vars),
                     }
                     input = new_rest;
                 }
@@ -57,43 +90,114 @@ impl<'a> Parsable<'a> for CppHeader<'a> {
             }
         }
     }
+
+    /// Paths as written in the `#include` directives, see [`crate::parser::include::IncludeResolver`]
+    pub fn includes(&self) -> &[&'a str] {
+        &self.includes
+    }
+
+    pub fn classes(&self) -> &[ClassType] {
+        &self.classes
+    }
+
+    pub fn namespaces(&self) -> &[CppNamespace<'a>] {
+        &self.namespaces
+    }
+
+    pub fn functions(&self) -> &[CppFunction<'a>] {
+        &self.functions
+    }
+
+    pub fn declarations(&self) -> &[CppMember<'a>] {
+        &self.declarations
+    }
+
+    pub fn enums(&self) -> &[CppEnum<'a>] {
+        &self.enums
+    }
+
+    pub fn aliases(&self) -> &[CppAlias<'a>] {
+        &self.aliases
+    }
+
+    pub fn macros(&self) -> &[CppMacro<'a>] {
+        &self.macros
+    }
+
+    /// File scope using-declarations and using-directives
+    pub fn usings(&self) -> &[CppUsing<'a>] {
+        &self.usings
+    }
+
+    /// File scope `extern "C"` blocks and declarations
+    pub fn linkage_blocks(&self) -> &[CppLinkageBlock<'a>] {
+        &self.linkage_blocks
+    }
+}
+
+impl<'a> CppHeader<'a> {
+    /// Free and `friend` operators anywhere in the header that take `class_name` as an operand
+    pub fn operators_for(&self, class_name: &str) -> Vec<&CppFunction<'a>> {
+        operators_for(&self.functions, &self.classes, &self.namespaces, class_name)
+    }
 }
 
 #[derive(Debug)]
-enum CppHeaderItem<'a> {
+enum CppHeaderItem<'a, ClassType> {
     Preprocessor(&'a str),
     Include(&'a str),
-    Define(&'a str),
+    Define(CppMacro<'a>),
     Comment(CppComment),
-    Declaration(CppMember<'a>),
-    Alias(CppAlias<'a>),
+    Declarations(Vec<CppMember<'a>>),
+    Aliases(Vec<CppAlias<'a>>),
+    Enum(CppEnum<'a>),
     Function(CppFunction<'a>),
-    Class(CppClass<'a>),
+    Class(ClassType),
     Namespace(CppNamespace<'a>),
+    Linkage(CppLinkageBlock<'a>),
+    Using(CppUsing<'a>),
     Ignore,
 }
 
-fn parse_header_item(input: &str) -> IResult<&str, CppHeaderItem, VerboseError<&str>> {
+fn parse_header_item<'a, ClassType>(
+    input: &'a str,
+    ignore_rules: &IgnoreRules,
+) -> IResult<&'a str, CppHeaderItem<'a, ClassType>, VerboseError<&'a str>>
+where
+    ClassType: Class<'a> + 'a,
+{
     preceded(
         multispace0,
         alt((
             map(char::<_, VerboseError<&str>>('\u{feff}'), |_| {
                 CppHeaderItem::Ignore
             }),
-            map(parse_comment, CppHeaderItem::Comment),
+            map(|i| ignore_rules.parse(i), |_| CppHeaderItem::Ignore),
             map(parse_include, CppHeaderItem::Include),
-            map(parse_define, CppHeaderItem::Define),
+            map(<CppMacro as Parsable>::parse, CppHeaderItem::Define),
             map(preprocessor_directive, CppHeaderItem::Preprocessor), // fallthrough for all other preprocess directives
-            map(<CppAlias as Parsable>::parse, CppHeaderItem::Alias),
-            map(|i| parse_class(i, &vec![]), CppHeaderItem::Class),
             map(
-                terminated(parse_member, char(';')),
-                CppHeaderItem::Declaration,
+                |i| parse_linkage_block(i, ignore_rules),
+                CppHeaderItem::Linkage,
             ),
-            map(parse_namespace, |namespace| {
-                CppHeaderItem::Namespace(namespace)
-            }),
-            map(parse_method, CppHeaderItem::Function),
+            map(|i| parse_aliases(i, ignore_rules), CppHeaderItem::Aliases),
+            map(<CppUsing as Parsable>::parse, CppHeaderItem::Using),
+            map(cpp_enum, CppHeaderItem::Enum),
+            map(|i| parse_class(i, ignore_rules), CppHeaderItem::Class),
+            map(
+                |i| parse_namespace::<_, CppClass>(i, ignore_rules),
+                |namespace| CppHeaderItem::Namespace(namespace),
+            ),
+            map(
+                terminated(parse_members, preceded(multispace0, char(';'))),
+                CppHeaderItem::Declarations,
+            ),
+            // free function declarations like `FVector operator+(const FVector& A, const FVector& B);`
+            map(
+                terminated(parse_method, opt(preceded(multispace0, char(';')))),
+                CppHeaderItem::Function,
+            ),
+            map(parse_comment, CppHeaderItem::Comment),
         )),
     )
     .parse(input)
@@ -103,32 +207,44 @@ pub fn preprocessor_directive(input: &str) -> IResult<&str, &str, VerboseError<&
     preceded(tag("#"), take_till(|c| c == '\n')).parse(input)
 }
 
-pub fn parse_define(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
-    preceded((tag("#define"), multispace1), take_until("\n")).parse(input)
-}
 pub fn parse_include(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
-    let (input, _) = preceded(multispace0, tag("#include")).parse(input)?;
+    map(include_directive, |(file, _)| file).parse(input)
+}
+
+/// `#include "Player.h"` or `# include <vector>` with the style of the path
+pub(crate) fn include_directive(
+    input: &str,
+) -> IResult<&str, (&str, IncludeStyle), VerboseError<&str>> {
+    let (input, _) = (multispace0, char('#'), space0, tag("include")).parse(input)?;
     let (input, _) = multispace0.parse(input)?;
 
-    let relative = delimited(char('"'), take_until("\""), char('"'));
-    let absolute = delimited(char('<'), take_until(">"), char('>'));
-    let (input, file) = alt((relative, absolute)).parse(input)?;
+    let quoted = map(delimited(char('"'), take_until("\""), char('"')), |file| {
+        (file, IncludeStyle::Quoted)
+    });
+    let angled = map(delimited(char('<'), take_until(">"), char('>')), |file| {
+        (file, IncludeStyle::Angled)
+    });
 
-    Ok((input, file))
+    alt((quoted, angled)).parse(input)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::parser::cpp::cenum::{CppEnum, EnumVariant};
     use crate::parser::cpp::class::CppClass;
     use crate::parser::cpp::comment::CppComment;
     use crate::parser::cpp::ctype::CType;
+    use crate::parser::cpp::expression::CppExpression;
     use crate::parser::cpp::header::{CppHeader, parse_include, preprocessor_directive};
     use crate::parser::cpp::member::CppMember;
     use crate::parser::cpp::member::CppMemberModifier::{Const, Static};
     use crate::parser::cpp::method::CppFunction;
-    use crate::parser::generic::class::{CppParentClass, InheritanceVisibility};
+    use crate::parser::cpp::preprocessor::{PreprocessorConfig, preprocess};
+    use crate::parser::generic::class::{ClassKind, CppParentClass, InheritanceVisibility};
+    use crate::parser::generic::ignore::IgnoreRules;
     use crate::parser::generic::method::CppStorageQualifier::Virtual;
     use crate::parser::generic::method::PostParamQualifier::Override;
+    use crate::parser::source::{SourceEncoding, SourceText};
     use crate::types::Parsable;
     use std::collections::HashMap;
 
@@ -138,6 +254,7 @@ mod tests {
         let result = parse_include(input);
 
         assert_eq!(result, Ok(("", "CoreMinimal.h")));
+        assert_eq!(parse_include("# include <vector>"), Ok(("", "vector")));
     }
 
     #[test]
@@ -180,6 +297,7 @@ mod tests {
                     classes: vec![
                         CppClass {
                             name: "Empty",
+                            kind: ClassKind::Struct,
                             ..Default::default()
                         },
                         CppClass {
@@ -211,6 +329,7 @@ mod tests {
                     ],
                     functions: vec![CppFunction {
                         name: "sayHello",
+                        body: Some("{}"),
                         comment: Some(CppComment {
                             comment: "Say hello to everyone".to_string()
                         }),
@@ -219,7 +338,7 @@ mod tests {
                     declarations: vec![CppMember {
                         name: "helloCount",
                         ctype: CType::Path(vec!["int"]),
-                        default_value: Some(CType::Path(vec!["0"])),
+                        default_value: Some(CppExpression::Literal("0")),
                         modifiers: vec![Const, Static],
                         ..Default::default()
                     }],
@@ -227,5 +346,222 @@ mod tests {
                 }
             ))
         );
+        assert_eq!(
+            result.unwrap().1.includes(),
+            ["CoreMinimal.h", "Modules/ModuleManager.h"]
+        );
+    }
+
+    #[test]
+    fn test_header_with_enum() {
+        let input = r#"#pragma once
+            enum class EMode : uint8 { A, B };
+            "#;
+
+        let result = CppHeader::parse(input);
+        assert_eq!(
+            result,
+            Ok((
+                "",
+                CppHeader {
+                    enums: vec![CppEnum {
+                        name: Some("EMode".to_string()),
+                        keyword: "enum",
+                        ctype: Some(CType::Path(vec!["uint8"])),
+                        variants: vec![
+                            EnumVariant {
+                                name: "A".to_string(),
+                                expression: None,
+                                value: Some(0),
+                                attributes: vec![],
+                                comment: None,
+                            },
+                            EnumVariant {
+                                name: "B".to_string(),
+                                expression: None,
+                                value: Some(1),
+                                attributes: vec![],
+                                comment: None,
+                            },
+                        ],
+                        scoped: true,
+                        attributes: vec![],
+                        comment: None,
+                    }],
+                    ..CppHeader::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_header_links_operators_to_class() {
+        let input = r#"struct FVector {
+                friend FVector operator*(float Scale, const FVector& V);
+                friend class FMatrix;
+                struct FHash {
+                    friend uint32 operator%(const FVector& V, uint32 Buckets);
+                };
+            };
+            namespace UE {
+                FVector operator+(const FVector& A, const FVector& B);
+                bool operator==(const FVector& A, const FVector& B);
+            }
+            FRotator operator-(const FRotator& R);
+            "#;
+
+        let (_, header) = CppHeader::parse(input).unwrap();
+        let operators: Vec<&str> = header
+            .operators_for("FVector")
+            .iter()
+            .map(|function| function.name)
+            .collect();
+
+        assert_eq!(
+            operators,
+            vec!["operator*", "operator%", "operator+", "operator=="]
+        );
+        assert_eq!(
+            header.classes[0].friends,
+            vec![CType::Path(vec!["FMatrix"])]
+        );
+    }
+
+    #[test]
+    fn test_header_with_conditional_declarations() {
+        let input = r#"#pragma once
+            #if WITH_EDITOR
+            class FEditorHelper : public FEditorBase
+            #else
+            class FEditorHelper
+            #endif
+            {
+            public:
+            #if WITH_EDITORONLY_DATA
+                int32 EditorValue;
+            #endif
+            };
+            "#;
+
+        let config = PreprocessorConfig::from_definitions(["WITH_EDITOR=1"])
+            .define("WITH_EDITORONLY_DATA", "WITH_EDITOR");
+        let source = preprocess(input, &config);
+        let (_, header) = CppHeader::parse(&source.text).unwrap();
+
+        let class = &header.classes[0];
+        assert_eq!(class.parents[0].name, CType::Path(vec!["FEditorBase"]));
+        assert_eq!(source.conditions_of(class.name), vec!["WITH_EDITOR"]);
+
+        let member = &class.members[&InheritanceVisibility::Public][0];
+        assert_eq!(
+            source.conditions_of(member.name),
+            vec!["WITH_EDITORONLY_DATA"]
+        );
+
+        let source = preprocess(input, &PreprocessorConfig::default());
+        let (_, header) = CppHeader::parse(&source.text).unwrap();
+        assert!(header.classes[0].parents.is_empty());
+        assert!(header.classes[0].members.is_empty());
+    }
+
+    #[test]
+    fn test_header_collects_macros() {
+        let input = r#"#pragma once
+            #include "CoreMinimal.h"
+
+            // Number of slots in the quick bar
+            #define QUICKBAR_SLOTS 8
+
+            /// Checks a condition and returns from the calling function
+            #define CHECK_RETURN(Condition, ...) \
+                if (!(Condition)) { return __VA_ARGS__; }
+
+            class FInventory {};
+            "#;
+
+        let (_, header) = CppHeader::parse(input).unwrap();
+        assert_eq!(
+            header
+                .macros
+                .iter()
+                .map(|define| (
+                    define.name,
+                    define.comment.as_ref().map(|c| c.comment.as_str())
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("QUICKBAR_SLOTS", Some("Number of slots in the quick bar")),
+                (
+                    "CHECK_RETURN",
+                    Some("Checks a condition and returns from the calling function")
+                ),
+            ]
+        );
+        assert_eq!(
+            header.macros[1].body,
+            "if (!(Condition)) { return __VA_ARGS__; }"
+        );
+        assert!(header.comments.is_empty());
+        assert_eq!(header.classes.len(), 1);
+    }
+
+    #[test]
+    fn test_header_with_ignore_rules() {
+        let input = r#"#pragma once
+
+            DECLARE_DYNAMIC_MULTICAST_DELEGATE_OneParam(FOnScoreChanged, int32, Score);
+
+            namespace Game
+            {
+                MYGAME_NAMESPACE_STATS(Game)
+
+                class FScore
+                {
+                    GENERATED_BODY()
+                    MYGAME_BODY_END;
+                public:
+                    int32 Value;
+                };
+            }
+            "#;
+        let config = r#"
+            macro MYGAME_NAMESPACE_STATS
+            literal MYGAME_BODY_END
+            regex DECLARE_\w+_DELEGATE\w*\([^)]*\)
+            "#;
+        let rules = IgnoreRules::unreal().extend(IgnoreRules::from_config(config).unwrap());
+
+        assert!(CppHeader::parse(input).is_err());
+
+        let (rest, header): (_, CppHeader) = CppHeader::parse_with_rules(input, &rules).unwrap();
+        assert_eq!(rest, "");
+        assert!(header.functions.is_empty());
+        assert_eq!(header.namespaces[0].name, "Game");
+        let class = &header.namespaces[0].classes[0];
+        assert_eq!(class.name, "FScore");
+        assert_eq!(
+            class.members[&InheritanceVisibility::Public][0].name,
+            "Value"
+        );
+    }
+
+    #[test]
+    fn test_header_saved_as_utf16_with_crlf() {
+        let text = "#pragma once\r\n\r\nclass FInventory {};\r\n// End of the inventory\r\n";
+        let bytes = [0xFF, 0xFE]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect::<Vec<_>>();
+        let source = SourceText::decode(&bytes).unwrap();
+        assert_eq!(source.encoding, SourceEncoding::Utf16Le);
+
+        let (_, header) = CppHeader::parse(&source.text).unwrap();
+        assert_eq!(header.classes[0].name, "FInventory");
+        assert_eq!(
+            header.comments,
+            vec![CppComment {
+                comment: "End of the inventory".to_string()
+            }]
+        );
     }
 }
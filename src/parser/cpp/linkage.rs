@@ -0,0 +1,178 @@
+﻿use crate::parser::cpp::alias::CppAlias;
+use crate::parser::cpp::cenum::CppEnum;
+use crate::parser::cpp::class::CppClass;
+use crate::parser::cpp::comment::CppComment;
+use crate::parser::cpp::member::CppMember;
+use crate::parser::cpp::method::CppFunction;
+use crate::parser::cpp::namespace::CppNamespace;
+use crate::parser::cpp::using::CppUsing;
+use crate::parser::generic::ignore::IgnoreRules;
+use crate::parser::generic::namespace::parse_linkage_specification;
+use nom::IResult;
+use nom_language::error::VerboseError;
+
+/// A linkage specification like `extern "C" { ... }`. Single declarations such as
+/// `extern "C" void Foo();` are stored as a block with one entry.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CppLinkageBlock<'a> {
+    /// The language in the string literal, `C` or `C++`
+    pub linkage: &'a str,
+    pub namespaces: Vec<CppNamespace<'a>>,
+    pub classes: Vec<CppClass<'a>>,
+    pub functions: Vec<CppFunction<'a>>,
+    pub variables: Vec<CppMember<'a>>,
+    pub aliases: Vec<CppAlias<'a>>,
+    pub enums: Vec<CppEnum<'a>>,
+    pub usings: Vec<CppUsing<'a>>,
+    pub comments: Vec<CppComment>,
+    /// Nested blocks like `extern "C++"` in an `extern "C"` block
+    pub linkage_blocks: Vec<CppLinkageBlock<'a>>,
+}
+
+impl<'a> CppLinkageBlock<'a> {
+    /// The declarations of the block as collected by [`parse_linkage_specification`]
+    pub(crate) fn new(linkage: &'a str, body: CppNamespace<'a>) -> CppLinkageBlock<'a> {
+        CppLinkageBlock {
+            linkage,
+            namespaces: body.namespaces,
+            classes: body.classes,
+            functions: body.functions,
+            variables: body.variables,
+            aliases: body.aliases,
+            enums: body.enums,
+            usings: body.usings,
+            comments: body.comments,
+            linkage_blocks: body.linkage_blocks,
+        }
+    }
+}
+
+/// The block body accepts everything a namespace body does, statements matching `ignore_rules`
+/// are skipped
+pub fn parse_linkage_block<'a>(
+    input: &'a str,
+    ignore_rules: &IgnoreRules,
+) -> IResult<&'a str, CppLinkageBlock<'a>, VerboseError<&'a str>> {
+    let (input, (linkage, body)) =
+        parse_linkage_specification::<CppNamespace, CppClass>(input, ignore_rules)?;
+
+    Ok((input, CppLinkageBlock::new(linkage, body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::cpp::ctype::CType;
+    use crate::parser::cpp::linkage::{CppLinkageBlock, parse_linkage_block};
+    use crate::parser::cpp::member::{CppMember, CppMemberModifier};
+    use crate::parser::cpp::method::{CppFunction, CppMethodParam};
+    use crate::parser::cpp::namespace::CppNamespace;
+    use crate::parser::generic::ignore::IgnoreRules;
+    use crate::parser::generic::namespace::parse_namespace;
+
+    #[test]
+    fn extern_c_block() {
+        let input = r#"extern "C" {
+            #endif
+            int zlib_compress(const char* Source, int Length);
+            extern int GCompressionLevel;
+        }"#;
+
+        let expected = CppLinkageBlock {
+            linkage: "C",
+            functions: vec![CppFunction {
+                name: "zlib_compress",
+                return_type: Some(CType::Path(vec!["int"])),
+                params: vec![
+                    CppMethodParam {
+                        name: Some("Source"),
+                        ctype: CType::Pointer(Box::new(CType::Const(Box::new(CType::Path(
+                            vec!["char"],
+                        ))))),
+                        default_value: None,
+                        attributes: vec![],
+                    },
+                    CppMethodParam {
+                        name: Some("Length"),
+                        ctype: CType::Path(vec!["int"]),
+                        default_value: None,
+                        attributes: vec![],
+                    },
+                ],
+                ..Default::default()
+            }],
+            variables: vec![CppMember {
+                name: "GCompressionLevel",
+                ctype: CType::Path(vec!["int"]),
+                modifiers: vec![CppMemberModifier::Extern],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_linkage_block(input, &IgnoreRules::default()),
+            Ok(("", expected))
+        );
+    }
+
+    #[test]
+    fn extern_c_single_declaration() {
+        let input = r#"extern "C" void Shutdown();"#;
+
+        let expected = CppLinkageBlock {
+            linkage: "C",
+            functions: vec![CppFunction {
+                name: "Shutdown",
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_linkage_block(input, &IgnoreRules::default()),
+            Ok(("", expected))
+        );
+    }
+
+    #[test]
+    fn extern_c_block_with_type_declarations() {
+        let input = r#"extern "C" {
+            typedef struct z_stream_s { int avail_in; } z_stream;
+            enum z_flush { Z_NO_FLUSH, Z_FINISH = 4 };
+            struct gz_state;
+            int deflate(z_stream* strm, int flush);
+        }"#;
+
+        let (rest, block) = parse_linkage_block(input, &IgnoreRules::default()).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(block.aliases[0].name, "z_stream");
+        assert_eq!(block.enums[0].variants[1].value, Some(4));
+        assert_eq!(block.classes[0].name, "gz_state");
+        assert_eq!(block.functions[0].name, "deflate");
+    }
+
+    #[test]
+    fn extern_c_block_in_namespace() {
+        let input = r#"namespace zlib {
+            extern "C" {
+                struct z_stream_s { int avail_in; };
+                int deflateEnd(z_stream_s* strm);
+            }
+            extern "C" int inflateEnd(z_stream_s* strm);
+        }"#;
+
+        let (rest, namespace) =
+            parse_namespace::<CppNamespace, _>(input, &IgnoreRules::default()).unwrap();
+        assert_eq!(rest, "");
+        assert!(namespace.classes.is_empty());
+        assert!(namespace.functions.is_empty());
+
+        let blocks = &namespace.linkage_blocks;
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].linkage, "C");
+        assert_eq!(blocks[0].classes[0].name, "z_stream_s");
+        assert_eq!(blocks[0].functions[0].name, "deflateEnd");
+        assert_eq!(blocks[1].linkage, "C");
+        assert_eq!(blocks[1].functions[0].name, "inflateEnd");
+    }
+}
@@ -0,0 +1,433 @@
+﻿use crate::parser::cpp::attribute::parenthesized;
+use std::collections::HashMap;
+
+/// What happens to a macro before parsing
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroExpansion {
+    /// Removed, e.g. `FORCENOINLINE`
+    Drop,
+    /// Replaced by fixed text, e.g. `FORCEINLINE` by `inline`
+    Replace(String),
+    /// Turned into an attribute, e.g. `UE_NODISCARD` into `[[nodiscard]]`. `argument` picks one
+    /// argument of a function-like macro, `UE_DEPRECATED(5.1, "Use Bar")` becomes
+    /// `[[deprecated("Use Bar")]]` with `Some(1)`. With `None` all arguments are kept.
+    Attribute {
+        name: String,
+        argument: Option<usize>,
+    },
+    /// Turned into an attribute named `api` carrying the macro name, `CORE_API` becomes
+    /// `[[api(CORE_API)]]`. The class parser stores it as the API of the class.
+    Api,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct MacroEntry {
+    function_like: bool,
+    expansion: MacroExpansion,
+}
+
+/// Macros in positions where the parser expects keywords, like API, inline or deprecation macros
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MacroTable {
+    entries: HashMap<String, MacroEntry>,
+    /// Object-like macros matched by a pattern like `*_API`, as prefix and suffix around the `*`
+    patterns: Vec<(String, String, MacroExpansion)>,
+}
+
+impl MacroTable {
+    /// Common engine macros and module API macros like `CORE_API`
+    pub fn unreal() -> Self {
+        let deprecated = MacroExpansion::Attribute {
+            name: "deprecated".to_string(),
+            argument: Some(1),
+        };
+
+        MacroTable::default()
+            .object("FORCEINLINE", MacroExpansion::Replace("inline".to_string()))
+            .object(
+                "FORCEINLINE_DEBUGGABLE",
+                MacroExpansion::Replace("inline".to_string()),
+            )
+            .object("FORCENOINLINE", MacroExpansion::Drop)
+            .object(
+                "CONSTEXPR",
+                MacroExpansion::Replace("constexpr".to_string()),
+            )
+            .object(
+                "UE_NODISCARD",
+                MacroExpansion::Attribute {
+                    name: "nodiscard".to_string(),
+                    argument: None,
+                },
+            )
+            .function("UE_DEPRECATED", deprecated.clone())
+            .function("UE_DEPRECATED_FORGAME", deprecated.clone())
+            .function("DEPRECATED_FORGAME", deprecated)
+            .pattern("*_API", MacroExpansion::Api)
+    }
+
+    /// Adds a macro that is used without arguments
+    pub fn object(mut self, name: &str, expansion: MacroExpansion) -> Self {
+        self.entries.insert(
+            name.to_string(),
+            MacroEntry {
+                function_like: false,
+                expansion,
+            },
+        );
+        self
+    }
+
+    /// Adds a macro that is only expanded if it is invoked with arguments
+    pub fn function(mut self, name: &str, expansion: MacroExpansion) -> Self {
+        self.entries.insert(
+            name.to_string(),
+            MacroEntry {
+                function_like: true,
+                expansion,
+            },
+        );
+        self
+    }
+
+    /// Adds object-like macros whose names match `pattern`, a name with one `*` standing for
+    /// any non-empty text like `*_API`. Macros added by name take precedence.
+    pub fn pattern(mut self, pattern: &str, expansion: MacroExpansion) -> Self {
+        let (prefix, suffix) = pattern.split_once('*').unwrap_or((pattern, ""));
+        self.patterns
+            .push((prefix.to_string(), suffix.to_string(), expansion));
+        self
+    }
+
+    fn entry(&self, name: &str) -> Option<MacroEntry> {
+        if let Some(entry) = self.entries.get(name) {
+            return Some(entry.clone());
+        }
+
+        self.patterns
+            .iter()
+            .find(|(prefix, suffix, _)| {
+                name.len() > prefix.len() + suffix.len()
+                    && name.starts_with(prefix.as_str())
+                    && name.ends_with(suffix.as_str())
+            })
+            .map(|(_, _, expansion)| MacroEntry {
+                function_like: false,
+                expansion: expansion.clone(),
+            })
+    }
+
+    /// Whether `name` is a function-like macro whose argument list is opened in `after` but not
+    /// closed, i.e. continues on the next line
+    fn is_unterminated(&self, name: &str, after: &str) -> bool {
+        let trimmed = after.trim_start();
+        self.entry(name).is_some_and(|entry| entry.function_like)
+            && trimmed.starts_with('(')
+            && parenthesized(trimmed).is_err()
+    }
+
+    /// Expansion of `name` and the number of bytes of `after` it consumed for the arguments.
+    /// Line breaks inside the arguments are kept so lines don't move.
+    fn expand_macro(&self, name: &str, after: &str) -> Option<(String, usize)> {
+        let entry = self.entry(name)?;
+
+        let (arguments, consumed) = match entry.function_like {
+            true => {
+                let trimmed = after.trim_start();
+                let (rest, arguments) = parenthesized(trimmed).ok()?;
+                (Some(arguments), after.len() - rest.len())
+            }
+            false => (None, 0),
+        };
+        let line_breaks = "\n".repeat(after[..consumed].matches('\n').count());
+        let arguments = arguments.map(|arguments| arguments.replace(['\r', '\n'], " "));
+
+        let expanded = match &entry.expansion {
+            MacroExpansion::Drop => {
+                let arguments: String = after[..consumed]
+                    .bytes()
+                    .map(|byte| match byte {
+                        b'\n' | b'\r' => byte as char,
+                        _ => ' ',
+                    })
+                    .collect();
+                " ".repeat(name.len()) + &arguments
+            }
+            MacroExpansion::Replace(text) => format!("{text}{line_breaks}"),
+            MacroExpansion::Api => format!("[[api({name})]]"),
+            MacroExpansion::Attribute { name, argument } => {
+                let arguments = match (arguments.as_deref(), argument) {
+                    (Some(arguments), Some(index)) => {
+                        split_arguments(arguments).get(*index).copied()
+                    }
+                    (arguments, _) => arguments,
+                };
+                match arguments.filter(|arguments| !arguments.is_empty()) {
+                    Some(arguments) => format!("[[{name}({arguments})]]{line_breaks}"),
+                    None => format!("[[{name}]]{line_breaks}"),
+                }
+            }
+        };
+
+        Some((expanded, consumed))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.patterns.is_empty()
+    }
+
+    /// Applies the table to `source`. Comments, string literals and preprocessor directives are
+    /// kept as they are. Dropped macros are replaced by spaces so offsets don't move.
+    pub fn expand(&self, source: &str) -> String {
+        let mut expander = Expander::default();
+        let mut result = String::with_capacity(source.len());
+        for line in source.split_inclusive('\n') {
+            expander.expand_line(self, line, &mut result);
+        }
+        expander.finish(&mut result);
+
+        result
+    }
+}
+
+/// Lines a macro invocation may span before it is left unexpanded
+const MAX_INVOCATION_LINES: usize = 32;
+
+/// Keeps track of block comments and macro invocations spanning several lines
+#[derive(Default)]
+pub(crate) struct Expander {
+    in_comment: bool,
+    /// Macro invocation whose argument list isn't closed yet, from the macro name on
+    pending: String,
+}
+
+impl Expander {
+    pub(crate) fn expand_line(&mut self, table: &MacroTable, line: &str, result: &mut String) {
+        if self.pending.is_empty() && !self.in_comment && line.trim_start().starts_with('#') {
+            result.push_str(line);
+            return;
+        }
+
+        let mut line = line;
+        let joined;
+        if !self.pending.is_empty() {
+            self.pending.push_str(line);
+            joined = std::mem::take(&mut self.pending);
+            line = &joined;
+        }
+
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            let end = match c {
+                _ if self.in_comment => match rest.find("*/") {
+                    Some(end) => {
+                        self.in_comment = false;
+                        end + 2
+                    }
+                    None => rest.len(),
+                },
+                '/' if rest.starts_with("//") => rest.find('\n').unwrap_or(rest.len()),
+                '/' if rest.starts_with("/*") => {
+                    self.in_comment = true;
+                    2
+                }
+                '"' | '\'' => literal_end(rest, c),
+                _ if c.is_alphabetic() || c == '_' => {
+                    let end = rest
+                        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .unwrap_or(rest.len());
+                    if table.is_unterminated(&rest[..end], &rest[end..])
+                        && rest.lines().count() < MAX_INVOCATION_LINES
+                    {
+                        self.pending.push_str(rest);
+                        return;
+                    }
+                    match table.expand_macro(&rest[..end], &rest[end..]) {
+                        Some((expanded, consumed)) => {
+                            result.push_str(&expanded);
+                            rest = &rest[end + consumed..];
+                            continue;
+                        }
+                        None => end,
+                    }
+                }
+                _ => c.len_utf8(),
+            };
+
+            result.push_str(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+
+    /// Writes an invocation that is still waiting for its closing parenthesis unexpanded
+    pub(crate) fn finish(&mut self, result: &mut String) {
+        result.push_str(&std::mem::take(&mut self.pending));
+    }
+}
+
+/// Length of the string or character literal at the start of `input`
+fn literal_end(input: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in input.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if c == quote => return i + 1,
+            _ => {}
+        }
+    }
+
+    input.len()
+}
+
+/// Splits macro arguments at top level commas
+fn split_arguments(arguments: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in arguments.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    result.push(arguments[start..i].trim());
+                    start = i + 1;
+                }
+                _ => {}
+            },
+        }
+    }
+    result.push(arguments[start..].trim());
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::cpp::attribute::Attributed;
+    use crate::parser::cpp::class::CppClass;
+    use crate::parser::cpp::macro_table::{MacroExpansion, MacroTable};
+    use crate::parser::generic::class::{InheritanceVisibility, parse_class};
+    use crate::parser::generic::ignore::IgnoreRules;
+    use crate::parser::generic::method::CppStorageQualifier;
+
+    #[test]
+    fn unreal_macros() {
+        let table = MacroTable::unreal();
+
+        assert_eq!(
+            table.expand("FORCEINLINE UE_NODISCARD int32 Num() const;"),
+            "inline [[nodiscard]] int32 Num() const;"
+        );
+        assert_eq!(
+            table.expand("UE_DEPRECATED(5.1, \"Use GetNum, it's faster\") int32 Count;"),
+            "[[deprecated(\"Use GetNum, it's faster\")]] int32 Count;"
+        );
+        assert_eq!(
+            table.expand("FORCENOINLINE void Crash();"),
+            "              void Crash();"
+        );
+        // function-like macros need arguments
+        assert_eq!(table.expand("int32 UE_DEPRECATED;"), "int32 UE_DEPRECATED;");
+    }
+
+    #[test]
+    fn comments_literals_and_directives_are_kept() {
+        let table = MacroTable::default().object("MYGAME_INLINE", MacroExpansion::Drop);
+
+        let input = r#"/* MYGAME_INLINE
+   MYGAME_INLINE */ MYGAME_INLINE void A(); // MYGAME_INLINE
+#define MYGAME_INLINE inline
+const TCHAR* B = TEXT("MYGAME_INLINE");"#;
+        let expected = r#"/* MYGAME_INLINE
+   MYGAME_INLINE */               void A(); // MYGAME_INLINE
+#define MYGAME_INLINE inline
+const TCHAR* B = TEXT("MYGAME_INLINE");"#;
+
+        assert_eq!(table.expand(input), expected);
+    }
+
+    #[test]
+    fn arguments_spanning_lines() {
+        let table = MacroTable::unreal();
+
+        assert_eq!(
+            table.expand("UE_DEPRECATED(5.1,\n    \"Use X\")\nvoid Y();"),
+            "[[deprecated(\"Use X\")]]\n\nvoid Y();"
+        );
+        assert_eq!(
+            MacroTable::default()
+                .function("MYGAME_HIDDEN", MacroExpansion::Drop)
+                .expand("MYGAME_HIDDEN(A,\n B) int32 C;"),
+            "                \n    int32 C;"
+        );
+        // never closed
+        assert_eq!(table.expand("UE_DEPRECATED(5.1,\n"), "UE_DEPRECATED(5.1,\n");
+    }
+
+    #[test]
+    fn api_macros() {
+        let input = r#"class CORE_API FArchive
+{
+public:
+    static MYMODULE_API int32 Count;
+    MYMODULE_API virtual void Serialize();
+};"#;
+        let text = MacroTable::unreal().expand(input);
+        let (_, class) = parse_class::<CppClass>(&text, &IgnoreRules::default()).unwrap();
+
+        assert_eq!(class.name, "FArchive");
+        assert_eq!(class.api, Some("CORE_API"));
+        assert!(class.attributes.is_empty());
+
+        let member = &class.members[&InheritanceVisibility::Public][0];
+        assert_eq!(member.name, "Count");
+        assert_eq!(member.attributes[0].arguments, Some("MYMODULE_API"));
+
+        let method = &class.methods[&InheritanceVisibility::Public][0];
+        assert_eq!(method.name, "Serialize");
+        assert_eq!(method.attributes[0].name, "api");
+    }
+
+    #[test]
+    fn attribute_with_all_arguments() {
+        let table = MacroTable::default().function(
+            "MYGAME_ALIGN",
+            MacroExpansion::Attribute {
+                name: "gnu::aligned".to_string(),
+                argument: None,
+            },
+        );
+
+        assert_eq!(
+            table.expand("struct MYGAME_ALIGN (16) FVector4 {};"),
+            "struct [[gnu::aligned(16)]] FVector4 {};"
+        );
+    }
+
+    #[test]
+    fn expanded_declarations_parse() {
+        let input = r#"class UE_DEPRECATED(5.0, "Use FNewThing") FOldThing
+{
+public:
+    FORCEINLINE UE_NODISCARD int32 Num() const { return Count; }
+};"#;
+        let text = MacroTable::unreal().expand(input);
+        let (_, class) = parse_class::<CppClass>(&text, &IgnoreRules::default()).unwrap();
+
+        assert_eq!(class.name, "FOldThing");
+        assert_eq!(class.deprecation_message(), Some("Use FNewThing"));
+
+        let method = &class.methods[&InheritanceVisibility::Public][0];
+        assert_eq!(method.storage_qualifiers, vec![CppStorageQualifier::Inline]);
+        assert_eq!(method.attributes[0].name, "nodiscard");
+    }
+}
@@ -1,5 +1,7 @@
-﻿use crate::parser::cpp::comment::CppComment;
+﻿use crate::parser::cpp::attribute::{Attributed, CppAttribute};
+use crate::parser::cpp::comment::CppComment;
 use crate::parser::cpp::ctype::CType;
+use crate::parser::cpp::expression::{CppExpression, Literal};
 use crate::parser::generic::annotation::NoAnnotation;
 use crate::parser::generic::member::Member;
 
@@ -7,9 +9,10 @@ use crate::parser::generic::member::Member;
 pub struct CppMember<'a> {
     pub name: &'a str,
     pub ctype: CType<'a>,
-    pub default_value: Option<CType<'a>>,
+    pub default_value: Option<CppExpression<'a>>,
     pub comment: Option<CppComment>,
     pub modifiers: Vec<CppMemberModifier>,
+    pub attributes: Vec<CppAttribute<'a>>,
 }
 
 impl<'a> Member<'a> for CppMember<'a> {
@@ -19,9 +22,10 @@ impl<'a> Member<'a> for CppMember<'a> {
     fn member(
         name: &'a str,
         ctype: CType<'a>,
-        default_value: Option<CType<'a>>,
+        default_value: Option<CppExpression<'a>>,
         comment: Option<CppComment>,
         modifiers: Vec<CppMemberModifier>,
+        attributes: Vec<CppAttribute<'a>>,
         _annotations: Vec<NoAnnotation>,
     ) -> Self
     where
@@ -33,24 +37,50 @@ impl<'a> Member<'a> for CppMember<'a> {
             default_value,
             comment,
             modifiers,
+            attributes,
         }
     }
 }
 
+impl CppMember<'_> {
+    /// Value of a constant or variable initialized with a literal, e.g. `constexpr int32 Max = 8;`
+    /// or `constexpr int32 Max{8};`
+    pub fn value(&self) -> Option<Literal> {
+        match self.default_value.as_ref()? {
+            CppExpression::InitList(None, items) if items.len() == 1 => items[0].literal(),
+            default_value => default_value.literal(),
+        }
+    }
+}
+
+impl<'a> Attributed<'a> for CppMember<'a> {
+    fn attributes(&self) -> &[CppAttribute<'a>] {
+        &self.attributes
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
 
 pub enum CppMemberModifier {
     Static,
     Const,
     Inline,
+    Constexpr,
+    Extern,
+    ThreadLocal,
+    Mutable,
 }
 
-impl Into<String> for CppMemberModifier {
-    fn into(self) -> String {
-        match self {
+impl From<CppMemberModifier> for String {
+    fn from(val: CppMemberModifier) -> Self {
+        match val {
             CppMemberModifier::Static => "static".to_string(),
             CppMemberModifier::Const => "const".to_string(),
             CppMemberModifier::Inline => "inline".to_string(),
+            CppMemberModifier::Constexpr => "constexpr".to_string(),
+            CppMemberModifier::Extern => "extern".to_string(),
+            CppMemberModifier::ThreadLocal => "thread_local".to_string(),
+            CppMemberModifier::Mutable => "mutable".to_string(),
         }
     }
 }
@@ -60,6 +90,10 @@ impl From<&str> for CppMemberModifier {
             "static" => CppMemberModifier::Static,
             "const" => CppMemberModifier::Const,
             "inline" => CppMemberModifier::Inline,
+            "constexpr" => CppMemberModifier::Constexpr,
+            "extern" => CppMemberModifier::Extern,
+            "thread_local" => CppMemberModifier::ThreadLocal,
+            "mutable" => CppMemberModifier::Mutable,
             _ => unimplemented!(),
         }
     }
@@ -67,9 +101,11 @@ impl From<&str> for CppMemberModifier {
 
 #[cfg(test)]
 mod tests {
-    use crate::parser::cpp::ctype::CType::Path;
+    use crate::parser::cpp::comment::CppComment;
+    use crate::parser::cpp::ctype::CType::{Array, Path, Pointer, Reference};
+    use crate::parser::cpp::expression::{CppExpression, Literal};
     use crate::parser::cpp::member::{CppMember, CppMemberModifier};
-    use crate::parser::generic::member::parse_member;
+    use crate::parser::generic::member::{parse_member, parse_members};
 
     #[test]
     fn test_cpp_member_without_default_value() {
@@ -89,7 +125,7 @@ mod tests {
 
     #[test]
     fn test_cpp_member_with_modifier() {
-        for modifier in vec!["static", "const", "inline"] {
+        for modifier in ["static", "const", "inline"] {
             let input = format!("{} int member", modifier);
             assert_eq!(
                 parse_member(&input),
@@ -108,7 +144,13 @@ mod tests {
 
     #[test]
     fn test_cpp_member_with_default_value() {
-        for input in ["int member = 0", "int member {0}"] {
+        for (input, default_value) in [
+            ("int member = 0", CppExpression::Literal("0")),
+            (
+                "int member {0}",
+                CppExpression::InitList(None, vec![CppExpression::Literal("0")]),
+            ),
+        ] {
             assert_eq!(
                 parse_member(input),
                 Ok((
@@ -116,11 +158,222 @@ mod tests {
                     CppMember {
                         name: "member",
                         ctype: Path(vec!["int"]),
-                        default_value: Some(Path(vec!["0"])),
+                        default_value: Some(default_value),
                         ..Default::default()
                     }
                 ))
             );
         }
     }
+
+    #[test]
+    fn test_cpp_members_with_multiple_declarators() {
+        let input = "int32 X, Y, Z";
+        assert_eq!(
+            parse_members(input),
+            Ok((
+                "",
+                vec![
+                    CppMember {
+                        name: "X",
+                        ctype: Path(vec!["int32"]),
+                        ..Default::default()
+                    },
+                    CppMember {
+                        name: "Y",
+                        ctype: Path(vec!["int32"]),
+                        ..Default::default()
+                    },
+                    CppMember {
+                        name: "Z",
+                        ctype: Path(vec!["int32"]),
+                        ..Default::default()
+                    }
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_cpp_members_share_modifiers_and_comment() {
+        let input = "// Scale factors\nstatic float A = 1.f, B = 2.f";
+        let comment = Some(CppComment {
+            comment: "Scale factors".to_string(),
+        });
+
+        assert_eq!(
+            parse_members(input),
+            Ok((
+                "",
+                vec![
+                    CppMember {
+                        name: "A",
+                        ctype: Path(vec!["float"]),
+                        default_value: Some(CppExpression::Literal("1.f")),
+                        comment: comment.clone(),
+                        modifiers: vec![CppMemberModifier::Static],
+                        attributes: vec![],
+                    },
+                    CppMember {
+                        name: "B",
+                        ctype: Path(vec!["float"]),
+                        default_value: Some(CppExpression::Literal("2.f")),
+                        comment,
+                        modifiers: vec![CppMemberModifier::Static],
+                        attributes: vec![],
+                    }
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_cpp_members_with_pointer_declarators() {
+        let pointer = |name| CppMember {
+            name,
+            ctype: Pointer(Box::new(Path(vec!["int32"]))),
+            ..Default::default()
+        };
+        let plain = |name| CppMember {
+            name,
+            ctype: Path(vec!["int32"]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            parse_members("int32* A, B"),
+            Ok(("", vec![pointer("A"), plain("B")]))
+        );
+        assert_eq!(
+            parse_members("int32 *A, *B"),
+            Ok(("", vec![pointer("A"), pointer("B")]))
+        );
+        assert_eq!(
+            parse_members("int32 A, &B"),
+            Ok((
+                "",
+                vec![
+                    plain("A"),
+                    CppMember {
+                        name: "B",
+                        ctype: Reference(Box::new(Path(vec!["int32"]))),
+                        ..Default::default()
+                    }
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_cpp_member_arrays() {
+        let input = "float M[4][4], Row[]";
+        assert_eq!(
+            parse_members(input),
+            Ok((
+                "",
+                vec![
+                    CppMember {
+                        name: "M",
+                        ctype: Array(
+                            Box::new(Array(Box::new(Path(vec!["float"])), Some("4"))),
+                            Some("4")
+                        ),
+                        ..Default::default()
+                    },
+                    CppMember {
+                        name: "Row",
+                        ctype: Array(Box::new(Path(vec!["float"])), None),
+                        ..Default::default()
+                    }
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_cpp_member_storage_modifiers() {
+        let input = "static thread_local mutable int32 Counter";
+        assert_eq!(
+            parse_member(input),
+            Ok((
+                "",
+                CppMember {
+                    name: "Counter",
+                    ctype: Path(vec!["int32"]),
+                    modifiers: vec![
+                        CppMemberModifier::Static,
+                        CppMemberModifier::ThreadLocal,
+                        CppMemberModifier::Mutable
+                    ],
+                    ..Default::default()
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_cpp_member_expression_defaults() {
+        let defaults = |input| parse_member::<CppMember>(input).unwrap().1.default_value;
+
+        assert_eq!(
+            defaults("UObject* Owner = nullptr"),
+            Some(CppExpression::Literal("nullptr"))
+        );
+        assert_eq!(
+            defaults("TArray<int32> Values = {1, 2, 3}")
+                .unwrap()
+                .to_string(),
+            "{1, 2, 3}"
+        );
+        assert_eq!(
+            defaults("EMode Mode = EMode::A | EMode::B")
+                .unwrap()
+                .to_string(),
+            "EMode::A | EMode::B"
+        );
+        assert_eq!(
+            defaults("FVector Location{FVector::ZeroVector}")
+                .unwrap()
+                .to_string(),
+            "{FVector::ZeroVector}"
+        );
+    }
+
+    #[test]
+    fn test_cpp_member_literal_values() {
+        let (_, constants) =
+            parse_members::<CppMember>(r#"static constexpr float A = 1.f, B = -2.5, C = 1e3"#)
+                .unwrap();
+        assert_eq!(
+            constants[0].modifiers,
+            vec![CppMemberModifier::Static, CppMemberModifier::Constexpr]
+        );
+        assert_eq!(
+            constants.iter().map(CppMember::value).collect::<Vec<_>>(),
+            vec![
+                Some(Literal::Flt(1.0)),
+                Some(Literal::Flt(-2.5)),
+                Some(Literal::Flt(1000.0))
+            ]
+        );
+
+        let value = |input| parse_member::<CppMember>(input).unwrap().1.value();
+        assert_eq!(
+            value("const auto A = \"hello\\tworld\""),
+            Some(Literal::Str("hello\tworld".to_string()))
+        );
+        assert_eq!(
+            value("const static FName B = TEXT(\"Name\")"),
+            Some(Literal::Str("Name".to_string()))
+        );
+        assert_eq!(
+            value("const TCHAR* C = R\"(C:\\Temp)\""),
+            Some(Literal::Str("C:\\Temp".to_string()))
+        );
+        assert_eq!(value("constexpr TCHAR D = 'x'"), Some(Literal::Char('x')));
+        assert_eq!(value("static const int32 E = 0x10"), Some(Literal::Int(16)));
+        assert_eq!(value("inline bool F = true"), Some(Literal::Bool(true)));
+        assert_eq!(value("constexpr int32 H{8}"), Some(Literal::Int(8)));
+        assert_eq!(value("static FVector G = FVector::ZeroVector"), None);
+    }
 }
@@ -1,9 +1,13 @@
-﻿use crate::parser::cpp::comment::CppComment;
+﻿use crate::parser::cpp::attribute::{Attributed, CppAttribute, parse_attributes};
+use crate::parser::cpp::comment::CppComment;
 use crate::parser::cpp::ctype::{CType, parse_cpp_type};
+use crate::parser::cpp::expression::{
+    CppExpression, call_arguments, init_list, parse_expression, qualified_name,
+};
 use crate::parser::generic::annotation::NoAnnotation;
 
 use crate::parser::generic::method::{
-    CppStorageQualifier, Method, PostParamQualifier, SpecialMember,
+    CppStorageQualifier, Method, MethodDefinition, PostParamQualifier, SpecialMember,
 };
 use crate::parser::{parse_str, parse_ws_str, ws};
 use nom::branch::alt;
@@ -14,6 +18,7 @@ use nom::multi::{many0, separated_list0};
 use nom::sequence::{delimited, preceded, terminated};
 use nom::{IResult, Parser};
 use nom_language::error::VerboseError;
+use std::ops::Range;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct CppFunction<'a> {
@@ -24,37 +29,197 @@ pub struct CppFunction<'a> {
     pub storage_qualifiers: Vec<CppStorageQualifier>,
     pub post_param_qualifiers: Vec<PostParamQualifier>,
     pub special: Option<SpecialMember>,
+    /// Constructor initializers like `: Super(ObjectInitializer), Health(100.f)`
+    pub initializers: Vec<CppMemberInitializer<'a>>,
+    /// Inline definition including its braces, e.g. `{ return Health; }`
+    pub body: Option<&'a str>,
     pub comment: Option<CppComment>,
+    pub attributes: Vec<CppAttribute<'a>>,
+    /// Constructors and copy or move operations are only recognized in the body of their class
+    pub kind: FunctionKind,
 }
 
 impl<'a> Method<'a> for CppFunction<'a> {
     type MethodAnnotation = NoAnnotation;
     type Comment = CppComment;
 
-    fn method(
-        name: &'a str,
-        return_type: Option<CType<'a>>,
-        template_params: Vec<CType<'a>>,
-        params: Vec<CppMethodParam<'a>>,
-        storage_qualifiers: Vec<CppStorageQualifier>,
-        post_param_qualifiers: Vec<PostParamQualifier>,
-        special: Option<SpecialMember>,
-        comment: Option<CppComment>,
-        _: Vec<NoAnnotation>,
-    ) -> Self {
-        CppFunction {
-            name,
-            return_type,
-            template_params,
-            params,
-            storage_qualifiers,
-            post_param_qualifiers,
-            special,
-            comment,
+    fn method(definition: MethodDefinition<'a, Self>) -> Self {
+        let mut function = CppFunction {
+            name: definition.name,
+            return_type: definition.return_type,
+            template_params: definition.template_params,
+            params: definition.params,
+            storage_qualifiers: definition.storage_qualifiers,
+            post_param_qualifiers: definition.post_param_qualifiers,
+            special: definition.special,
+            initializers: definition.initializers,
+            body: definition.body,
+            comment: definition.comment,
+            attributes: definition.attributes,
+            kind: FunctionKind::Regular,
+        };
+        function.kind = function.classify("");
+        function
+    }
+
+    fn declared_in(&mut self, class_name: &str) {
+        self.kind = self.classify(class_name);
+    }
+}
+
+impl<'a> Attributed<'a> for CppFunction<'a> {
+    fn attributes(&self) -> &[CppAttribute<'a>] {
+        &self.attributes
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub enum FunctionKind {
+    #[default]
+    Regular,
+    Constructor,
+    CopyConstructor,
+    MoveConstructor,
+    Destructor,
+    CopyAssignment,
+    MoveAssignment,
+    /// `operator bool()` or `explicit operator FString() const`
+    ConversionOperator,
+    Operator,
+}
+
+/// Groups operator overloads by what they do
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum OperatorKind {
+    /// `+`, `-`, `*`, `/`, `%`, `++` and `--`
+    Arithmetic,
+    /// `~`, `&`, `|`, `^`, `<<` and `>>`
+    Bitwise,
+    /// `!`, `&&` and `||`
+    Logical,
+    /// `==`, `!=`, `<`, `>`, `<=`, `>=` and `<=>`
+    Comparison,
+    /// `=` and compound assignments like `+=`
+    Assignment,
+    Subscript,
+    Call,
+    /// `->` and `->*`
+    MemberAccess,
+    Comma,
+    /// `operator bool()` or `operator const TCHAR*()`
+    Conversion,
+    /// `new`, `new[]`, `delete` and `delete[]`
+    Allocation,
+    /// User-defined literals like `operator""_km`
+    Literal,
+    CoAwait,
+}
+
+impl From<&str> for OperatorKind {
+    fn from(value: &str) -> Self {
+        match value {
+            "+" | "-" | "*" | "/" | "%" | "++" | "--" => OperatorKind::Arithmetic,
+            "~" | "&" | "|" | "^" | "<<" | ">>" => OperatorKind::Bitwise,
+            "!" | "&&" | "||" => OperatorKind::Logical,
+            "==" | "!=" | "<" | ">" | "<=" | ">=" | "<=>" => OperatorKind::Comparison,
+            "=" | "+=" | "-=" | "*=" | "/=" | "%=" | "^=" | "&=" | "|=" | "<<=" | ">>=" => {
+                OperatorKind::Assignment
+            }
+            "[]" => OperatorKind::Subscript,
+            "()" => OperatorKind::Call,
+            "->" | "->*" => OperatorKind::MemberAccess,
+            "," => OperatorKind::Comma,
+            "co_await" => OperatorKind::CoAwait,
+            _ if value.starts_with("new") || value.starts_with("delete") => {
+                OperatorKind::Allocation
+            }
+            _ if value.starts_with("\"\"") => OperatorKind::Literal,
+            _ => OperatorKind::Conversion,
         }
     }
 }
 
+impl CppFunction<'_> {
+    /// Byte range of the inline body within `source`, the text the function was parsed from.
+    pub fn body_span(&self, source: &str) -> Option<Range<usize>> {
+        let body = self.body?;
+        let start = (body.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+        let end = start + body.len();
+
+        (end <= source.len()).then_some(start..end)
+    }
+
+    pub fn is_operator(&self) -> bool {
+        self.operator_kind().is_some()
+    }
+
+    /// Kind of an operator overload, `None` for other functions
+    pub fn operator_kind(&self) -> Option<OperatorKind> {
+        let operator = self.name.strip_prefix("operator")?;
+        match operator.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+            // a function like `operatorCount`
+            true => None,
+            false => Some(OperatorKind::from(operator.trim())),
+        }
+    }
+
+    /// Classifies the function as a member of `class_name`, an empty name classifies a free
+    /// function that is either a regular function, a destructor or an operator.
+    fn classify(&self, class_name: &str) -> FunctionKind {
+        // the single parameter of copy and move operations, further ones need defaults
+        let source = match &self.params[..] {
+            [first, rest @ ..] if rest.iter().all(|param| param.default_value.is_some()) => {
+                match &first.ctype {
+                    CType::Reference(inner) => match inner.as_ref() {
+                        CType::Reference(moved) if moved.base_name() == Some(class_name) => {
+                            Some(true)
+                        }
+                        copied if copied.base_name() == Some(class_name) => Some(false),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        if self.name.starts_with('~') {
+            return FunctionKind::Destructor;
+        }
+
+        if !class_name.is_empty() && self.name == class_name {
+            return match source {
+                Some(true) => FunctionKind::MoveConstructor,
+                Some(false) => FunctionKind::CopyConstructor,
+                None => FunctionKind::Constructor,
+            };
+        }
+
+        match self.operator_kind() {
+            None => FunctionKind::Regular,
+            Some(OperatorKind::Conversion) => FunctionKind::ConversionOperator,
+            Some(OperatorKind::Assignment) if self.name == "operator=" => {
+                match source {
+                    Some(true) => FunctionKind::MoveAssignment,
+                    Some(false) => FunctionKind::CopyAssignment,
+                    None => FunctionKind::Operator,
+                }
+            }
+            Some(_) => FunctionKind::Operator,
+        }
+    }
+
+    /// True for operator overloads that take `class_name` as one of their operands, which links
+    /// free and `friend` operators to the class documentation
+    pub fn operates_on(&self, class_name: &str) -> bool {
+        self.is_operator()
+            && self
+                .params
+                .iter()
+                .any(|param| param.ctype.base_name() == Some(class_name))
+    }
+}
+
 impl<'a> Default for CppFunction<'a> {
     fn default() -> Self {
         Self {
@@ -65,7 +230,11 @@ impl<'a> Default for CppFunction<'a> {
             storage_qualifiers: vec![],
             post_param_qualifiers: vec![],
             special: None,
+            initializers: vec![],
+            body: None,
             comment: None,
+            attributes: vec![],
+            kind: FunctionKind::Regular,
         }
     }
 }
@@ -74,10 +243,20 @@ impl<'a> Default for CppFunction<'a> {
 pub struct CppMethodParam<'a> {
     pub name: Option<&'a str>,
     pub ctype: CType<'a>,
-    pub default_value: Option<CType<'a>>,
+    pub default_value: Option<CppExpression<'a>>,
+    /// Attributes like `[[maybe_unused]]` in front of the parameter
+    pub attributes: Vec<CppAttribute<'a>>,
+}
+
+impl<'a> Attributed<'a> for CppMethodParam<'a> {
+    fn attributes(&self) -> &[CppAttribute<'a>] {
+        &self.attributes
+    }
 }
 
-fn parse_function_pointer_param(input: &str) -> IResult<&str, CppMethodParam, VerboseError<&str>> {
+fn parse_function_pointer_param(
+    input: &str,
+) -> IResult<&str, CppMethodParam<'_>, VerboseError<&str>> {
     let (input, return_type) = ws(parse_cpp_type).parse(input)?;
     let (input, _) = (multispace0, char('(')).parse(input)?;
     let (input, _) = (multispace0, char('*'), multispace0).parse(input)?;
@@ -96,18 +275,20 @@ fn parse_function_pointer_param(input: &str) -> IResult<&str, CppMethodParam, Ve
             name: None,
             ctype: CType::Function(Box::from(return_type), params),
             default_value: None,
+            attributes: vec![],
         },
     ))
 }
 
-fn parse_simple_param(input: &str) -> IResult<&str, CppMethodParam, VerboseError<&str>> {
+fn parse_simple_param(input: &str) -> IResult<&str, CppMethodParam<'_>, VerboseError<&str>> {
     let (input, _) = multispace0(input)?;
+    let (input, attributes) = parse_attributes(input)?;
     let (input, ctype) = parse_cpp_type(input)?;
     let (input, name) = opt(parse_ws_str).parse(input)?;
 
     let (input, default_value) = opt(preceded(
         (multispace0, char('='), multispace0),
-        parse_cpp_type,
+        parse_expression,
     ))
     .parse(input)?;
 
@@ -117,16 +298,61 @@ fn parse_simple_param(input: &str) -> IResult<&str, CppMethodParam, VerboseError
             name,
             ctype,
             default_value,
+            attributes,
+        },
+    ))
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct CppMemberInitializer<'a> {
+    /// Member or base class, e.g. `Health` or `Super`
+    pub member: CType<'a>,
+    /// Arguments like `ObjectInitializer, Name` in `Super(ObjectInitializer, Name)`
+    pub arguments: Vec<CppExpression<'a>>,
+    pub style: InitializerStyle,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum InitializerStyle {
+    /// `Health(100.f)`
+    Parenthesized,
+    /// `Health{100.f}`
+    Braced,
+}
+
+pub(crate) fn parse_member_initializer(
+    input: &str,
+) -> IResult<&str, CppMemberInitializer<'_>, VerboseError<&str>> {
+    let (input, member) = qualified_name(input)?;
+    let (input, (style, arguments)) = preceded(
+        multispace0,
+        alt((
+            map(call_arguments, |arguments| {
+                (InitializerStyle::Parenthesized, arguments)
+            }),
+            map(init_list, |arguments| (InitializerStyle::Braced, arguments)),
+        )),
+    )
+    .parse(input)?;
+
+    Ok((
+        input,
+        CppMemberInitializer {
+            member,
+            arguments,
+            style,
         },
     ))
 }
 
-fn parse_cpp_method_param(input: &str) -> IResult<&str, CppMethodParam, VerboseError<&str>> {
+fn parse_cpp_method_param(input: &str) -> IResult<&str, CppMethodParam<'_>, VerboseError<&str>> {
     alt((parse_function_pointer_param, parse_simple_param)).parse(input)
 }
 
-pub fn parse_method_params(input: &str) -> IResult<&str, Vec<CppMethodParam>, VerboseError<&str>> {
-    let (input, _) = (char('('), multispace0).parse(input)?;
+pub fn parse_method_params(
+    input: &str,
+) -> IResult<&str, Vec<CppMethodParam<'_>>, VerboseError<&str>> {
+    let (input, _) = (multispace0, char('('), multispace0).parse(input)?;
 
     let (input, params) = alt((
         map(peek(char(')')), |_| Vec::new()),
@@ -165,10 +391,19 @@ pub fn parse_brace_block(input: &str) -> IResult<&str, &str, VerboseError<&str>>
 
 #[cfg(test)]
 mod tests {
+    use crate::parser::cpp::attribute::{Attributed, CppAttribute};
+    use crate::parser::cpp::class::CppClass;
     use crate::parser::cpp::comment::CppComment;
     use crate::parser::cpp::ctype::CType;
     use crate::parser::cpp::ctype::CType::{Const, Function, Generic, Path, Pointer, Reference};
-    use crate::parser::cpp::method::{CppFunction, CppMethodParam, parse_brace_block};
+    use crate::parser::cpp::expression::CppExpression;
+    use crate::parser::cpp::method::{
+        CppFunction, CppMethodParam, FunctionKind, InitializerStyle, OperatorKind,
+        parse_brace_block,
+    };
+    use crate::parser::generic::class::{InheritanceVisibility, parse_class};
+    use crate::parser::generic::ignore::IgnoreRules;
+    use crate::parser::generic::method::CppStorageQualifier;
     use crate::parser::generic::method::CppStorageQualifier::Virtual;
     use crate::parser::generic::method::PostParamQualifier::Final;
     use crate::parser::generic::method::{PostParamQualifier, SpecialMember, parse_method};
@@ -179,13 +414,13 @@ mod tests {
     #[test]
     fn test_empty_braces() {
         let input = "{}";
-        assert_eq!(parse_brace_block(&input), Ok(("", "")));
+        assert_eq!(parse_brace_block(input), Ok(("", "")));
     }
 
     #[test]
     fn test_only_braces() {
         let input = "{} CONTENT {}";
-        assert_eq!(parse_brace_block(&input), Ok((" CONTENT {}", "")));
+        assert_eq!(parse_brace_block(input), Ok((" CONTENT {}", "")));
     }
 
     #[test]
@@ -243,7 +478,8 @@ mod tests {
                     params: vec![CppMethodParam {
                         name: Some("i"),
                         ctype: Const(Box::from(Path(vec!["int"]))),
-                        default_value: Some(Path(vec!["0"])),
+                        default_value: Some(CppExpression::Literal("0")),
+                        attributes: vec![],
                     }],
                     ..Default::default()
                 }
@@ -269,6 +505,7 @@ mod tests {
                             vec![CType::Path(vec!["int"]), CType::Path(vec!["int"])]
                         ),
                         default_value: None,
+                        attributes: vec![],
                     }],
                     ..Default::default()
                 }
@@ -297,6 +534,7 @@ mod tests {
                             )]
                         ))))),
                         default_value: None,
+                        attributes: vec![],
                     }],
                     ..Default::default()
                 }
@@ -335,6 +573,7 @@ mod tests {
                 "",
                 CppFunction {
                     name: "method",
+                    body: Some("{\n            int i=42;\n        }"),
                     ..Default::default()
                 }
             ))
@@ -353,6 +592,7 @@ mod tests {
                 "",
                 CppFunction {
                     name: "sayHello",
+                    body: Some(r#"{ std::cout << "Hi" << std::endl; }"#),
                     comment: Some(CppComment {
                         comment: "Say hello to everyone".to_string()
                     }),
@@ -467,7 +707,8 @@ mod tests {
                     params: vec![CppMethodParam {
                         name: Some("a"),
                         ctype: Path(vec!["int"]),
-                        default_value: None
+                        default_value: None,
+                        attributes: vec![],
                     }],
                     post_param_qualifiers: vec![Final],
                     ..Default::default()
@@ -511,7 +752,8 @@ mod tests {
                     params: vec![CppMethodParam {
                         name: Some("a"),
                         ctype: Reference(Box::from(Path(vec!["int"]))),
-                        default_value: None
+                        default_value: None,
+                        attributes: vec![],
                     }],
                     post_param_qualifiers: vec![Final],
                     ..Default::default()
@@ -534,7 +776,8 @@ mod tests {
                     params: vec![CppMethodParam {
                         name: Some("a"),
                         ctype: Reference(Box::from(Const(Box::from(Path(vec!["int"]))))),
-                        default_value: None
+                        default_value: None,
+                        attributes: vec![],
                     }],
                     post_param_qualifiers: vec![Final],
                     ..Default::default()
@@ -557,7 +800,8 @@ mod tests {
                     params: vec![CppMethodParam {
                         name: Some("a"),
                         ctype: Pointer(Box::from(Path(vec!["int"]))),
-                        default_value: None
+                        default_value: None,
+                        attributes: vec![],
                     }],
                     post_param_qualifiers: vec![Final],
                     ..Default::default()
@@ -581,12 +825,14 @@ mod tests {
                         CppMethodParam {
                             name: Some("a"),
                             ctype: Reference(Box::from(Path(vec!["int"]))),
-                            default_value: None
+                            default_value: None,
+                            attributes: vec![],
                         },
                         CppMethodParam {
                             name: Some("b"),
                             ctype: Path(vec!["std", "string"]),
-                            default_value: None
+                            default_value: None,
+                            attributes: vec![],
                         }
                     ],
                     post_param_qualifiers: vec![Final],
@@ -610,7 +856,8 @@ mod tests {
                     params: vec![CppMethodParam {
                         name: Some("a"),
                         ctype: Generic(Box::from(Path(vec!["TArray"])), vec![Path(vec!["int32"])]),
-                        default_value: None
+                        default_value: None,
+                        attributes: vec![],
                     }],
                     post_param_qualifiers: vec![Final],
                     ..Default::default()
@@ -652,7 +899,8 @@ mod tests {
                     params: vec![CppMethodParam {
                         name: Some("a"),
                         ctype: Pointer(Box::from(Path(vec!["int"]))),
-                        default_value: None
+                        default_value: None,
+                        attributes: vec![],
                     }],
                     post_param_qualifiers: vec![Final],
                     ..Default::default()
@@ -661,6 +909,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_method_with_return_type_and_trailing_return_type() {
+        assert!(parse_method::<CppFunction>("int method() -> int").is_err());
+    }
+
+    #[test]
+    fn test_method_names_starting_with_qualifiers() {
+        for name in [
+            "inlineHelper",
+            "virtualize",
+            "staticMesh",
+            "friendly",
+            "constexprValue",
+        ] {
+            let input = format!("{name}()");
+            let (rest, function) = parse_method::<CppFunction>(&input).unwrap();
+
+            assert_eq!(rest, "");
+            assert_eq!(function.name, name);
+            assert!(function.storage_qualifiers.is_empty());
+        }
+    }
+
     #[test]
     fn test_method_with_lambda_param() {
         let input = "auto method(std::function<int(int)>& lambda) -> int";
@@ -682,7 +953,8 @@ mod tests {
                                 vec![Path(vec!["int"])],
                             )],
                         ))),
-                        default_value: None
+                        default_value: None,
+                        attributes: vec![],
                     }],
                     ..Default::default()
                 }
@@ -726,7 +998,8 @@ mod tests {
                     params: vec![CppMethodParam {
                         name: Some("a"),
                         ctype: Path(vec!["Integer"]),
-                        default_value: None
+                        default_value: None,
+                        attributes: vec![],
                     },],
                     ..Default::default()
                 }
@@ -749,7 +1022,8 @@ mod tests {
                     params: vec![CppMethodParam {
                         name: None,
                         ctype: Path(vec!["int"]),
-                        default_value: None
+                        default_value: None,
+                        attributes: vec![],
                     },],
                     ..Default::default()
                 }
@@ -791,4 +1065,312 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_method_with_attributes() {
+        let input = r#"[[nodiscard]] [[deprecated("Use GetLocation")]] static FVector GetPosition([[maybe_unused]] int32 Index)"#;
+        let (input, method) = parse_method::<CppFunction>(input).unwrap();
+
+        assert_eq!(input, "");
+        assert_eq!(
+            method.attributes,
+            vec![
+                CppAttribute {
+                    name: "nodiscard",
+                    arguments: None
+                },
+                CppAttribute {
+                    name: "deprecated",
+                    arguments: Some(r#""Use GetLocation""#)
+                }
+            ]
+        );
+        assert_eq!(method.deprecation_message(), Some("Use GetLocation"));
+        assert_eq!(method.params[0].name, Some("Index"));
+        assert_eq!(
+            method.params[0].attributes,
+            vec![CppAttribute {
+                name: "maybe_unused",
+                arguments: None
+            }]
+        );
+
+        let (input, method) =
+            parse_method::<CppFunction>("void F() const [[deprecated]] override;").unwrap();
+        assert_eq!(input, ";");
+        assert_eq!(
+            method.post_param_qualifiers,
+            vec![PostParamQualifier::Const, PostParamQualifier::Override]
+        );
+        assert_eq!(
+            method.attributes,
+            vec![CppAttribute {
+                name: "deprecated",
+                arguments: None
+            }]
+        );
+    }
+
+    #[test]
+    fn test_method_with_expression_defaults() {
+        let input = r#"void Log(const FString& Message = TEXT("Hi"), float Scale = 1.5f)"#;
+        let (_, method) = parse_method::<CppFunction>(input).unwrap();
+
+        let defaults: Vec<String> = method
+            .params
+            .iter()
+            .map(|param| param.default_value.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(defaults, vec![r#"TEXT("Hi")"#, "1.5f"]);
+
+        let (_, method) = parse_method::<CppFunction>("void G(int X = bFlag ? 1 : 2)").unwrap();
+        assert_eq!(
+            method.params[0].default_value.as_ref().unwrap().to_string(),
+            "bFlag ? 1 : 2"
+        );
+    }
+
+    #[test]
+    fn test_method_special_members() {
+        let special = |input| parse_method::<CppFunction>(input).unwrap().1.special;
+
+        assert_eq!(special("FFoo(const FFoo&) = delete"), Some(SpecialMember::Deleted));
+        assert_eq!(special("FFoo(FFoo&&) = default"), Some(SpecialMember::Defaulted));
+        assert_eq!(special("virtual void Tick() override = 0"), Some(SpecialMember::PureVirtual));
+        assert_eq!(
+            special("virtual int32 GetCount() const PURE_VIRTUAL(UFoo::GetCount, return 0;)"),
+            Some(SpecialMember::PureVirtualMacro)
+        );
+    }
+
+    #[test]
+    fn test_method_post_param_qualifiers() {
+        let qualifiers =
+            |input| parse_method::<CppFunction>(input).unwrap().1.post_param_qualifiers;
+
+        assert_eq!(
+            qualifiers("FString Get() const && noexcept(bIsNoexcept) override"),
+            vec![
+                PostParamQualifier::Const,
+                PostParamQualifier::RValueRef,
+                PostParamQualifier::NoexceptIf("bIsNoexcept".to_string()),
+                PostParamQualifier::Override
+            ]
+        );
+        assert_eq!(
+            qualifiers("void Set() volatile & noexcept"),
+            vec![
+                PostParamQualifier::Volatile,
+                PostParamQualifier::LValueRef,
+                PostParamQualifier::Noexcept
+            ]
+        );
+        assert_eq!(
+            qualifiers("auto Get() const -> int32 final"),
+            vec![PostParamQualifier::Const, PostParamQualifier::Final]
+        );
+    }
+
+    #[test]
+    fn test_method_conditional_explicit() {
+        let (_, method) =
+            parse_method::<CppFunction>("explicit(!std::is_same_v<T, int>) FWrapper(T Value)")
+                .unwrap();
+
+        assert_eq!(
+            method.storage_qualifiers,
+            vec![CppStorageQualifier::ExplicitIf(
+                "!std::is_same_v<T, int>".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_function_kind() {
+        let kind = |input, class_name| {
+            let input = format!("class {} {{ {}; }}", class_name, input);
+            let (_, class) = parse_class::<CppClass>(&input, &IgnoreRules::default()).unwrap();
+            class.methods[&InheritanceVisibility::Private][0]
+                .kind
+                .clone()
+        };
+
+        assert_eq!(kind("FFoo(int32 Value)", "FFoo"), FunctionKind::Constructor);
+        assert_eq!(kind("FFoo(const FFoo& Other)", "FFoo"), FunctionKind::CopyConstructor);
+        assert_eq!(kind("FFoo(FFoo&& Other)", "FFoo"), FunctionKind::MoveConstructor);
+        assert_eq!(kind("~FFoo()", "FFoo"), FunctionKind::Destructor);
+        assert_eq!(
+            kind("FFoo& operator=(const FFoo& Other)", "FFoo"),
+            FunctionKind::CopyAssignment
+        );
+        assert_eq!(
+            kind("FFoo& operator=(FFoo&& Other)", "FFoo"),
+            FunctionKind::MoveAssignment
+        );
+        assert_eq!(
+            kind("FFoo& operator=(int32 Value)", "FFoo"),
+            FunctionKind::Operator
+        );
+        assert_eq!(kind("void Reset()", "FFoo"), FunctionKind::Regular);
+
+        let free = |input| parse_method::<CppFunction>(input).unwrap().1.kind;
+        assert_eq!(free("FFoo(int32 Value)"), FunctionKind::Regular);
+        assert_eq!(
+            free("FFoo operator+(const FFoo& A, const FFoo& B)"),
+            FunctionKind::Operator
+        );
+    }
+
+    #[test]
+    fn test_constructor_member_initializers() {
+        let input = r#"AMyActor(const FObjectInitializer& ObjectInitializer) noexcept
+            : Super(ObjectInitializer), Health(100.f), Tags{TEXT("A"), TEXT("B")}, Owner{}
+        {
+        }"#;
+        let (_, method) = parse_method::<CppFunction>(input).unwrap();
+
+        assert_eq!(
+            method.post_param_qualifiers,
+            vec![PostParamQualifier::Noexcept]
+        );
+        assert_eq!(
+            method
+                .initializers
+                .iter()
+                .map(|initializer| (
+                    initializer.member.to_string(),
+                    initializer.arguments.len(),
+                    initializer.style.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Super".to_string(), 1, InitializerStyle::Parenthesized),
+                ("Health".to_string(), 1, InitializerStyle::Parenthesized),
+                ("Tags".to_string(), 2, InitializerStyle::Braced),
+                ("Owner".to_string(), 0, InitializerStyle::Braced),
+            ]
+        );
+        assert_eq!(
+            method.initializers[1].arguments,
+            vec![CppExpression::Literal("100.f")]
+        );
+        assert_eq!(method.body, Some("{\n        }"));
+
+        let (_, method) = parse_method::<CppFunction>("B(int X) : B(X > 0 ? X : 0) {}").unwrap();
+        assert_eq!(
+            method.initializers[0].arguments[0].to_string(),
+            "X > 0 ? X : 0"
+        );
+    }
+
+    #[test]
+    fn test_inline_body_span() {
+        let input = "// Current health\nfloat GetHealth() const { return Health; }";
+        let (_, method) = parse_method::<CppFunction>(input).unwrap();
+
+        let span = method.body_span(input).unwrap();
+        assert_eq!(&input[span.clone()], "{ return Health; }");
+        assert_eq!(span.end, input.len());
+        assert_eq!(method.body_span("{ return Health; }"), None);
+    }
+
+    #[test]
+    fn test_operator_names() {
+        let name = |input| parse_method::<CppFunction>(input).unwrap().1.name;
+
+        for (input, expected) in [
+            ("bool operator<=(const FFoo& Other) const", "operator<="),
+            ("FFoo& operator<<=(int32 Shift)", "operator<<="),
+            (
+                "auto operator<=>(const FFoo& Other) const = default",
+                "operator<=>",
+            ),
+            (
+                "FArchive& operator<<(FArchive& Ar, FFoo& Foo)",
+                "operator<<",
+            ),
+            ("bool operator&&(const FFoo& Other) const", "operator&&"),
+            ("R& operator->*(T Member)", "operator->*"),
+            ("bool operator == (const FFoo& Other) const", "operator=="),
+            ("void* operator new[](size_t Size)", "operator new[]"),
+            ("void operator delete [](void* Ptr)", "operator delete[]"),
+            ("void* operator new(size_t Size)", "operator new"),
+            (
+                "float operator\"\"_cm(long double Value)",
+                "operator\"\"_cm",
+            ),
+            ("auto operator co_await() const", "operator co_await"),
+            ("explicit operator bool() const", "operator bool"),
+            ("operator const TCHAR*() const", "operator const TCHAR*"),
+            (
+                "operator TArray<TPair<int32, float>>() const",
+                "operator TArray<TPair<int32, float>>",
+            ),
+            ("int32 operatorCount()", "operatorCount"),
+        ] {
+            assert_eq!(name(input), expected);
+        }
+    }
+
+    #[test]
+    fn test_operator_kinds() {
+        let kind = |input| {
+            parse_method::<CppFunction>(input)
+                .unwrap()
+                .1
+                .operator_kind()
+        };
+
+        assert_eq!(
+            kind("FFoo operator+(const FFoo& A)"),
+            Some(OperatorKind::Arithmetic)
+        );
+        assert_eq!(kind("FFoo& operator++()"), Some(OperatorKind::Arithmetic));
+        assert_eq!(kind("FFoo operator~()"), Some(OperatorKind::Bitwise));
+        assert_eq!(kind("bool operator!()"), Some(OperatorKind::Logical));
+        assert_eq!(
+            kind("bool operator!=(const FFoo& A)"),
+            Some(OperatorKind::Comparison)
+        );
+        assert_eq!(
+            kind("FFoo& operator>>=(int32 Shift)"),
+            Some(OperatorKind::Assignment)
+        );
+        assert_eq!(
+            kind("T& operator[](int32 Index)"),
+            Some(OperatorKind::Subscript)
+        );
+        assert_eq!(
+            kind("void operator()(int32 Value)"),
+            Some(OperatorKind::Call)
+        );
+        assert_eq!(kind("T* operator->()"), Some(OperatorKind::MemberAccess));
+        assert_eq!(kind("FFoo& operator,(FFoo& A)"), Some(OperatorKind::Comma));
+        assert_eq!(
+            kind("explicit operator bool() const"),
+            Some(OperatorKind::Conversion)
+        );
+        assert_eq!(
+            kind("void operator delete[](void* Ptr)"),
+            Some(OperatorKind::Allocation)
+        );
+        assert_eq!(
+            kind("float operator\"\" _cm(long double Value)"),
+            Some(OperatorKind::Literal)
+        );
+        assert_eq!(
+            kind("auto operator co_await()"),
+            Some(OperatorKind::CoAwait)
+        );
+        assert_eq!(kind("int32 operatorCount()"), None);
+
+        let (_, conversion) =
+            parse_method::<CppFunction>("explicit operator bool() const").unwrap();
+        assert_eq!(conversion.return_type, None);
+        assert_eq!(
+            conversion.storage_qualifiers,
+            vec![CppStorageQualifier::Explicit]
+        );
+        assert_eq!(conversion.kind, FunctionKind::ConversionOperator);
+    }
 }
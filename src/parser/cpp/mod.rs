@@ -6,7 +6,14 @@ pub mod member;
 pub mod header;
 pub mod namespace;
 pub(crate) mod template;
-mod variable;
 pub mod cenum;
-mod alias;
+pub mod alias;
+pub mod linkage;
+pub mod using;
+pub mod attribute;
+pub mod expression;
 
+
+pub mod preprocessor;
+pub mod cmacro;
+pub mod macro_table;
\ No newline at end of file
@@ -1,47 +1,97 @@
-﻿use crate::parser::cpp::class::CppClass;
+use crate::parser::cpp::alias::CppAlias;
+use crate::parser::cpp::cenum::CppEnum;
+use crate::parser::cpp::class::CppClass;
 use crate::parser::cpp::comment::CppComment;
+use crate::parser::cpp::linkage::CppLinkageBlock;
 use crate::parser::cpp::member::CppMember;
 use crate::parser::cpp::method::CppFunction;
-use crate::parser::generic::namespace::Namespace;
+use crate::parser::cpp::using::CppUsing;
+use crate::parser::generic::namespace::{Namespace, NamespaceDefinition};
 
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct CppNamespace<'a> {
+    /// Empty for anonymous namespaces
     pub name: &'a str,
+    /// Members of an `inline namespace` are also members of the enclosing namespace
+    pub is_inline: bool,
     pub namespaces: Vec<CppNamespace<'a>>,
     pub classes: Vec<CppClass<'a>>,
     pub functions: Vec<CppFunction<'a>>,
     pub variables: Vec<CppMember<'a>>,
+    pub aliases: Vec<CppAlias<'a>>,
+    pub enums: Vec<CppEnum<'a>>,
+    pub usings: Vec<CppUsing<'a>>,
     pub comments: Vec<CppComment>,
+    pub linkage_blocks: Vec<CppLinkageBlock<'a>>,
 }
 
-impl<'a> Namespace<'a, CppClass<'a>> for CppNamespace<'a> {
+impl<'a> CppNamespace<'a> {
+    /// Anonymous namespaces have internal linkage. Their contents are not visible outside of
+    /// the translation unit, so renderers usually hide them.
+    pub fn has_internal_linkage(&self) -> bool {
+        self.name.is_empty()
+    }
+
+    /// Free and `friend` operators in this namespace and its nested namespaces that take
+    /// `class_name` as an operand
+    pub fn operators_for(&self, class_name: &str) -> Vec<&CppFunction<'a>> {
+        operators_for(&self.functions, &self.classes, &self.namespaces, class_name)
+    }
+}
 
-    fn namespace(
-        name: &'a str,
-        namespaces: Vec<Self>,
-        functions: Vec<CppFunction<'a>>,
-        variables: Vec<CppMember<'a>>,
-        classes: Vec<CppClass<'a>>,
-        comments: Vec<CppComment>,
-    ) -> Self
+pub(crate) fn operators_for<'b, 'a>(
+    functions: &'b [CppFunction<'a>],
+    classes: &'b [CppClass<'a>],
+    namespaces: &'b [CppNamespace<'a>],
+    class_name: &str,
+) -> Vec<&'b CppFunction<'a>> {
+    functions
+        .iter()
+        .chain(classes.iter().flat_map(|class| class.friend_operators()))
+        .filter(|function| function.operates_on(class_name))
+        .chain(
+            namespaces
+                .iter()
+                .flat_map(|namespace| namespace.operators_for(class_name)),
+        )
+        .collect()
+}
+
+impl<'a> Namespace<'a, CppClass<'a>> for CppNamespace<'a> {
+    fn namespace(definition: NamespaceDefinition<'a, Self, CppClass<'a>>) -> Self
     where
         Self: 'a + Sized,
     {
         CppNamespace {
-            name,
-            namespaces,
-            classes,
-            functions,
-            variables,
-            comments,
+            name: definition.name,
+            is_inline: definition.is_inline,
+            namespaces: definition.namespaces,
+            classes: definition.classes,
+            functions: definition.functions,
+            variables: definition.variables,
+            aliases: definition.aliases,
+            enums: definition.enums,
+            usings: definition.usings,
+            comments: definition.comments,
+            linkage_blocks: definition
+                .linkage_blocks
+                .into_iter()
+                .map(|(linkage, body)| CppLinkageBlock::new(linkage, body))
+                .collect(),
         }
     }
 }
 #[cfg(test)]
 mod tests {
+    use crate::parser::cpp::alias::{AliasKind, CppAlias};
+    use crate::parser::cpp::cenum::{CppEnum, EnumVariant};
     use crate::parser::cpp::class::CppClass;
     use crate::parser::cpp::comment::CppComment;
+    use crate::parser::cpp::ctype::CType;
+    use crate::parser::cpp::member::CppMember;
     use crate::parser::cpp::namespace::CppNamespace;
+    use crate::parser::cpp::using::CppUsing;
+    use crate::parser::generic::ignore::IgnoreRules;
     use crate::parser::generic::namespace::parse_namespace;
 
     #[test]
@@ -55,7 +105,7 @@ mod tests {
             },
         ));
 
-        let result = parse_namespace(input);
+        let result = parse_namespace(input, &IgnoreRules::default());
         assert_eq!(result, expected);
     }
 
@@ -74,7 +124,7 @@ mod tests {
             },
         ));
 
-        let result = parse_namespace(input);
+        let result = parse_namespace(input, &IgnoreRules::default());
         assert_eq!(result, expected);
     }
     #[test]
@@ -94,7 +144,7 @@ mod tests {
             },
         ));
 
-        let result = parse_namespace(input);
+        let result = parse_namespace(input, &IgnoreRules::default());
         assert_eq!(result, expected);
     }
 
@@ -116,7 +166,7 @@ mod tests {
             },
         ));
 
-        let result = parse_namespace(input);
+        let result = parse_namespace(input, &IgnoreRules::default());
         assert_eq!(result, expected);
     }
 
@@ -138,7 +188,161 @@ mod tests {
             },
         ));
 
-        let result = parse_namespace(input);
+        let result = parse_namespace(input, &IgnoreRules::default());
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn namespace_with_typedefs() {
+        let input = r#"namespace test {
+            typedef unsigned int uint;
+            using Callback = void;
+        }"#;
+
+        let expected = Ok((
+            "",
+            CppNamespace {
+                name: "test",
+                aliases: vec![
+                    CppAlias {
+                        name: "uint",
+                        ctype: CType::Path(vec!["unsigned int"]),
+                        kind: AliasKind::Typedef,
+                        class: None,
+                    },
+                    CppAlias {
+                        name: "Callback",
+                        ctype: CType::Path(vec!["void"]),
+                        kind: AliasKind::Using,
+                        class: None,
+                    },
+                ],
+                ..Default::default()
+            },
+        ));
+
+        let result = parse_namespace(input, &IgnoreRules::default());
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn namespace_with_enum() {
+        let input = r#"namespace test {
+            enum EColor { Red, Green };
+        }"#;
+
+        let expected = Ok((
+            "",
+            CppNamespace {
+                name: "test",
+                enums: vec![CppEnum {
+                    name: Some("EColor".to_string()),
+                    keyword: "enum",
+                    variants: vec![
+                        EnumVariant {
+                            name: "Red".to_string(),
+                            expression: None,
+                            value: Some(0),
+                            attributes: vec![],
+                            comment: None,
+                        },
+                        EnumVariant {
+                            name: "Green".to_string(),
+                            expression: None,
+                            value: Some(1),
+                            attributes: vec![],
+                            comment: None,
+                        },
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ));
+
+        let result = parse_namespace(input, &IgnoreRules::default());
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn nested_namespace_definition() {
+        let input = "namespace UE::Math::inline V2 { class FVector; }";
+        let expected = Ok((
+            "",
+            CppNamespace {
+                name: "UE",
+                namespaces: vec![CppNamespace {
+                    name: "Math",
+                    namespaces: vec![CppNamespace {
+                        name: "V2",
+                        is_inline: true,
+                        classes: vec![CppClass {
+                            name: "FVector",
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ));
+
+        let result = parse_namespace(input, &IgnoreRules::default());
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn inline_namespace() {
+        let input = "inline namespace v2 {}";
+        let expected = Ok((
+            "",
+            CppNamespace {
+                name: "v2",
+                is_inline: true,
+                ..Default::default()
+            },
+        ));
+
+        let result = parse_namespace(input, &IgnoreRules::default());
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn anonymous_namespace() {
+        let input = "namespace { int Counter; }";
+
+        let (input, namespace): (&str, CppNamespace) =
+            parse_namespace(input, &IgnoreRules::default()).unwrap();
+        assert_eq!(input, "");
+        assert!(namespace.has_internal_linkage());
+        assert_eq!(
+            namespace,
+            CppNamespace {
+                name: "",
+                variables: vec![CppMember {
+                    name: "Counter",
+                    ctype: CType::Path(vec!["int"]),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn namespace_with_using_directive() {
+        let input = "namespace Game { using namespace UE::Math; }";
+        let expected = Ok((
+            "",
+            CppNamespace {
+                name: "Game",
+                usings: vec![CppUsing::Directive(vec!["UE", "Math"])],
+                ..Default::default()
+            },
+        ));
+
+        let result = parse_namespace(input, &IgnoreRules::default());
         assert_eq!(result, expected);
     }
 }
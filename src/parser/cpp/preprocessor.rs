@@ -0,0 +1,621 @@
+﻿use crate::parser::cpp::ctype::CType;
+use crate::parser::cpp::expression::{CppExpression, Literal, parse_expression};
+use crate::parser::cpp::macro_table::{Expander, MacroTable};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Macros conditional branches are evaluated against, like `-D` arguments of a compiler
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PreprocessorConfig {
+    defines: HashMap<String, String>,
+    macros: MacroTable,
+}
+
+impl PreprocessorConfig {
+    /// Reads definitions like `WITH_EDITOR=1` or `PLATFORM_WINDOWS`, which is defined as `1`
+    pub fn from_definitions<'b>(definitions: impl IntoIterator<Item = &'b str>) -> Self {
+        PreprocessorConfig::default().definitions(definitions)
+    }
+
+    /// Adds definitions in the form of [`PreprocessorConfig::from_definitions`], replacing
+    /// earlier values
+    pub fn definitions<'b>(mut self, definitions: impl IntoIterator<Item = &'b str>) -> Self {
+        self.defines
+            .extend(definitions.into_iter().map(split_definition));
+        self
+    }
+
+    pub fn define(mut self, name: &str, value: &str) -> Self {
+        self.defines.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Macros expanded in the active regions before parsing
+    pub fn macros(mut self, macros: MacroTable) -> Self {
+        self.macros = macros;
+        self
+    }
+}
+
+/// `WITH_EDITOR=1` into name and value, `PLATFORM_WINDOWS` is defined as `1`
+fn split_definition(definition: &str) -> (String, String) {
+    match definition.split_once('=') {
+        Some((name, value)) => (name.trim().to_string(), value.trim().to_string()),
+        None => (definition.trim().to_string(), "1".to_string()),
+    }
+}
+
+/// One branch of an `#if`/`#ifdef`/`#ifndef` ... `#elif`/`#else` ... `#endif` group
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionalBranch {
+    /// `WITH_EDITOR`, `defined(PLATFORM_WINDOWS)` for an `#ifdef` or `!(WITH_EDITOR)` for the
+    /// `#else` branch
+    pub condition: String,
+    /// Byte range of the lines between the directives within the preprocessed text
+    pub range: Range<usize>,
+    /// Whether the branch is kept for the configured defines
+    pub active: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PreprocessedSource {
+    /// The source with conditional directives and inactive branches replaced by spaces. Byte
+    /// offsets are the same as in the original source unless macros were replaced.
+    pub text: String,
+    pub branches: Vec<ConditionalBranch>,
+}
+
+impl PreprocessedSource {
+    /// Conditions of all branches enclosing `offset`, outermost first
+    pub fn conditions_at(&self, offset: usize) -> Vec<&str> {
+        self.branches
+            .iter()
+            .filter(|branch| branch.range.contains(&offset))
+            .map(|branch| branch.condition.as_str())
+            .collect()
+    }
+
+    /// Conditions an entity sits under. `name` has to be a slice of `text`, e.g. the name of a
+    /// class parsed from it.
+    pub fn conditions_of(&self, name: &str) -> Vec<&str> {
+        match (name.as_ptr() as usize).checked_sub(self.text.as_ptr() as usize) {
+            Some(offset) if offset < self.text.len() => self.conditions_at(offset),
+            _ => vec![],
+        }
+    }
+}
+
+/// `#ifndef FOO_H` directly followed by `#define FOO_H` that encloses the whole file. Its branch
+/// isn't a condition of the declarations in the header.
+struct IncludeGuard {
+    name: String,
+    /// Byte range of the `#define` line in the result, it is blanked like the conditionals
+    define: Option<Range<usize>>,
+    closed: bool,
+}
+
+struct ConditionalGroup {
+    parent_active: bool,
+    taken: bool,
+    /// Conditions of the previous branches, negated by the following ones
+    previous: Vec<String>,
+    branch: usize,
+}
+
+/// Evaluates `#if` groups with the defines of `config` and blanks out everything the compiler
+/// wouldn't see. `#define` and `#undef` in active regions update the defines, the macro table
+/// of `config` is applied to the remaining lines. Include guards don't get a branch.
+pub fn preprocess(source: &str, config: &PreprocessorConfig) -> PreprocessedSource {
+    let mut defines = config.defines.clone();
+    let mut groups: Vec<ConditionalGroup> = vec![];
+    let mut branches: Vec<ConditionalBranch> = vec![];
+    let mut text = String::with_capacity(source.len());
+    let mut expander = Expander::default();
+    let mut seen_code = false;
+    let mut guard: Option<IncludeGuard> = None;
+
+    let mut lines = source.split_inclusive('\n');
+    while let Some(first) = lines.next() {
+        // directives continue on the next line after a trailing backslash
+        let mut line = first.to_string();
+        while line.trim_end().ends_with('\\') {
+            match lines.next() {
+                Some(next) => line.push_str(next),
+                None => break,
+            }
+        }
+        let active = groups
+            .last()
+            .is_none_or(|group| group.parent_active && branches[group.branch].active);
+        let (directive, argument) = match line.trim_start().strip_prefix('#') {
+            Some(directive) => split_directive(directive),
+            None => ("", String::new()),
+        };
+        // macro arguments don't continue across directives or inactive lines
+        if !active || !directive.is_empty() {
+            expander.finish(&mut text);
+        }
+        // branches are tracked in offsets of the result, directive lines keep their length
+        let start = text.len();
+        let offset = start + line.len();
+
+        if !is_blank_or_comment(&line) {
+            guard = match guard.take() {
+                // the guard macro has to be defined right after the `#ifndef`
+                Some(guard) if guard.define.is_none() => (directive == "define"
+                    && argument.split_whitespace().next() == Some(guard.name.as_str()))
+                .then_some(IncludeGuard {
+                    define: Some(start..offset),
+                    ..guard
+                }),
+                // nothing but comments may follow the closing `#endif`
+                Some(guard) if guard.closed => None,
+                guard => guard,
+            };
+            if directive == "ifndef" && !seen_code {
+                guard = Some(IncludeGuard {
+                    name: argument.clone(),
+                    define: None,
+                    closed: false,
+                });
+            }
+            seen_code = true;
+        }
+
+        match directive {
+            "if" | "ifdef" | "ifndef" => {
+                let (condition, value) = match directive {
+                    "ifdef" => (
+                        format!("defined({argument})"),
+                        defines.contains_key(&argument),
+                    ),
+                    "ifndef" => (
+                        format!("!defined({argument})"),
+                        !defines.contains_key(&argument),
+                    ),
+                    _ => (argument.clone(), evaluate(&argument, &defines)),
+                };
+                groups.push(ConditionalGroup {
+                    parent_active: active,
+                    taken: false,
+                    previous: vec![condition.clone()],
+                    branch: 0,
+                });
+                open_branch(&mut groups, &mut branches, condition, value, offset);
+            }
+            "elif" | "else" if !groups.is_empty() => {
+                let group = groups.last_mut().unwrap();
+                branches[group.branch].range.end = start;
+
+                let mut condition: Vec<String> = group
+                    .previous
+                    .iter()
+                    .map(|previous| format!("!({previous})"))
+                    .collect();
+                let value = match directive {
+                    "elif" => {
+                        group.previous.push(argument.clone());
+                        condition.push(argument.clone());
+                        evaluate(&argument, &defines)
+                    }
+                    _ => true,
+                };
+                open_branch(
+                    &mut groups,
+                    &mut branches,
+                    condition.join(" && "),
+                    value,
+                    offset,
+                );
+            }
+            "endif" if !groups.is_empty() => {
+                let group = groups.pop().unwrap();
+                branches[group.branch].range.end = start;
+                if let Some(guard) = guard.as_mut().filter(|_| groups.is_empty()) {
+                    guard.closed = true;
+                }
+            }
+            "define" if active => {
+                let (name, value) = match argument.split_once(char::is_whitespace) {
+                    Some((name, value)) => (name, value.trim()),
+                    None => (argument.as_str(), ""),
+                };
+                // function-like macros only count as defined
+                let name = name.split('(').next().unwrap_or(name);
+                defines.insert(name.to_string(), value.to_string());
+            }
+            "undef" if active => {
+                defines.remove(&argument);
+            }
+            _ => {}
+        }
+
+        let is_conditional = matches!(
+            directive,
+            "if" | "ifdef" | "ifndef" | "elif" | "else" | "endif"
+        );
+        match active && !is_conditional {
+            true if config.macros.is_empty() => text.push_str(&line),
+            true => expander.expand_line(&config.macros, &line, &mut text),
+            false => text.extend(line.bytes().map(|byte| match byte {
+                b'\n' | b'\r' => byte as char,
+                _ => ' ',
+            })),
+        }
+    }
+
+    expander.finish(&mut text);
+    for group in groups {
+        branches[group.branch].range.end = text.len();
+    }
+    // the guard is the first group of the file, so its branch comes first
+    if let Some(IncludeGuard {
+        define: Some(define),
+        closed: true,
+        ..
+    }) = guard
+    {
+        branches.remove(0);
+        let blank = " ".repeat(text[define.clone()].trim_end().len());
+        text.replace_range(define.start..define.start + blank.len(), &blank);
+    }
+
+    PreprocessedSource { text, branches }
+}
+
+/// Lines before an include guard and after its `#endif`
+fn is_blank_or_comment(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with("//") || line.starts_with("/*") || line.starts_with('*')
+}
+
+/// Starts the next branch of the innermost group at `offset`, only the first branch whose
+/// condition holds is active
+fn open_branch(
+    groups: &mut [ConditionalGroup],
+    branches: &mut Vec<ConditionalBranch>,
+    condition: String,
+    value: bool,
+    offset: usize,
+) {
+    let group = groups.last_mut().unwrap();
+    let active = group.parent_active && !group.taken && value;
+    group.taken |= active;
+    group.branch = branches.len();
+    branches.push(ConditionalBranch {
+        condition,
+        range: offset..offset,
+        active,
+    });
+}
+
+/// `ifdef WITH_EDITOR // comment` is split into `ifdef` and `WITH_EDITOR`
+fn split_directive(directive: &str) -> (&str, String) {
+    let directive = directive.trim_start();
+    let end = directive
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(directive.len());
+    let (name, argument) = directive.split_at(end);
+
+    let mut argument = argument.replace("\\\r\n", " ").replace("\\\n", " ");
+    if let Some(comment) = argument.find("//") {
+        argument.truncate(comment);
+    }
+    while let (Some(open), Some(close)) = (argument.find("/*"), argument.find("*/")) {
+        match open < close {
+            true => argument.replace_range(open..close + 2, " "),
+            false => break,
+        }
+    }
+
+    (name, argument.trim().to_string())
+}
+
+/// Evaluates the condition of an `#if` or `#elif`, unknown macros are `0`
+fn evaluate(condition: &str, defines: &HashMap<String, String>) -> bool {
+    value_of(condition, defines, 0) != 0
+}
+
+fn value_of(condition: &str, defines: &HashMap<String, String>, depth: usize) -> i128 {
+    // `defined X` is rewritten as the call `defined(X)` the expression parser understands
+    let mut normalized = String::with_capacity(condition.len());
+    let mut rest = condition;
+    while let Some(index) = rest.find("defined") {
+        let (before, after) = rest.split_at(index + "defined".len());
+        normalized.push_str(before);
+
+        let name = after.trim_start();
+        let is_word_start = !before[..index].ends_with(|c: char| c.is_alphanumeric() || c == '_');
+        let end = name
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(name.len());
+        rest = match is_word_start && end > 0 && after.starts_with(char::is_whitespace) {
+            true => {
+                normalized.push_str(&format!("({})", &name[..end]));
+                &name[end..]
+            }
+            false => after,
+        };
+    }
+    normalized.push_str(rest);
+
+    match parse_expression(normalized.trim()) {
+        Ok(("", expression)) => evaluate_expression(&expression, defines, depth),
+        _ => 0,
+    }
+}
+
+fn evaluate_expression(
+    expression: &CppExpression,
+    defines: &HashMap<String, String>,
+    depth: usize,
+) -> i128 {
+    let value = |expression| evaluate_expression(expression, defines, depth);
+
+    match expression {
+        CppExpression::Literal(_) => match expression.literal() {
+            Some(Literal::Int(value)) => value,
+            Some(Literal::Bool(value)) => value as i128,
+            Some(Literal::Char(value)) => value as i128,
+            _ => 0,
+        },
+        CppExpression::Name(CType::Path(path)) => match (&path[..], depth < 16) {
+            ([name], true) => defines
+                .get(*name)
+                .map_or(0, |value| value_of(value, defines, depth + 1)),
+            _ => 0,
+        },
+        CppExpression::Call(CType::Path(path), args) if path[..] == ["defined"] => {
+            match &args[..] {
+                [CppExpression::Name(CType::Path(name))] if name.len() == 1 => {
+                    defines.contains_key(name[0]) as i128
+                }
+                _ => 0,
+            }
+        }
+        CppExpression::Unary(op, operand) => {
+            let operand = value(operand);
+            match *op {
+                "!" => (operand == 0) as i128,
+                "-" => operand.wrapping_neg(),
+                "~" => !operand,
+                _ => operand,
+            }
+        }
+        CppExpression::Binary(op, lhs, rhs) => {
+            let lhs = value(lhs);
+            // `&&` and `||` short-circuit like in the compiler
+            match *op {
+                "&&" => return (lhs != 0 && value(rhs) != 0) as i128,
+                "||" => return (lhs != 0 || value(rhs) != 0) as i128,
+                _ => {}
+            }
+
+            let rhs = value(rhs);
+            match *op {
+                "*" => lhs.wrapping_mul(rhs),
+                "/" => lhs.checked_div(rhs).unwrap_or(0),
+                "%" => lhs.checked_rem(rhs).unwrap_or(0),
+                "+" => lhs.wrapping_add(rhs),
+                "-" => lhs.wrapping_sub(rhs),
+                "<<" => lhs.checked_shl(rhs as u32).unwrap_or(0),
+                ">>" => lhs.checked_shr(rhs as u32).unwrap_or(0),
+                "<" => (lhs < rhs) as i128,
+                ">" => (lhs > rhs) as i128,
+                "<=" => (lhs <= rhs) as i128,
+                ">=" => (lhs >= rhs) as i128,
+                "==" => (lhs == rhs) as i128,
+                "!=" => (lhs != rhs) as i128,
+                "&" => lhs & rhs,
+                "^" => lhs ^ rhs,
+                "|" => lhs | rhs,
+                _ => 0,
+            }
+        }
+        CppExpression::Parenthesized(inner) => value(inner),
+        // function-like macros such as `UE_VERSION_NEWER_THAN(5, 1, 0)` aren't expanded
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::cpp::macro_table::MacroTable;
+    use crate::parser::cpp::preprocessor::{PreprocessorConfig, evaluate, preprocess};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_evaluate_conditions() {
+        let defines = HashMap::from([
+            ("WITH_EDITOR".to_string(), "1".to_string()),
+            ("ENGINE_MAJOR_VERSION".to_string(), "5".to_string()),
+            ("UE_BUILD_SHIPPING".to_string(), "0".to_string()),
+            (
+                "WITH_EDITORONLY_DATA".to_string(),
+                "WITH_EDITOR".to_string(),
+            ),
+        ]);
+
+        for (condition, expected) in [
+            ("WITH_EDITOR", true),
+            ("WITH_EDITORONLY_DATA", true),
+            ("UE_BUILD_SHIPPING", false),
+            ("!UE_BUILD_SHIPPING", true),
+            ("PLATFORM_WINDOWS", false),
+            ("defined(UE_BUILD_SHIPPING)", true),
+            ("defined PLATFORM_WINDOWS", false),
+            ("!defined PLATFORM_WINDOWS && WITH_EDITOR", true),
+            (
+                "ENGINE_MAJOR_VERSION >= 5 && (WITH_EDITOR || UE_BUILD_SHIPPING)",
+                true,
+            ),
+            ("ENGINE_MAJOR_VERSION * 100 + 3 < 500", false),
+            ("UE_VERSION_NEWER_THAN(5, 1, 0)", false),
+            ("1 /", false),
+        ] {
+            assert_eq!(evaluate(condition, &defines), expected, "{condition}");
+        }
+    }
+
+    #[test]
+    fn test_inactive_branches_are_blanked() {
+        let input = "int32 A;\n#if WITH_EDITOR\nint32 B;\n#else\nint32 C;\n#endif\n";
+        let result = preprocess(
+            input,
+            &PreprocessorConfig::from_definitions(["WITH_EDITOR"]),
+        );
+
+        assert_eq!(result.text.len(), input.len());
+        assert_eq!(
+            result.text,
+            "int32 A;\n               \nint32 B;\n     \n        \n      \n"
+        );
+        assert_eq!(
+            result
+                .branches
+                .iter()
+                .map(|branch| (branch.condition.as_str(), branch.active))
+                .collect::<Vec<_>>(),
+            vec![("WITH_EDITOR", true), ("!(WITH_EDITOR)", false)]
+        );
+        assert_eq!(
+            result.conditions_at(input.find("B").unwrap()),
+            vec!["WITH_EDITOR"]
+        );
+        assert_eq!(
+            result.conditions_at(input.find("A").unwrap()),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn test_nested_and_chained_conditionals() {
+        let input = r#"#ifdef PLATFORM_WINDOWS
+    #if WITH_EDITOR
+        int32 Windows;
+    #endif
+#elif defined(PLATFORM_MAC) /* Apple */
+    int32 Mac;
+#else
+    int32 Other;
+#endif"#;
+        let config = PreprocessorConfig::default()
+            .define("PLATFORM_MAC", "1")
+            .define("WITH_EDITOR", "1");
+        let result = preprocess(input, &config);
+
+        assert!(!result.text.contains("Windows"));
+        assert!(result.text.contains("int32 Mac;"));
+        assert!(!result.text.contains("Other"));
+        assert!(!result.text.contains('#'));
+        assert_eq!(
+            result.conditions_at(input.find("Windows").unwrap()),
+            vec!["defined(PLATFORM_WINDOWS)", "WITH_EDITOR"]
+        );
+        assert_eq!(
+            result.conditions_at(input.find("Mac;").unwrap()),
+            vec!["!(defined(PLATFORM_WINDOWS)) && defined(PLATFORM_MAC)"]
+        );
+        assert_eq!(
+            result.conditions_at(input.find("Other").unwrap()),
+            vec!["!(defined(PLATFORM_WINDOWS)) && !(defined(PLATFORM_MAC))"]
+        );
+    }
+
+    #[test]
+    fn test_include_guard_is_no_condition() {
+        let input = "// Copyright
+#ifndef FOO_H
+#define FOO_H
+#if WITH_EDITOR
+int32 A;
+#endif
+int32 B;
+#endif // FOO_H
+";
+        let config = PreprocessorConfig::from_definitions(["WITH_EDITOR"]);
+        let result = preprocess(input, &config);
+
+        assert!(result.text.contains("int32 A;"));
+        assert!(!result.text.contains("FOO_H"));
+        assert_eq!(
+            result.conditions_at(input.find("A;").unwrap()),
+            vec!["WITH_EDITOR"]
+        );
+        assert!(result.conditions_at(input.find("B;").unwrap()).is_empty());
+
+        // not a guard if anything follows the `#endif`
+        let input = "#ifndef FOO_H
+#define FOO_H
+int32 A;
+#endif
+int32 B;
+";
+        let result = preprocess(input, &config);
+        assert_eq!(
+            result.conditions_at(input.find("A;").unwrap()),
+            vec!["!defined(FOO_H)"]
+        );
+    }
+
+    #[test]
+    fn test_defines_in_source() {
+        let input = "#define USE_FAST_PATH 1\n#undef WITH_EDITOR\n#if USE_FAST_PATH && !WITH_EDITOR\nint32 Fast;\n#endif\n";
+        let result = preprocess(
+            input,
+            &PreprocessorConfig::from_definitions(["WITH_EDITOR=1"]),
+        );
+
+        assert!(result.text.contains("int32 Fast;"));
+        assert!(result.text.starts_with("#define USE_FAST_PATH 1\n"));
+        assert!(result.branches[0].active);
+    }
+
+    #[test]
+    fn test_macros_in_active_regions() {
+        let input = "#if WITH_EDITOR
+FORCEINLINE bool IsEditor();
+#endif
+FORCEINLINE int32 Num();
+";
+        let config =
+            PreprocessorConfig::from_definitions(["WITH_EDITOR"]).macros(MacroTable::unreal());
+        let result = preprocess(input, &config);
+
+        assert_eq!(
+            result.text,
+            "               \ninline bool IsEditor();\n      \ninline int32 Num();\n"
+        );
+        let editor = result.text.find("IsEditor").unwrap();
+        assert_eq!(result.conditions_at(editor), vec!["WITH_EDITOR"]);
+        assert!(
+            result
+                .conditions_at(result.text.find("Num").unwrap())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_macro_arguments_spanning_lines() {
+        let input = "UE_DEPRECATED(5.1,
+    \"Use Y\") void X();
+UE_DEPRECATED(5.1,
+#if WITH_EDITOR
+int32 Z;
+#endif
+";
+        let config =
+            PreprocessorConfig::from_definitions(["WITH_EDITOR"]).macros(MacroTable::unreal());
+        let result = preprocess(input, &config);
+
+        assert!(
+            result
+                .text
+                .starts_with("[[deprecated(\"Use Y\")]]\n void X();\nUE_DEPRECATED(5.1,\n")
+        );
+        assert_eq!(
+            result.conditions_at(result.text.find("Z").unwrap()),
+            vec!["WITH_EDITOR"]
+        );
+    }
+}
@@ -8,7 +8,7 @@ use nom_language::error::VerboseError;
 use crate::parser::cpp::ctype::{parse_cpp_type, CType};
 use crate::parser::ws;
 
-fn parse_template_param(input: &str) -> IResult<&str, CType, VerboseError<&str>> {
+fn parse_template_param(input: &str) -> IResult<&str, CType<'_>, VerboseError<&str>> {
     let (input, _) = ws(alt((tag("typename"), tag("class")))).parse(input)?;
     let (input, ctype) = parse_cpp_type(input)?;
     let (input, _) = multispace0(input)?;
@@ -16,7 +16,7 @@ fn parse_template_param(input: &str) -> IResult<&str, CType, VerboseError<&str>>
 
     Ok((input, ctype))
 }
-pub fn parse_template(input: &str) -> IResult<&str, Vec<CType>, VerboseError<&str>> {
+pub fn parse_template(input: &str) -> IResult<&str, Vec<CType<'_>>, VerboseError<&str>> {
     let (input, _) = ws(tag("template")).parse(input)?;
     let (input, _) = char('<').parse(input)?;
     let (input, params) = separated_list0(tag(","), parse_template_param).parse(input)?;
@@ -0,0 +1,80 @@
+﻿use crate::parser::{keyword, parse_str};
+use crate::types::Parsable;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, multispace0, multispace1};
+use nom::combinator::opt;
+use nom::multi::separated_list1;
+use nom::sequence::{preceded, terminated};
+use nom::{IResult, Parser};
+use nom_language::error::VerboseError;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CppUsing<'a> {
+    /// `using Super::BeginPlay;` makes a member of a base class or a name of another namespace
+    /// visible in the current scope
+    Declaration(Vec<&'a str>),
+    /// `using namespace UE::Math;`
+    Directive(Vec<&'a str>),
+}
+
+impl CppUsing<'_> {
+    /// `using Base::Base;` inherits all constructors of `Base`
+    pub fn is_inheriting_constructor(&self) -> bool {
+        match self {
+            CppUsing::Declaration(path) => match path.as_slice() {
+                [.., class, constructor] => class == constructor,
+                _ => false,
+            },
+            CppUsing::Directive(_) => false,
+        }
+    }
+}
+
+impl<'a> Parsable<'a> for CppUsing<'a> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self, VerboseError<&'a str>> {
+        let (input, _) = terminated(keyword("using"), multispace1).parse(input)?;
+        let (input, directive) =
+            opt(terminated(keyword("namespace"), multispace1)).parse(input)?;
+        let (input, _) = opt(terminated(keyword("typename"), multispace1)).parse(input)?;
+        let (input, _) = opt(tag("::")).parse(input)?;
+        let (input, path) = separated_list1(tag("::"), parse_str).parse(input)?;
+        let (input, _) = preceded(multispace0, char(';')).parse(input)?;
+
+        let using = match directive {
+            Some(_) => CppUsing::Directive(path),
+            None => CppUsing::Declaration(path),
+        };
+
+        Ok((input, using))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::cpp::using::CppUsing;
+    use crate::types::Parsable;
+
+    #[test]
+    fn using_declaration() {
+        let result = CppUsing::parse("using Super::BeginPlay;");
+        assert_eq!(result, Ok(("", CppUsing::Declaration(vec!["Super", "BeginPlay"]))));
+        assert!(!result.unwrap().1.is_inheriting_constructor());
+    }
+
+    #[test]
+    fn using_inheriting_constructor() {
+        let (_, using) = CppUsing::parse("using FBase::FBase;").unwrap();
+        assert!(using.is_inheriting_constructor());
+    }
+
+    #[test]
+    fn using_directive() {
+        let result = CppUsing::parse("using namespace UE::Math;");
+        assert_eq!(result, Ok(("", CppUsing::Directive(vec!["UE", "Math"]))));
+    }
+
+    #[test]
+    fn using_alias_is_not_a_declaration() {
+        assert!(CppUsing::parse("using FReal = double;").is_err());
+    }
+}
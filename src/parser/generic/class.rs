@@ -1,19 +1,24 @@
-﻿use crate::parser::cpp::ctype::{CType, parse_cpp_type};
+﻿use crate::parser::cpp::alias::{CppAlias, parse_aliases};
+use crate::parser::cpp::attribute::{CppAttribute, parse_attributes};
+use crate::parser::cpp::cenum::{CppEnum, cpp_enum};
+use crate::parser::cpp::ctype::{CType, parse_cpp_type};
 use crate::parser::cpp::template::parse_template;
+use crate::parser::cpp::using::CppUsing;
 use crate::parser::generic::annotation::Annotation;
 use crate::parser::generic::comment::parse_comment;
-use crate::parser::generic::member::{Member, parse_member};
+use crate::parser::generic::ignore::IgnoreRules;
+use crate::parser::generic::member::{Member, parse_declared_members, parse_members};
 use crate::parser::generic::method::{Method, parse_method};
-use crate::parser::{parse_ws_str, ws};
+use crate::parser::{keyword, parse_ws_str, ws};
+use crate::types::Parsable;
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take_until};
 use nom::character::complete::{char, multispace0, multispace1};
-use nom::combinator::{map, map_res, opt, value};
-use nom::error::ParseError;
+use nom::combinator::{map, opt, peek, value};
 use nom::multi::{many0, separated_list1};
 use nom::sequence::{delimited, preceded, terminated};
 use nom::{IResult, Parser};
-use nom_language::error::VerboseError;
+use nom_language::error::{VerboseError, VerboseErrorKind};
 use std::collections::HashMap;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -43,6 +48,14 @@ impl From<&str> for InheritanceVisibility {
     }
 }
 
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub enum ClassKind {
+    #[default]
+    Class,
+    Struct,
+    Union,
+}
+
 pub trait Class<'a> {
     type ClassAnnotation: Annotation<'a> + 'a;
     type MemberAnnotation: Annotation<'a> + 'a;
@@ -50,17 +63,59 @@ pub trait Class<'a> {
     type Method: Method<'a> + 'a;
     type Member: Member<'a> + 'a;
 
-    fn class(
-        name: &'a str,
-        api: Option<&'a str>,
-        parents: Vec<CppParentClass<'a>>,
-        methods: HashMap<InheritanceVisibility, Vec<Self::Method>>,
-        members: HashMap<InheritanceVisibility, Vec<Self::Member>>,
-        inner_classes: HashMap<InheritanceVisibility, Vec<Self>>,
-        annotations: Option<Vec<Self::ClassAnnotation>>,
-    ) -> Self
+    fn class(definition: ClassDefinition<'a, Self>) -> Self
     where
         Self: 'a + Sized;
+
+    /// Empty for anonymous structs and unions
+    fn name(&self) -> &'a str;
+}
+
+/// Everything [`parse_class`] collects for one class, passed to [`Class::class`]
+pub struct ClassDefinition<'a, Ctx>
+where
+    Ctx: Class<'a>,
+{
+    /// Empty for anonymous structs and unions
+    pub name: &'a str,
+    pub kind: ClassKind,
+    pub api: Option<&'a str>,
+    pub parents: Vec<CppParentClass<'a>>,
+    pub methods: HashMap<InheritanceVisibility, Vec<Ctx::Method>>,
+    pub members: HashMap<InheritanceVisibility, Vec<Ctx::Member>>,
+    pub inner_classes: HashMap<InheritanceVisibility, Vec<Ctx>>,
+    pub anonymous_aggregates: HashMap<InheritanceVisibility, Vec<Ctx>>,
+    pub aliases: HashMap<InheritanceVisibility, Vec<CppAlias<'a>>>,
+    pub enums: HashMap<InheritanceVisibility, Vec<CppEnum<'a>>>,
+    pub usings: HashMap<InheritanceVisibility, Vec<CppUsing<'a>>>,
+    pub friends: Vec<CType<'a>>,
+    pub attributes: Vec<CppAttribute<'a>>,
+    pub annotations: Vec<Ctx::ClassAnnotation>,
+}
+
+impl<'a, Ctx> ClassDefinition<'a, Ctx>
+where
+    Ctx: Class<'a>,
+{
+    /// A class without parents and with an empty body
+    pub fn new(name: &'a str, kind: ClassKind) -> Self {
+        Self {
+            name,
+            kind,
+            api: None,
+            parents: vec![],
+            methods: HashMap::new(),
+            members: HashMap::new(),
+            inner_classes: HashMap::new(),
+            anonymous_aggregates: HashMap::new(),
+            aliases: HashMap::new(),
+            enums: HashMap::new(),
+            usings: HashMap::new(),
+            friends: vec![],
+            attributes: vec![],
+            annotations: vec![],
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -71,23 +126,28 @@ pub struct ParentClass<'a> {
 
 pub fn parse_class<'a, Ctx>(
     input: &'a str,
-    ignore_statements: &Vec<fn(&'a str) -> IResult<&'a str, &'a str, VerboseError<&'a str>>>,
+    ignore_rules: &IgnoreRules,
 ) -> IResult<&'a str, Ctx, VerboseError<&'a str>>
 where
     Ctx: Class<'a> + 'a,
 {
     let (input, _) = opt(parse_comment::<Ctx::Comment>).parse(input)?;
-    let (input, annotations) = opt(many0(|i| Ctx::ClassAnnotation::parse(i))).parse(input)?;
+    let (input, annotations) =
+        opt(many0(preceded(multispace0, Ctx::ClassAnnotation::parse))).parse(input)?;
 
     let (input, _) = opt(parse_template).parse(input)?;
-    let (input, _) = parse_class_identifier(input)?;
-    let (input, maybe_api) = parse_ws_str(input)?;
-    let (input, maybe_name_result) = opt(parse_ws_str).parse(input)?;
-
-    let (api, name) = match maybe_name_result {
-        Some(name) => (Some(maybe_api), name), // two identifiers → api + name
-        None => (None, maybe_api),             // only one → name, no api
-    };
+    let (input, kind) = parse_class_identifier(input)?;
+    // `struct [[deprecated]] alignas(16) FVector`
+    let (input, _) = multispace0(input)?;
+    let (input, attributes) = parse_attributes(input)?;
+    // API macros like `CORE_API` are expanded to `[[api(CORE_API)]]` by the macro table
+    let (api, attributes): (Vec<_>, Vec<_>) = attributes
+        .into_iter()
+        .partition(|attribute| attribute.name == "api");
+    let api = api.first().and_then(|attribute| attribute.arguments);
+    // no name → anonymous struct or union
+    let (input, name) = opt(parse_ws_str).parse(input)?;
+    let name = name.unwrap_or_default();
 
     // ignore template specialisation atm
     let input = match parse_ignore_template(input) {
@@ -98,31 +158,26 @@ where
 
     // Return early for empty classes (e.g. forward declaration)
     let (input, empty) = opt(char::<_, VerboseError<&str>>(';')).parse(input)?;
+    let mut class = ClassDefinition::<Ctx> {
+        attributes,
+        annotations: annotations.unwrap_or_default(),
+        ..ClassDefinition::new(name, kind)
+    };
     if empty.is_some() {
-        return Ok((
-            input,
-            Class::class(
-                name,
-                None,
-                vec![],
-                HashMap::new(),
-                HashMap::new(),
-                HashMap::new(),
-                annotations,
-            ),
-        ));
+        return Ok((input, Class::class(class)));
     }
 
     let (input, parents) = opt(parse_inheritance).parse(input)?;
-
-    let mut methods: HashMap<InheritanceVisibility, Vec<Ctx::Method>> = HashMap::from([]);
-
-    let mut members: HashMap<InheritanceVisibility, Vec<Ctx::Member>> = HashMap::from([]);
-    let mut inner_classes: HashMap<InheritanceVisibility, Vec<Ctx>> = HashMap::from([]);
+    class.api = api;
+    class.parents = parents.unwrap_or_default();
 
     // now parse the body
     let (input, _) = char('{')(input)?;
-    let mut current_access = InheritanceVisibility::Private;
+    // only class bodies start private
+    let mut current_access = match class.kind {
+        ClassKind::Class => InheritanceVisibility::Private,
+        ClassKind::Struct | ClassKind::Union => InheritanceVisibility::Public,
+    };
 
     let mut items = Vec::new();
     let mut input = input;
@@ -134,46 +189,53 @@ where
         if trimmed.starts_with('}') {
             break;
         }
+        // Unterminated body, e.g. a truncated header
+        if trimmed.is_empty() {
+            return Err(nom::Err::Error(VerboseError {
+                errors: vec![(trimmed, VerboseErrorKind::Char('}'))],
+            }));
+        }
 
-        match parse_class_item::<Ctx>(input, ignore_statements) {
+        match parse_class_item::<Ctx>(input, ignore_rules) {
             Ok((next_input, item)) => {
                 items.push(item);
                 input = next_input;
             }
-            Err(e) => {
-                println!("{:#?}", e);
-                return Err(e)
-            }, // This error now reflects the *first failing item*
+            Err(e) => return Err(e), // This error now reflects the *first failing item*
         }
     }
 
     for item in items {
+        let access = current_access.clone();
         match item {
             ClassItem::Access(a) => current_access = a,
-            ClassItem::Method(m) => methods.entry(current_access.clone()).or_default().push(m),
-            ClassItem::Member(mem) => members.entry(current_access.clone()).or_default().push(mem),
-            ClassItem::Class(inner_class) => inner_classes
-                .entry(current_access.clone())
+            ClassItem::Method(mut m) => {
+                m.declared_in(name);
+                class.methods.entry(access).or_default().push(m)
+            }
+            ClassItem::Members(mem) => class.members.entry(access).or_default().extend(mem),
+            ClassItem::Class(inner_class) => class.inner_classes.entry(access).or_default().push(inner_class),
+            ClassItem::ClassWithMembers(inner_class, mem) => {
+                class.inner_classes.entry(access.clone()).or_default().push(inner_class);
+                class.members.entry(access).or_default().extend(mem);
+            }
+            ClassItem::AnonymousAggregate(aggregate) => class
+                .anonymous_aggregates
+                .entry(access)
                 .or_default()
-                .push(inner_class),
+                .push(aggregate),
+            ClassItem::Aliases(alias) => class.aliases.entry(access).or_default().extend(alias),
+            ClassItem::Enum(e) => class.enums.entry(access).or_default().push(e),
+            ClassItem::Using(using) => class.usings.entry(access).or_default().push(using),
+            ClassItem::Friend(friend) => class.friends.push(friend),
             _ => {}
         }
     }
 
+    let (input, _) = preceded(multispace0, char('}')).parse(input)?;
     let (input, _) = opt(char(';')).parse(input)?;
 
-    Ok((
-        input,
-        Class::class(
-            name,
-            api,
-            parents.unwrap_or_default(),
-            methods,
-            members,
-            inner_classes,
-            annotations,
-        ),
-    ))
+    Ok((input, Class::class(class)))
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -185,15 +247,88 @@ where
     Ignore,
     Access(InheritanceVisibility),
     Method(ClassType::Method),
-    Member(ClassType::Member),
+    Members(Vec<ClassType::Member>),
     Class(ClassType),
+    /// Class definition that also declares members, e.g. `struct { int32 X; } Point;`
+    ClassWithMembers(ClassType, Vec<ClassType::Member>),
+    AnonymousAggregate(ClassType),
+    Aliases(Vec<CppAlias<'a>>),
+    Enum(CppEnum<'a>),
+    Using(CppUsing<'a>),
+    Friend(CType<'a>),
     Comment(ClassType::Comment),
     End,
 }
 
-fn parse_class_identifier(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
-    alt((tag("class"), tag("struct"))).parse(input)
+fn parse_class_identifier(input: &str) -> IResult<&str, ClassKind, VerboseError<&str>> {
+    alt((
+        value(ClassKind::Class, keyword("class")),
+        value(ClassKind::Struct, keyword("struct")),
+        value(ClassKind::Union, keyword("union")),
+    ))
+    .parse(input)
+}
+
+/// Matches the start of an unnamed struct or union member like `union { ... };`
+fn anonymous_aggregate_start(input: &str) -> IResult<&str, (), VerboseError<&str>> {
+    value(
+        (),
+        peek((
+            opt(parse_comment::<String>),
+            multispace0,
+            alt((keyword("struct"), keyword("union"))),
+            multispace0,
+            char('{'),
+        )),
+    )
+    .parse(input)
+}
+
+/// Class definition inside a class body. Declarators after the body like
+/// `union { float Value; int32 Bits; } Data;` declare members of the enclosing class, without
+/// them an unnamed struct or union is an anonymous aggregate.
+fn nested_class<'a, Ctx>(
+    input: &'a str,
+    ignore_rules: &IgnoreRules,
+) -> IResult<&'a str, ClassItem<'a, Ctx>, VerboseError<&'a str>>
+where
+    Ctx: Class<'a>,
+{
+    let (input, anonymous) = opt(anonymous_aggregate_start).parse(input)?;
+    let (input, class) = parse_class::<Ctx>(input, ignore_rules)?;
+
+    // members of an unnamed type have an empty type path
+    let ctype = CType::Path(match class.name() {
+        "" => vec![],
+        name => vec![name],
+    });
+    let (input, members) = opt(preceded(multispace0, |i| parse_declared_members(i, &ctype)))
+        .parse(input)?;
+
+    let item = match (members, anonymous) {
+        (Some(members), _) => ClassItem::ClassWithMembers(class, members),
+        (None, Some(_)) => ClassItem::AnonymousAggregate(class),
+        (None, None) => ClassItem::Class(class),
+    };
+
+    Ok((input, item))
 }
+
+/// `friend class FOther;`, `friend struct FOther;` or `friend FOther;`. Friend functions are
+/// parsed as methods with the `friend` storage qualifier.
+fn friend_class(input: &str) -> IResult<&str, CType<'_>, VerboseError<&str>> {
+    delimited(
+        (
+            keyword("friend"),
+            multispace1,
+            opt((alt((keyword("class"), keyword("struct"))), multispace1)),
+        ),
+        parse_cpp_type,
+        preceded(multispace0, char(';')),
+    )
+    .parse(input)
+}
+
 fn parse_inheritance_visibility(
     input: &str,
 ) -> IResult<&str, InheritanceVisibility, VerboseError<&str>> {
@@ -216,13 +351,13 @@ fn parse_inheritance_visibility(
     Ok((input, visibility))
 }
 
-fn parse_single_inheritance(input: &str) -> IResult<&str, CppParentClass, VerboseError<&str>> {
+fn parse_single_inheritance(input: &str) -> IResult<&str, CppParentClass<'_>, VerboseError<&str>> {
     let (input, visibility) = parse_inheritance_visibility(input)?;
     let (input, name) = ws(parse_cpp_type).parse(input)?;
 
     Ok((input, CppParentClass { name, visibility }))
 }
-fn parse_inheritance(input: &str) -> IResult<&str, Vec<CppParentClass>, VerboseError<&str>> {
+fn parse_inheritance(input: &str) -> IResult<&str, Vec<CppParentClass<'_>>, VerboseError<&str>> {
     let (input, _) = multispace0(input)?;
     let (input, _) = char(':')(input)?;
     let (input, parent_classes) =
@@ -231,24 +366,9 @@ fn parse_inheritance(input: &str) -> IResult<&str, Vec<CppParentClass>, VerboseE
     Ok((input, parent_classes))
 }
 
-fn try_ignore<'a, ClassType>(
-    input: &'a str,
-    ignore_parsers: &Vec<fn(&'a str) -> IResult<&'a str, &'a str, VerboseError<&'a str>>>,
-) -> Option<(&'a str, ClassItem<'a, ClassType>)>
-where
-    ClassType: Class<'a>,
-{
-    for parser in ignore_parsers {
-        if let Ok((i, _)) = parser(input) {
-            return Some((i, ClassItem::Ignore));
-        }
-    }
-    None
-}
-
 fn parse_class_item<'a, Ctx>(
     input: &'a str,
-    ignore_statements: &Vec<fn(&'a str) -> IResult<&'a str, &'a str, VerboseError<&'a str>>>,
+    ignore_rules: &IgnoreRules,
 ) -> IResult<&'a str, ClassItem<'a, Ctx>, VerboseError<&'a str>>
 where
     Ctx: Class<'a>,
@@ -256,26 +376,17 @@ where
     let (input, item) = preceded(
         multispace0,
         alt((
-            map_res(
-                |input| {
-                    for parser in ignore_statements {
-                        if let Ok((next_input, _)) = parser(input) {
-                            return Ok((next_input, ClassItem::Ignore));
-                        }
-                    }
-                    Err(nom::Err::Error(VerboseError::from_error_kind(
-                        input,
-                        nom::error::ErrorKind::Alt,
-                    )))
-                },
-                Ok::<_, VerboseError<&str>>,
-            ),
+            map(|i| ignore_rules.parse(i), |_| ClassItem::Ignore),
             map(alt((char(';'), char('\n'))), |_| ClassItem::Ignore),
             map(multispace1, |_| ClassItem::Ignore),
             map(access_specifier, ClassItem::Access),
-            map(|i| parse_class(i, ignore_statements), ClassItem::Class),
+            map(|i| parse_aliases(i, ignore_rules), ClassItem::Aliases),
+            map(CppUsing::parse, ClassItem::Using),
+            map(cpp_enum, ClassItem::Enum),
+            map(friend_class, ClassItem::Friend),
+            |i| nested_class(i, ignore_rules),
             map(parse_method, ClassItem::Method),
-            map(parse_member, ClassItem::Member),
+            map(parse_members, ClassItem::Members),
             map(parse_comment, ClassItem::Comment),
             map(preceded(char('}'), opt(char(';'))), |_| ClassItem::End),
         )),
@@ -291,7 +402,7 @@ fn access_specifier(input: &str) -> IResult<&str, InheritanceVisibility, Verbose
             alt((tag("public"), tag("private"), tag("protected"))),
             (tag(":"), multispace0),
         ),
-        |t| InheritanceVisibility::from(t),
+        InheritanceVisibility::from,
     )
     .parse(input)?;
 
@@ -32,7 +32,7 @@ fn parse_multiline_comment(input: &str) -> IResult<&str, Vec<&str>, VerboseError
 
     let stripped_content = lines
         .lines()
-        .map(|line| strip_indent(line))
+        .map(strip_indent)
         .filter(|line| !line.trim().is_empty())
         .collect::<Vec<&str>>();
 
@@ -0,0 +1,227 @@
+﻿use crate::parser::cpp::attribute::parenthesized;
+use nom::bytes::complete::{tag, take_while1};
+use nom::character::complete::{char, space0};
+use nom::combinator::opt;
+use nom::error::{ErrorKind, ParseError};
+use nom::sequence::preceded;
+use nom::{IResult, Parser};
+use nom_language::error::VerboseError;
+use regex::Regex;
+use std::fmt;
+
+/// A statement the parser skips, like `GENERATED_BODY()` in a class body
+#[derive(Debug, Clone)]
+pub enum IgnoreRule {
+    /// Exact text, e.g. `GENERATED_BODY()`. A token ending in an identifier character does not
+    /// match the start of a longer identifier.
+    Literal(String),
+    /// Pattern matched at the current position, e.g. `DECLARE_\w+\(.*\)`
+    Regex(Regex),
+    /// Macro invocation with balanced parentheses, e.g. `UE_DECLARE_THING(A, (B, C))`
+    MacroCall(String),
+}
+
+impl IgnoreRule {
+    fn parse<'a>(&self, input: &'a str) -> IResult<&'a str, &'a str, VerboseError<&'a str>> {
+        match self {
+            IgnoreRule::Literal(token) => {
+                let (rest, matched) = tag(token.as_str()).parse(input)?;
+                let ends_in_identifier = token.ends_with(is_identifier_char);
+                if ends_in_identifier && rest.starts_with(is_identifier_char) {
+                    return Err(error(input));
+                }
+                Ok((rest, matched))
+            }
+            IgnoreRule::Regex(regex) => match regex.find(input) {
+                Some(found) if found.end() > 0 => Ok((&input[found.end()..], found.as_str())),
+                _ => Err(error(input)),
+            },
+            IgnoreRule::MacroCall(name) => {
+                let (rest, identifier) = take_while1(is_identifier_char).parse(input)?;
+                if identifier != name {
+                    return Err(error(input));
+                }
+                let (rest, _) = (space0, parenthesized).parse(rest)?;
+                Ok((rest, &input[..input.len() - rest.len()]))
+            }
+        }
+    }
+}
+
+/// Error while loading rules with [`IgnoreRules::from_config`], `line` starts at 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoreRulesError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for IgnoreRulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for IgnoreRulesError {}
+
+/// Statements skipped in class bodies, namespaces and headers. Teams can teach the parser their
+/// own macros by listing them in a configuration file, see [`IgnoreRules::from_config`].
+#[derive(Debug, Default, Clone)]
+pub struct IgnoreRules {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    /// The generated body macros UHT expects in reflected types
+    pub fn unreal() -> Self {
+        [
+            "GENERATED_BODY",
+            "GENERATED_UCLASS_BODY",
+            "GENERATED_USTRUCT_BODY",
+            "GENERATED_UINTERFACE_BODY",
+            "GENERATED_IINTERFACE_BODY",
+        ]
+        .into_iter()
+        .fold(IgnoreRules::default(), IgnoreRules::macro_call)
+    }
+
+    /// Loads one rule per line, written as `literal <text>`, `regex <pattern>` or
+    /// `macro <name>`. Empty lines and lines starting with `#` are skipped.
+    ///
+    /// ```text
+    /// # skipped in every class body
+    /// macro GENERATED_BODY
+    /// literal MYGAME_BODY_END;
+    /// regex DECLARE_\w+_DELEGATE\w*\([^)]*\);
+    /// ```
+    pub fn from_config(config: &str) -> Result<Self, IgnoreRulesError> {
+        let mut rules = IgnoreRules::default();
+        for (index, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: String| IgnoreRulesError {
+                line: index + 1,
+                message,
+            };
+            let (kind, value) = line
+                .split_once(char::is_whitespace)
+                .map(|(kind, value)| (kind, value.trim()))
+                .ok_or_else(|| error(format!("missing value for `{line}`")))?;
+
+            rules = match kind {
+                "literal" => rules.literal(value),
+                "macro" => rules.macro_call(value),
+                "regex" => rules
+                    .regex(value)
+                    .map_err(|e| error(format!("invalid regex `{value}`: {e}")))?,
+                _ => return Err(error(format!("unknown rule kind `{kind}`"))),
+            };
+        }
+
+        Ok(rules)
+    }
+
+    pub fn literal(mut self, token: &str) -> Self {
+        self.rules.push(IgnoreRule::Literal(token.to_string()));
+        self
+    }
+
+    /// The pattern is anchored at the current position, a leading `^` is not needed
+    pub fn regex(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        let regex = Regex::new(&format!("^(?:{pattern})"))?;
+        self.rules.push(IgnoreRule::Regex(regex));
+        Ok(self)
+    }
+
+    pub fn macro_call(mut self, name: &str) -> Self {
+        self.rules.push(IgnoreRule::MacroCall(name.to_string()));
+        self
+    }
+
+    /// Combines both rule sets, e.g. project rules on top of [`IgnoreRules::unreal`]
+    pub fn extend(mut self, other: IgnoreRules) -> Self {
+        self.rules.extend(other.rules);
+        self
+    }
+
+    /// Matches the first rule that applies at the start of `input`, including a `;` after it
+    pub fn parse<'a>(&self, input: &'a str) -> IResult<&'a str, &'a str, VerboseError<&'a str>> {
+        for rule in &self.rules {
+            if let Ok((rest, _)) = rule.parse(input) {
+                let (rest, _) = opt(preceded(space0, char(';'))).parse(rest)?;
+                return Ok((rest, &input[..input.len() - rest.len()]));
+            }
+        }
+
+        Err(error(input))
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn error(input: &str) -> nom::Err<VerboseError<&str>> {
+    nom::Err::Error(VerboseError::from_error_kind(input, ErrorKind::Verify))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::generic::ignore::{IgnoreRules, IgnoreRulesError};
+
+    #[test]
+    fn rule_kinds() {
+        let rules = IgnoreRules::default()
+            .literal("MYGAME_BODY")
+            .macro_call("UE_DECLARE_THING")
+            .regex(r"DECLARE_\w+_DELEGATE\w*\([^)]*\)")
+            .unwrap();
+
+        assert_eq!(
+            rules.parse("MYGAME_BODY\nint32 A;"),
+            Ok(("\nint32 A;", "MYGAME_BODY"))
+        );
+        // literals ending in an identifier don't match longer identifiers
+        assert!(rules.parse("MYGAME_BODY_END").is_err());
+        assert_eq!(
+            rules.parse("UE_DECLARE_THING (A, (B, C));\n"),
+            Ok(("\n", "UE_DECLARE_THING (A, (B, C));"))
+        );
+        assert!(rules.parse("UE_DECLARE_THINGS(A)").is_err());
+        assert_eq!(
+            rules.parse("DECLARE_DYNAMIC_MULTICAST_DELEGATE_OneParam(FOnDeath, AActor*, Killer);"),
+            Ok((
+                "",
+                "DECLARE_DYNAMIC_MULTICAST_DELEGATE_OneParam(FOnDeath, AActor*, Killer);"
+            ))
+        );
+        // regexes are anchored at the current position
+        assert!(rules.parse("int32 A; DECLARE_DELEGATE(F)").is_err());
+    }
+
+    #[test]
+    fn rules_from_config() {
+        let config = r#"
+# project macros
+macro GENERATED_BODY
+literal MYGAME_BODY_END;
+regex   MYGAME_\w+\(\)
+"#;
+        let rules = IgnoreRules::from_config(config).unwrap();
+        assert!(rules.parse("GENERATED_BODY()").is_ok());
+        assert!(rules.parse("MYGAME_BODY_END;").is_ok());
+        assert!(rules.parse("MYGAME_REPLICATED()").is_ok());
+
+        assert_eq!(
+            IgnoreRules::from_config("macro A\nkeyword B").unwrap_err(),
+            IgnoreRulesError {
+                line: 2,
+                message: "unknown rule kind `keyword`".to_string()
+            }
+        );
+        assert_eq!(IgnoreRules::from_config("regex (").unwrap_err().line, 1);
+        assert_eq!(IgnoreRules::from_config("\n\nliteral").unwrap_err().line, 3);
+    }
+}
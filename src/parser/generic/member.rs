@@ -1,80 +1,213 @@
-﻿use crate::parser::cpp::ctype::{CType, parse_cpp_type};
+﻿use crate::parser::cpp::attribute::{CppAttribute, parse_attributes};
+use crate::parser::cpp::ctype::{CType, parse_cpp_type};
+use crate::parser::cpp::expression::{CppExpression, init_list, parse_expression};
 use crate::parser::cpp::member::CppMemberModifier;
 use crate::parser::generic::annotation::Annotation;
 use crate::parser::generic::comment::parse_comment;
-use crate::parser::parse_ws_str;
+use crate::parser::{keyword, parse_ws_str};
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::{char, multispace0};
-use nom::combinator::{map, opt};
-use nom::multi::many0;
-use nom::sequence::{delimited, preceded};
+use nom::bytes::complete::take_till1;
+use nom::character::complete::{char, multispace0, one_of};
+use nom::combinator::{map, map_opt, opt};
+use nom::multi::{many0, separated_list1};
+use nom::sequence::{delimited, preceded, terminated};
 use nom::{IResult, Parser};
 use nom_language::error::VerboseError;
 
 pub trait Member<'a> {
-    type Annotation: Annotation<'a> + 'a;
-    type Comment: From<String>;
+    type Annotation: Annotation<'a> + Clone + 'a;
+    type Comment: From<String> + Clone;
 
     fn member(
         name: &'a str,
         ctype: CType<'a>,
-        default_value: Option<CType<'a>>,
+        default_value: Option<CppExpression<'a>>,
         comment: Option<Self::Comment>,
         modifiers: Vec<CppMemberModifier>,
+        attributes: Vec<CppAttribute<'a>>,
         annotations: Vec<Self::Annotation>,
     ) -> Self;
 }
+/// Parses a declaration with exactly one declarator, e.g. `int32 X = 1`, see [`parse_members`]
 pub fn parse_member<'a, MemberType>(
     input: &'a str,
 ) -> IResult<&'a str, MemberType, VerboseError<&'a str>>
+where
+    MemberType: 'a + Member<'a>,
+{
+    map_opt(parse_members, |mut members: Vec<MemberType>| match members.len() {
+        1 => members.pop(),
+        _ => None,
+    })
+    .parse(input)
+}
+
+/// Parses a declaration that may declare several names at once, e.g. `int32 X, *Y = nullptr;`.
+/// Every declarator becomes its own member sharing the base type, the modifiers and the
+/// preceding comment. `*` and `&` belong to their declarator.
+pub fn parse_members<'a, MemberType>(
+    input: &'a str,
+) -> IResult<&'a str, Vec<MemberType>, VerboseError<&'a str>>
 where
     MemberType: 'a + Member<'a>,
 {
     let (input, comment) = opt(parse_comment::<MemberType::Comment>).parse(input)?;
 
-    let (input, annotations) = opt(many0(|i| Annotation::parse(i))).parse(input)?;
+    let (input, annotations) =
+        opt(many0(preceded(multispace0, Annotation::parse))).parse(input)?;
     let (input, _) = multispace0.parse(input)?;
+    let (input, mut attributes) = parse_attributes(input)?;
     let (input, modifiers) = parse_modifiers(input)?;
     let (input, _) = multispace0.parse(input)?;
+    // `static CORE_API int32 Count;` after macro expansion
+    let (input, modifier_attributes) = parse_attributes(input)?;
+    attributes.extend(modifier_attributes);
     let (input, ctype) = parse_cpp_type(input)?;
+    // the parsed type ends in the indirections of the first declarator
+    let (ctype, indirections) = strip_indirections(ctype);
+    let (input, mut declarators) = separated_list1(char(','), parse_declarator).parse(input)?;
+    declarators[0].0.splice(0..0, indirections);
+
+    let annotations = annotations.unwrap_or_default();
+    let members = declarators
+        .into_iter()
+        .map(|(indirections, name, extents, default_value)| {
+            MemberType::member(
+                name,
+                array_type(indirect_type(ctype.clone(), indirections), extents),
+                default_value,
+                comment.clone(),
+                modifiers.clone(),
+                attributes.clone(),
+                annotations.clone(),
+            )
+        })
+        .collect();
+
+    Ok((input, members))
+}
+
+/// Members declared after a class body like `struct { ... } A, B[2];`, all of type `ctype`
+pub(crate) fn parse_declared_members<'a, MemberType>(
+    input: &'a str,
+    ctype: &CType<'a>,
+) -> IResult<&'a str, Vec<MemberType>, VerboseError<&'a str>>
+where
+    MemberType: 'a + Member<'a>,
+{
+    let (input, declarators) =
+        terminated(separated_list1(char(','), parse_declarator), char(';')).parse(input)?;
+
+    let members = declarators
+        .into_iter()
+        .map(|(indirections, name, extents, default_value)| {
+            MemberType::member(
+                name,
+                array_type(indirect_type(ctype.clone(), indirections), extents),
+                default_value,
+                None,
+                vec![],
+                vec![],
+                vec![],
+            )
+        })
+        .collect();
+
+    Ok((input, members))
+}
+
+type Declarator<'a> = (
+    Vec<char>,
+    &'a str,
+    Vec<Option<&'a str>>,
+    Option<CppExpression<'a>>,
+);
+
+/// `*Next = nullptr` or `Values[4]`, the indirections are in source order
+fn parse_declarator(input: &str) -> IResult<&str, Declarator<'_>, VerboseError<&str>> {
+    let (input, indirections) = declarator_indirections(input)?;
     let (input, name) = parse_ws_str(input)?;
+    let (input, extents) = array_extents(input)?;
     let (input, _) = multispace0.parse(input)?;
 
+    // `= 1.f`, `= {1, 2}` or a direct brace initializer like `X{1.f}`
     let (input, default_value) = opt(alt((
-        delimited(
-            char('{'),
-            delimited(multispace0, parse_cpp_type, multispace0),
-            char('}'),
-        ),
-        preceded(
-            char('='),
-            delimited(multispace0, parse_cpp_type, multispace0),
-        ),
+        map(init_list, |items| CppExpression::InitList(None, items)),
+        preceded((char('='), multispace0), parse_expression),
     )))
     .parse(input)?;
+    let (input, _) = multispace0.parse(input)?;
+
+    Ok((input, (indirections, name, extents, default_value)))
+}
+
+/// `*` and `&` in front of a declarator name
+pub(crate) fn declarator_indirections(input: &str) -> IResult<&str, Vec<char>, VerboseError<&str>> {
+    many0(preceded(multispace0, one_of("*&"))).parse(input)
+}
+
+/// Splits the trailing pointers and references off `ctype`, `int32**` is `int32` and `['*', '*']`
+pub(crate) fn strip_indirections(mut ctype: CType) -> (CType, Vec<char>) {
+    let mut indirections = vec![];
+    while let CType::Pointer(inner) | CType::Reference(inner) = &ctype {
+        let indirection = match ctype {
+            CType::Reference(_) => '&',
+            _ => '*',
+        };
+        indirections.insert(0, indirection);
+        ctype = (**inner).clone();
+    }
 
-    Ok((
-        input,
-        MemberType::member(
-            name,
-            ctype,
-            default_value,
-            comment,
-            modifiers,
-            annotations.unwrap_or_default(),
+    (ctype, indirections)
+}
+
+/// Wraps `ctype` in the indirections of a declarator like `*&Ref`
+pub(crate) fn indirect_type(ctype: CType, indirections: Vec<char>) -> CType {
+    indirections
+        .into_iter()
+        .fold(ctype, |ctype, indirection| match indirection {
+            '&' => CType::Reference(Box::new(ctype)),
+            _ => CType::Pointer(Box::new(ctype)),
+        })
+}
+
+/// Extents of an array declarator like `[3]` or `[4][]`, empty for anything that is not an array
+pub(crate) fn array_extents(input: &str) -> IResult<&str, Vec<Option<&str>>, VerboseError<&str>> {
+    many0(terminated(
+        delimited(
+            char('['),
+            opt(map(take_till1(|c| c == ']'), str::trim)),
+            char(']'),
         ),
+        multispace0,
     ))
+    .parse(input)
+}
+
+/// Wraps `ctype` for a declarator like `XYZ[3]`, `M[4][4]` is an array of 4 arrays of 4
+pub(crate) fn array_type<'a>(ctype: CType<'a>, extents: Vec<Option<&'a str>>) -> CType<'a> {
+    extents
+        .into_iter()
+        .rev()
+        .fold(ctype, |ctype, extent| CType::Array(Box::new(ctype), extent))
 }
 
 fn parse_modifier(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
     preceded(
         multispace0,
-        alt((tag("static"), tag("const"), tag("inline"))),
+        alt((
+            keyword("static"),
+            keyword("const"),
+            keyword("inline"),
+            keyword("constexpr"),
+            keyword("extern"),
+            keyword("thread_local"),
+            keyword("mutable"),
+        )),
     )
     .parse(input)
 }
 
 fn parse_modifiers(input: &str) -> IResult<&str, Vec<CppMemberModifier>, VerboseError<&str>> {
-    many0(map(parse_modifier, |x| CppMemberModifier::from(x))).parse(input)
+    many0(map(parse_modifier, CppMemberModifier::from)).parse(input)
 }
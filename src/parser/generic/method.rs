@@ -1,13 +1,18 @@
-﻿use crate::parser::cpp::ctype::{CType, parse_cpp_type};
-use crate::parser::cpp::method::{parse_brace_block, parse_method_params, CppMethodParam};
+﻿use crate::parser::cpp::attribute::{CppAttribute, parenthesized, parse_attributes};
+use crate::parser::cpp::ctype::{CType, parse_cpp_type};
+use crate::parser::cpp::method::{
+    CppMemberInitializer, CppMethodParam, parse_brace_block, parse_member_initializer,
+    parse_method_params,
+};
 use crate::parser::cpp::template::parse_template;
 use crate::parser::generic::annotation::Annotation;
 use crate::parser::generic::comment::parse_comment;
-use crate::parser::ws;
+use crate::parser::{keyword, ws};
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take_while1};
 use nom::character::complete::{char, multispace0, multispace1};
-use nom::combinator::{map, opt, recognize};
+use nom::combinator::{map, opt, recognize, value, verify};
+use nom::error::{ErrorKind, ParseError};
 use nom::multi::{many0, separated_list0};
 use nom::sequence::{delimited, preceded};
 use nom::{IResult, Parser};
@@ -18,19 +23,33 @@ pub trait Method<'a> {
 
     type Comment: From<String>;
 
-    fn method(
-        name: &'a str,
-        return_type: Option<CType<'a>>,
-        template_params: Vec<CType<'a>>,
-        params: Vec<CppMethodParam<'a>>,
-        storage_qualifiers: Vec<CppStorageQualifier>,
-        post_param_qualifiers: Vec<PostParamQualifier>,
-        special: Option<SpecialMember>,
-        comment: Option<Self::Comment>,
-        annotations: Vec<Self::MethodAnnotation>,
-    ) -> Self
+    fn method(definition: MethodDefinition<'a, Self>) -> Self
     where
-        Self: 'a;
+        Self: 'a + Sized;
+
+    /// Called by the class parser for every method declared in the body of `class_name`
+    fn declared_in(&mut self, _class_name: &str) {}
+}
+
+/// Everything [`parse_method`] collects for one function, passed to [`Method::method`]
+pub struct MethodDefinition<'a, MethodType>
+where
+    MethodType: Method<'a> + ?Sized,
+{
+    pub name: &'a str,
+    /// `None` for `void` and for functions without return type like constructors
+    pub return_type: Option<CType<'a>>,
+    pub template_params: Vec<CType<'a>>,
+    pub params: Vec<CppMethodParam<'a>>,
+    pub storage_qualifiers: Vec<CppStorageQualifier>,
+    pub post_param_qualifiers: Vec<PostParamQualifier>,
+    pub special: Option<SpecialMember>,
+    pub initializers: Vec<CppMemberInitializer<'a>>,
+    /// Inline definition including its braces
+    pub body: Option<&'a str>,
+    pub comment: Option<MethodType::Comment>,
+    pub attributes: Vec<CppAttribute<'a>>,
+    pub annotations: Vec<MethodType::MethodAnnotation>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -38,9 +57,12 @@ pub enum CppStorageQualifier {
     Inline,
     Constexpr,
     Explicit,
+    /// `explicit(bool)` with the condition as written
+    ExplicitIf(String),
     Friend,
     Static,
     Virtual,
+    Extern,
 }
 
 impl From<&str> for CppStorageQualifier {
@@ -52,6 +74,7 @@ impl From<&str> for CppStorageQualifier {
             "friend" => CppStorageQualifier::Friend,
             "static" => CppStorageQualifier::Static,
             "virtual" => CppStorageQualifier::Virtual,
+            "extern" => CppStorageQualifier::Extern,
             _ => unimplemented!(),
         }
     }
@@ -60,7 +83,14 @@ impl From<&str> for CppStorageQualifier {
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum PostParamQualifier {
     Const,
+    Volatile,
+    /// `void F() &`
+    LValueRef,
+    /// `void F() &&`
+    RValueRef,
     Noexcept,
+    /// `noexcept(expr)` with the condition as written
+    NoexceptIf(String),
     Override,
     Final,
 }
@@ -69,6 +99,9 @@ impl From<&str> for PostParamQualifier {
     fn from(value: &str) -> Self {
         match value {
             "const" => PostParamQualifier::Const,
+            "volatile" => PostParamQualifier::Volatile,
+            "&" => PostParamQualifier::LValueRef,
+            "&&" => PostParamQualifier::RValueRef,
             "noexcept" => PostParamQualifier::Noexcept,
             "override" => PostParamQualifier::Override,
             "final" => PostParamQualifier::Final,
@@ -80,19 +113,31 @@ impl From<&str> for PostParamQualifier {
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum SpecialMember {
     PureVirtual,
+    /// Unreal's `PURE_VIRTUAL(Name, Body)` which provides a body that asserts, for classes that
+    /// must not be abstract
+    PureVirtualMacro,
     Defaulted,
     Deleted,
 }
 
 fn special_member(input: &str) -> IResult<&str, SpecialMember, VerboseError<&str>> {
-    preceded(
-        ws(char('=')),
-        alt((
-            map(tag("0"), |_| SpecialMember::PureVirtual),
-            map(tag("default"), |_| SpecialMember::Defaulted),
-            map(tag("deleted"), |_| SpecialMember::Deleted),
-        )),
-    )
+    alt((
+        preceded(
+            ws(char('=')),
+            alt((
+                map(char('0'), |_| SpecialMember::PureVirtual),
+                map(keyword("default"), |_| SpecialMember::Defaulted),
+                map(keyword("delete"), |_| SpecialMember::Deleted),
+            )),
+        ),
+        map(
+            preceded(
+                (multispace0, keyword("PURE_VIRTUAL"), multispace0),
+                parenthesized,
+            ),
+            |_| SpecialMember::PureVirtualMacro,
+        ),
+    ))
     .parse(input)
 }
 
@@ -108,14 +153,37 @@ pub fn parse_method<'a, MethodType>(
 where
     MethodType: Method<'a> + 'a,
 {
-    let (input, annotations) = opt(many0(|i| MethodType::MethodAnnotation::parse(i))).parse(input)?;
-
+    // `/** Doc */ UFUNCTION() void F();` and `UFUNCTION() /** Doc */ void F();`
+    let (input, leading_comment) = opt(parse_comment::<MethodType::Comment>).parse(input)?;
+    let (input, annotations) = opt(many0(preceded(multispace0, |i| {
+        MethodType::MethodAnnotation::parse(i)
+    })))
+    .parse(input)?;
+    let (input, _) = multispace0(input)?;
     let (input, comment) = opt(parse_comment::<MethodType::Comment>).parse(input)?;
+    let comment = comment.or(leading_comment);
+    let (input, mut attributes) = parse_attributes(input)?;
     let (input, storage_qualifiers) = opt(storage_qualifiers).parse(input)?;
     let (input, template_params) = opt(parse_template).parse(input)?;
+    // `template<typename T> [[nodiscard]] T Get();`
+    let (input, template_attributes) = parse_attributes(input)?;
+    attributes.extend(template_attributes);
+    // `FFoo() noexcept` is a constructor and not a function type `FFoo()` named `noexcept`
     let (input, (return_type, name)) = alt((
+        // conversion operators have no return type, `explicit operator bool()` mustn't be read
+        // as a function `bool` returning `operator`
+        map(operator_name, |name| (None, name)),
         map(
-            (parse_cpp_type, multispace1, method_name),
+            (
+                parse_cpp_type,
+                multispace1,
+                verify(method_name, |name: &str| {
+                    !matches!(
+                        name,
+                        "const" | "volatile" | "noexcept" | "override" | "final"
+                    )
+                }),
+            ),
             |(ret, _, name)| (Some(ret), name),
         ),
         map(method_name, |name| (None, name)),
@@ -124,21 +192,30 @@ where
 
     let (input, params) = parse_method_params.parse(input)?;
     let (input, _) = multispace0(input)?;
-    let (input, _) = member_initializer_list.parse(input)?;
+    // cv, ref and noexcept qualifiers precede a trailing return type, `override` and `final`
+    // follow it
+    let (input, mut qualifiers) = post_param_qualifiers(input)?;
+    // attributes of the function type like `void F() [[deprecated]];`
+    let (input, type_attributes) = parse_attributes(input)?;
+    attributes.extend(type_attributes);
+    let trailing_return_input = input;
     let (input, return_type_trailing) = method_trailing_return.parse(input)?;
-    let (input, post_param_qualifiers) = post_param_qualifiers(input)?;
-    let (input, special) = opt(special_member).parse(input)?;
-    let (input, _) = opt(preceded(multispace0, parse_brace_block)).parse(input)?;
-
+    // only `auto F() -> int` may have both
     let return_type = match (return_type, return_type_trailing) {
-        (None, None) => None,
-        (None, Some(return_type_trailing)) => Some(return_type_trailing),
-        (Some(return_type), None) => Some(return_type),
-        (Some(return_type), Some(return_type_trailing)) => match return_type == CType::Auto {
-            true => Some(return_type_trailing),
-            false => unreachable!(),
-        },
+        (Some(CType::Auto), Some(return_type_trailing)) => Some(return_type_trailing),
+        (Some(_), Some(_)) => {
+            return Err(nom::Err::Error(VerboseError::from_error_kind(
+                trailing_return_input,
+                ErrorKind::Verify,
+            )));
+        }
+        (return_type, return_type_trailing) => return_type.or(return_type_trailing),
     };
+    let (input, virt_specifiers) = post_param_qualifiers(input)?;
+    qualifiers.extend(virt_specifiers);
+    let (input, initializers) = member_initializer_list.parse(input)?;
+    let (input, special) = opt(special_member).parse(input)?;
+    let (input, body) = opt(preceded(multispace0, recognize(parse_brace_block))).parse(input)?;
 
     let return_type = match &return_type {
         Some(x) if *x == CType::Path(vec!["void"]) => None,
@@ -147,17 +224,20 @@ where
 
     Ok((
         input,
-        MethodType::method(
+        MethodType::method(MethodDefinition {
             name,
             return_type,
-            template_params.unwrap_or_default(),
+            template_params: template_params.unwrap_or_default(),
             params,
-            storage_qualifiers.unwrap_or_default(),
-            post_param_qualifiers,
+            storage_qualifiers: storage_qualifiers.unwrap_or_default(),
+            post_param_qualifiers: qualifiers,
             special,
+            initializers,
+            body,
             comment,
-            annotations.unwrap_or_default(),
-        ),
+            attributes,
+            annotations: annotations.unwrap_or_default(),
+        }),
     ))
 }
 
@@ -174,65 +254,145 @@ fn method_identifier(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
     take_while1(|c: char| c.is_alphanumeric() || c == '_' || c == '~')(input)
 }
 
+/// Names of symbol operators ordered longest first, so `operator<=` isn't read as `operator<`
+const OPERATOR_NAMES: [&str; 39] = [
+    "operator<=>",
+    "operator<<=",
+    "operator>>=",
+    "operator->*",
+    "operator()",
+    "operator[]",
+    "operator->",
+    "operator++",
+    "operator--",
+    "operator<<",
+    "operator>>",
+    "operator==",
+    "operator!=",
+    "operator<=",
+    "operator>=",
+    "operator&&",
+    "operator||",
+    "operator+=",
+    "operator-=",
+    "operator*=",
+    "operator/=",
+    "operator%=",
+    "operator^=",
+    "operator&=",
+    "operator|=",
+    "operator+",
+    "operator-",
+    "operator*",
+    "operator/",
+    "operator%",
+    "operator^",
+    "operator&",
+    "operator|",
+    "operator~",
+    "operator!",
+    "operator=",
+    "operator<",
+    "operator>",
+    "operator,",
+];
+
+/// The symbol after `operator`, e.g. `==`, returned as the normalized name `operator==`
+fn operator_symbol(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    let symbol = |name: &'static str| &name["operator".len()..];
+    match OPERATOR_NAMES
+        .into_iter()
+        .find(|name| input.starts_with(symbol(name)))
+    {
+        Some(name) => Ok((&input[symbol(name).len()..], name)),
+        None => Err(nom::Err::Error(VerboseError::from_error_kind(
+            input,
+            ErrorKind::Tag,
+        ))),
+    }
+}
+
+/// Target type of a conversion operator like `operator const TCHAR*()`, up to its parameter list
+fn conversion_type(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    let mut depth = 0usize;
+    for (i, c) in input.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            '(' if depth == 0 => {
+                let ctype = input[..i].trim_end();
+                if ctype.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+                    return Ok((&input[ctype.len()..], ctype));
+                }
+                break;
+            }
+            ';' | '{' | '}' => break,
+            _ => {}
+        }
+    }
+
+    Err(nom::Err::Error(VerboseError::from_error_kind(
+        input,
+        ErrorKind::TakeUntil,
+    )))
+}
+
+/// Symbol, allocation and `co_await` operators get a normalized name like `operator==` or
+/// `operator delete[]`, however they are spaced. User-defined literals and conversion operators
+/// keep the name as written.
 fn operator_name(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
-    recognize((
-        tag("operator"),
-        alt((
+    alt((
+        preceded(
+            (keyword("operator"), multispace0),
             alt((
-                tag("()"),
-                tag("[]"),
-                tag("->"),
-                tag("~"),
-                tag("+"),
-                tag("-"),
-                tag("*"),
-                tag("/"),
-                tag("%"),
-                tag("^"),
-                tag("&"),
-                tag("|"),
-                tag("!"),
-                tag("="),
-                tag("<"),
-                tag(">"),
-                tag("++"),
-                tag("--"),
+                allocation_operator,
+                value("operator co_await", keyword("co_await")),
+                operator_symbol,
             )),
+        ),
+        recognize((
+            keyword("operator"),
             alt((
-                tag("<<"),
-                tag(">>"),
-                tag("=="),
-                tag("!="),
-                tag("<="),
-                tag(">="),
-                tag("<=>"),
-                tag("+="),
-                tag("-="),
-                tag("*="),
-                tag("/="),
-                tag("%="),
-                tag("^="),
-                tag("&="),
-                tag("|="),
-                tag(","),
-                tag("new"),
-                tag("delete"),
+                // user-defined literal `operator""_km`
+                recognize((multispace0, tag("\"\""), multispace0, method_identifier)),
+                preceded(multispace1, conversion_type),
             )),
         )),
     ))
     .parse(input)
 }
 
+/// `new`, `delete []` and the like after `operator`
+fn allocation_operator(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    let (input, operator) = alt((keyword("new"), keyword("delete"))).parse(input)?;
+    let (input, array) = opt(preceded(multispace0, tag("[]"))).parse(input)?;
+
+    let name = match (operator, array.is_some()) {
+        ("new", false) => "operator new",
+        ("new", true) => "operator new[]",
+        (_, false) => "operator delete",
+        (_, true) => "operator delete[]",
+    };
+    Ok((input, name))
+}
+
 fn post_param_qualifiers(
     input: &str,
 ) -> IResult<&str, Vec<PostParamQualifier>, VerboseError<&str>> {
     many0(delimited(
         multispace0,
         alt((
-            map(tag("const"), |_| PostParamQualifier::Const),
-            map(tag("noexcept"), |_| PostParamQualifier::Noexcept),
-            map(tag("override"), |_| PostParamQualifier::Override),
-            map(tag("final"), |_| PostParamQualifier::Final),
+            map(keyword("const"), |_| PostParamQualifier::Const),
+            map(keyword("volatile"), |_| PostParamQualifier::Volatile),
+            map(tag("&&"), |_| PostParamQualifier::RValueRef),
+            map(tag("&"), |_| PostParamQualifier::LValueRef),
+            map(
+                preceded((keyword("noexcept"), multispace0), parenthesized),
+                |condition| PostParamQualifier::NoexceptIf(condition.to_string()),
+            ),
+            map(keyword("noexcept"), |_| PostParamQualifier::Noexcept),
+            map(keyword("override"), |_| PostParamQualifier::Override),
+            map(keyword("final"), |_| PostParamQualifier::Final),
         )),
         multispace0,
     ))
@@ -241,17 +401,22 @@ fn post_param_qualifiers(
 
 fn storage_qualifiers(input: &str) -> IResult<&str, Vec<CppStorageQualifier>, VerboseError<&str>> {
     many0(ws(alt((
-        map(tag("inline"), |_| CppStorageQualifier::Inline),
-        map(tag("constexpr"), |_| CppStorageQualifier::Constexpr),
-        map(tag("explicit"), |_| CppStorageQualifier::Explicit),
-        map(tag("friend"), |_| CppStorageQualifier::Friend),
-        map(tag("static"), |_| CppStorageQualifier::Static),
-        map(tag("virtual"), |_| CppStorageQualifier::Virtual),
+        map(keyword("inline"), |_| CppStorageQualifier::Inline),
+        map(keyword("constexpr"), |_| CppStorageQualifier::Constexpr),
+        map(
+            preceded((keyword("explicit"), multispace0), parenthesized),
+            |condition| CppStorageQualifier::ExplicitIf(condition.to_string()),
+        ),
+        map(keyword("explicit"), |_| CppStorageQualifier::Explicit),
+        map(keyword("friend"), |_| CppStorageQualifier::Friend),
+        map(keyword("static"), |_| CppStorageQualifier::Static),
+        map(keyword("virtual"), |_| CppStorageQualifier::Virtual),
+        map(keyword("extern"), |_| CppStorageQualifier::Extern),
     ))))
     .parse(input)
 }
 
-fn method_trailing_return(input: &str) -> IResult<&str, Option<CType>, VerboseError<&str>> {
+fn method_trailing_return(input: &str) -> IResult<&str, Option<CType<'_>>, VerboseError<&str>> {
     opt(preceded(
         delimited(multispace0, tag("->"), multispace0),
         parse_cpp_type,
@@ -259,17 +424,13 @@ fn method_trailing_return(input: &str) -> IResult<&str, Option<CType>, VerboseEr
     .parse(input)
 }
 
-fn member_initializer(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
-    map(
-        take_while1(|c: char| c.is_alphanumeric() || c != ',' && c != '{' && c != '\n'),
-        |s: &str| s.trim(),
-    )
-    .parse(input)
-}
-fn member_initializer_list(input: &str) -> IResult<&str, Vec<&str>, VerboseError<&str>> {
+/// `: Super(ObjectInitializer), Health{100.f}` of a constructor
+fn member_initializer_list(
+    input: &str,
+) -> IResult<&str, Vec<CppMemberInitializer<'_>>, VerboseError<&str>> {
     opt(preceded(
-        preceded(multispace0, tag(":")),
-        separated_list0(ws(char(',')), member_initializer),
+        ws(char(':')),
+        separated_list0(ws(char(',')), parse_member_initializer),
     ))
     .parse(input)
     .map(|(i, opt_list)| (i, opt_list.unwrap_or_default()))
@@ -3,4 +3,5 @@ pub mod member;
 pub mod class;
 pub mod namespace;
 pub mod annotation;
-pub mod method;
\ No newline at end of file
+pub mod method;
+pub mod ignore;
\ No newline at end of file
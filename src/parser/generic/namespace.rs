@@ -1,14 +1,20 @@
-﻿use crate::parser::generic::class::{Class, parse_class};
+use crate::parser::cpp::alias::{CppAlias, parse_aliases};
+use crate::parser::cpp::cenum::{CppEnum, cpp_enum};
+use crate::parser::cpp::header::preprocessor_directive;
+use crate::parser::cpp::using::CppUsing;
+use crate::parser::generic::class::{Class, parse_class};
 use crate::parser::generic::comment::parse_comment;
-use crate::parser::generic::member::parse_member;
+use crate::parser::generic::ignore::IgnoreRules;
+use crate::parser::generic::member::parse_members;
 use crate::parser::generic::method::parse_method;
-use crate::parser::{parse_str, ws};
+use crate::parser::{keyword, parse_str, ws};
+use crate::types::Parsable;
 use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::{char, multispace0};
+use nom::bytes::complete::{tag, take_until};
+use nom::character::complete::{char, multispace0, multispace1};
 use nom::combinator::{map, opt};
-use nom::multi::many_till;
-use nom::sequence::preceded;
+use nom::multi::{many_till, separated_list0};
+use nom::sequence::{delimited, preceded, terminated};
 use nom::{IResult, Parser};
 use nom_language::error::VerboseError;
 
@@ -16,52 +22,194 @@ pub trait Namespace<'a, ClassType>
 where
     ClassType: Class<'a>,
 {
-    fn namespace(
-        name: &'a str,
-        namespaces: Vec<Self>,
-        functions: Vec<ClassType::Method>,
-        variables: Vec<ClassType::Member>,
-        classes: Vec<ClassType>,
-        comments: Vec<ClassType::Comment>,
-    ) -> Self
+    fn namespace(definition: NamespaceDefinition<'a, Self, ClassType>) -> Self
     where
         Self: 'a + Sized;
 }
 
+/// Everything [`parse_namespace`] collects for one namespace, passed to [`Namespace::namespace`]
+pub struct NamespaceDefinition<'a, NamespaceType, ClassType>
+where
+    ClassType: Class<'a>,
+{
+    /// Empty for anonymous namespaces, which have internal linkage
+    pub name: &'a str,
+    pub is_inline: bool,
+    pub namespaces: Vec<NamespaceType>,
+    pub functions: Vec<ClassType::Method>,
+    pub variables: Vec<ClassType::Member>,
+    pub classes: Vec<ClassType>,
+    pub aliases: Vec<CppAlias<'a>>,
+    pub enums: Vec<CppEnum<'a>>,
+    pub usings: Vec<CppUsing<'a>>,
+    pub comments: Vec<ClassType::Comment>,
+    /// `extern "C" { ... }` blocks with their language, the declarations of a block are collected
+    /// in an unnamed namespace
+    pub linkage_blocks: Vec<(&'a str, NamespaceType)>,
+}
+
+impl<'a, NamespaceType, ClassType> NamespaceDefinition<'a, NamespaceType, ClassType>
+where
+    ClassType: Class<'a>,
+{
+    /// A namespace with an empty body
+    pub fn new(name: &'a str, is_inline: bool) -> Self {
+        Self {
+            name,
+            is_inline,
+            namespaces: vec![],
+            functions: vec![],
+            variables: vec![],
+            classes: vec![],
+            aliases: vec![],
+            enums: vec![],
+            usings: vec![],
+            comments: vec![],
+            linkage_blocks: vec![],
+        }
+    }
+}
+
+/// Statements matching `ignore_rules` are skipped, here and in all nested classes
 pub fn parse_namespace<'a, NamespaceType, ClassType>(
     input: &'a str,
+    ignore_rules: &IgnoreRules,
 ) -> IResult<&'a str, NamespaceType, VerboseError<&'a str>>
 where
     NamespaceType: Namespace<'a, ClassType> + 'a,
     ClassType: Class<'a> + 'a,
 {
-    let (input, _) = tag("namespace")(input)?;
-    let (input, name) = ws(parse_str).parse(input)?;
-    let (input, _) = char('{')(input)?;
-
-    let mut namespaces: Vec<NamespaceType> = Vec::new();
-    let mut functions = Vec::new();
-    let mut variables = Vec::new();
-    let mut classes = Vec::new();
-    let mut comments = Vec::new();
-
-    let (input, (items, _)) =
-        many_till(parse_namespace_item, preceded(multispace0, char('}'))).parse(input)?;
-    for item in items {
+    let (input, is_inline) = opt(terminated(keyword("inline"), multispace1)).parse(input)?;
+    let (input, _) = keyword("namespace").parse(input)?;
+    let (input, _) = multispace0(input)?;
+    // `namespace A::inline B::C` is a shorthand for three nested namespaces
+    let (input, path) = separated_list0(
+        ws(tag("::")),
+        (
+            opt(terminated(keyword("inline"), multispace1)).map(|i| i.is_some()),
+            parse_str,
+        ),
+    )
+    .parse(input)?;
+    let (input, _) = preceded(multispace0, char('{')).parse(input)?;
+    let (input, items) = namespace_body(input, ignore_rules)?;
+
+    let mut path = path;
+    if let Some(first) = path.first_mut() {
+        first.0 |= is_inline.is_some();
+    }
+    let (innermost_inline, innermost_name) = path.pop().unwrap_or((is_inline.is_some(), ""));
+
+    let namespace = namespace_of(innermost_name, innermost_inline, items);
+
+    let namespace = path
+        .into_iter()
+        .rev()
+        .fold(namespace, |inner, (is_inline, name)| {
+            NamespaceType::namespace(NamespaceDefinition {
+                namespaces: vec![inner],
+                ..NamespaceDefinition::new(name, is_inline)
+            })
+        });
+
+    Ok((input, namespace))
+}
+
+/// A linkage specification like `extern "C" { ... }` or `extern "C" void Foo();`. Returns the
+/// language in the string literal and the declarations collected in an unnamed namespace.
+pub fn parse_linkage_specification<'a, NamespaceType, ClassType>(
+    input: &'a str,
+    ignore_rules: &IgnoreRules,
+) -> IResult<&'a str, (&'a str, NamespaceType), VerboseError<&'a str>>
+where
+    NamespaceType: Namespace<'a, ClassType> + 'a,
+    ClassType: Class<'a> + 'a,
+{
+    let (input, (linkage, items)) = linkage_specification(input, ignore_rules)?;
+
+    Ok((input, (linkage, namespace_of("", false, items))))
+}
+
+fn linkage_specification<'a, NamespaceType, ClassType>(
+    input: &'a str,
+    ignore_rules: &IgnoreRules,
+) -> IResult<&'a str, (&'a str, NamespaceItems<'a, NamespaceType, ClassType>), VerboseError<&'a str>>
+where
+    NamespaceType: Namespace<'a, ClassType> + 'a,
+    ClassType: Class<'a> + 'a,
+{
+    let (input, _) = keyword("extern").parse(input)?;
+    let (input, linkage) = ws(delimited(char('"'), take_until("\""), char('"'))).parse(input)?;
+    let (input, items) = alt((
+        preceded(char('{'), |i| namespace_body(i, ignore_rules)),
+        map(
+            terminated(
+                |i| parse_namespace_item(i, ignore_rules),
+                opt(preceded(multispace0, char(';'))),
+            ),
+            |item| vec![item],
+        ),
+    ))
+    .parse(input)?;
+
+    Ok((input, (linkage, items)))
+}
+
+type NamespaceItems<'a, NamespaceType, ClassType> =
+    Vec<NamespaceItem<'a, NamespaceType, ClassType>>;
+
+/// Items up to and including the closing `}` of a namespace or linkage block
+fn namespace_body<'a, NamespaceType, ClassType>(
+    input: &'a str,
+    ignore_rules: &IgnoreRules,
+) -> IResult<&'a str, NamespaceItems<'a, NamespaceType, ClassType>, VerboseError<&'a str>>
+where
+    NamespaceType: Namespace<'a, ClassType> + 'a,
+    ClassType: Class<'a> + 'a,
+{
+    map(
+        many_till(
+            |i| parse_namespace_item(i, ignore_rules),
+            preceded(multispace0, char('}')),
+        ),
+        |(items, _)| items,
+    )
+    .parse(input)
+}
+
+/// A linkage specification does not open a scope, but its declarations are kept in their own
+/// block so they don't lose the linkage
+fn namespace_of<'a, NamespaceType, ClassType>(
+    name: &'a str,
+    is_inline: bool,
+    items: NamespaceItems<'a, NamespaceType, ClassType>,
+) -> NamespaceType
+where
+    NamespaceType: Namespace<'a, ClassType> + 'a,
+    ClassType: Class<'a> + 'a,
+{
+    let mut definition = NamespaceDefinition::new(name, is_inline);
+
+    let mut items = items;
+    items.reverse();
+    while let Some(item) = items.pop() {
         match item {
-            NamespaceItem::Namespace(namespace) => namespaces.push(namespace),
-            NamespaceItem::Class(class) => classes.push(class),
-            NamespaceItem::Method(method) => functions.push(method),
-            NamespaceItem::Variable(variable) => variables.push(variable),
-            NamespaceItem::Comment(comment) => comments.push(comment),
+            NamespaceItem::Namespace(namespace) => definition.namespaces.push(namespace),
+            NamespaceItem::Linkage(linkage, block) => definition
+                .linkage_blocks
+                .push((linkage, namespace_of("", false, block))),
+            NamespaceItem::Class(class) => definition.classes.push(class),
+            NamespaceItem::Aliases(alias) => definition.aliases.extend(alias),
+            NamespaceItem::Enum(e) => definition.enums.push(e),
+            NamespaceItem::Using(using) => definition.usings.push(using),
+            NamespaceItem::Method(method) => definition.functions.push(method),
+            NamespaceItem::Variables(variable) => definition.variables.extend(variable),
+            NamespaceItem::Comment(comment) => definition.comments.push(comment),
             _ => {}
         }
     }
 
-    Ok((
-        input,
-        NamespaceType::namespace(name, namespaces, functions, variables, classes, comments),
-    ))
+    NamespaceType::namespace(definition)
 }
 
 enum NamespaceItem<'a, NamespaceType, ClassType>
@@ -71,15 +219,20 @@ where
 {
     Ignore,
     Namespace(NamespaceType),
+    Linkage(&'a str, NamespaceItems<'a, NamespaceType, ClassType>),
     Class(ClassType),
+    Aliases(Vec<CppAlias<'a>>),
+    Enum(CppEnum<'a>),
+    Using(CppUsing<'a>),
     Method(ClassType::Method),
-    Variable(ClassType::Member),
+    Variables(Vec<ClassType::Member>),
     Comment(ClassType::Comment),
     End, // matched on `}` (+ optional `;`)
 }
 
 fn parse_namespace_item<'a, NamespaceType, ClassType>(
     input: &'a str,
+    ignore_rules: &IgnoreRules,
 ) -> IResult<&'a str, NamespaceItem<'a, NamespaceType, ClassType>, VerboseError<&'a str>>
 where
     NamespaceType: Namespace<'a, ClassType> + 'a,
@@ -89,10 +242,23 @@ where
         multispace0,
         alt((
             map(char(';'), |_| NamespaceItem::Ignore),
-            map(parse_namespace, NamespaceItem::Namespace),
-            map(|i| parse_class(i, &vec![]), NamespaceItem::Class),
+            map(|i| ignore_rules.parse(i), |_| NamespaceItem::Ignore),
+            // `#ifdef __cplusplus` guards around the braces of an `extern "C"` block
+            map(preprocessor_directive, |_| NamespaceItem::Ignore),
+            map(
+                |i| parse_namespace(i, ignore_rules),
+                NamespaceItem::Namespace,
+            ),
+            map(
+                |i| linkage_specification(i, ignore_rules),
+                |(linkage, items)| NamespaceItem::Linkage(linkage, items),
+            ),
+            map(|i| parse_aliases(i, ignore_rules), NamespaceItem::Aliases),
+            map(CppUsing::parse, NamespaceItem::Using),
+            map(cpp_enum, NamespaceItem::Enum),
+            map(|i| parse_class(i, ignore_rules), NamespaceItem::Class),
             map(parse_method, NamespaceItem::Method),
-            map(parse_member, NamespaceItem::Variable),
+            map(parse_members, NamespaceItem::Variables),
             map(parse_comment, NamespaceItem::Comment),
             map(preceded(char('}'), opt(char(';'))), |_| NamespaceItem::End),
         )),
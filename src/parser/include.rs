@@ -0,0 +1,406 @@
+use crate::graph::strongly_connected_components;
+use crate::parser::cpp::header::include_directive;
+use crate::parser::cpp::preprocessor::{PreprocessorConfig, preprocess};
+use crate::parser::source::{SourceDiagnostic, SourceText};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// `#include "Player.h"` is looked up next to the including file first, `#include <vector>` only
+/// in the search directories
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncludeStyle {
+    Quoted,
+    Angled,
+}
+
+/// An `#include` directive, `line` starts at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Include<'a> {
+    pub line: usize,
+    pub path: &'a str,
+    pub style: IncludeStyle,
+}
+
+/// Turns the path of an `#include` into a file
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IncludeResolver {
+    search_dirs: Vec<PathBuf>,
+}
+
+impl IncludeResolver {
+    /// Directories are searched in the order they were added
+    pub fn search_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.search_dirs.push(dir.into());
+        self
+    }
+
+    /// Adds the `Public/` and `Classes/` folders of a module and the module folder itself
+    pub fn module(self, module_dir: &Path) -> Self {
+        self.search_dir(module_dir.join("Public"))
+            .search_dir(module_dir.join("Classes"))
+            .search_dir(module_dir)
+    }
+
+    /// Adds every module below `Source/Runtime`, `Source/Developer` and `Source/Editor` of an
+    /// engine installation, e.g. `C:/Program Files/Epic Games/UE_5.4/Engine`
+    pub fn engine_root(self, engine_dir: &Path) -> Self {
+        let mut resolver = self;
+        for group in ["Runtime", "Developer", "Editor"] {
+            let Ok(entries) = std::fs::read_dir(engine_dir.join("Source").join(group)) else {
+                continue;
+            };
+            let mut modules = entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect::<Vec<_>>();
+            modules.sort();
+            for module in modules {
+                resolver = resolver.module(&module);
+            }
+        }
+
+        resolver
+    }
+
+    /// `include` is looked up next to `from` first if it is quoted and then in the search
+    /// directories
+    pub fn resolve(&self, include: &str, style: IncludeStyle, from: &Path) -> Option<PathBuf> {
+        let next_to_file = from
+            .parent()
+            .filter(|_| style == IncludeStyle::Quoted)
+            .map(|dir| dir.join(include));
+        next_to_file
+            .into_iter()
+            .chain(self.search_dirs.iter().map(|dir| dir.join(include)))
+            .find(|candidate| candidate.is_file())
+            .map(|file| normalize(&file))
+    }
+}
+
+/// An `#include` that matched no file, `line` starts at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedInclude {
+    pub file: PathBuf,
+    pub line: usize,
+    pub include: String,
+}
+
+impl fmt::Display for UnresolvedInclude {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: cannot resolve `{}`",
+            self.file.display(),
+            self.line,
+            self.include
+        )
+    }
+}
+
+/// Which file includes which, starting from a set of headers and following their includes.
+/// Every file is preprocessed first, includes in inactive branches are skipped.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IncludeGraph {
+    edges: BTreeMap<PathBuf, Vec<PathBuf>>,
+    unresolved: Vec<UnresolvedInclude>,
+    diagnostics: Vec<SourceDiagnostic>,
+}
+
+impl IncludeGraph {
+    pub fn build(
+        files: impl IntoIterator<Item = PathBuf>,
+        resolver: &IncludeResolver,
+        config: &PreprocessorConfig,
+    ) -> IncludeGraph {
+        let mut graph = IncludeGraph::default();
+        let mut queue = files
+            .into_iter()
+            .map(|file| normalize(&file))
+            .collect::<VecDeque<_>>();
+
+        while let Some(file) = queue.pop_front() {
+            if graph.edges.contains_key(&file) {
+                continue;
+            }
+
+            let source = match SourceText::load(&file) {
+                Ok(source) => source,
+                Err(diagnostic) => {
+                    graph.diagnostics.push(diagnostic);
+                    graph.edges.insert(file, vec![]);
+                    continue;
+                }
+            };
+
+            let preprocessed = preprocess(&source.text, config);
+            let mut included = vec![];
+            for include in includes_of(&preprocessed.text) {
+                match resolver.resolve(include.path, include.style, &file) {
+                    Some(target) => {
+                        if !included.contains(&target) {
+                            included.push(target.clone());
+                        }
+                        queue.push_back(target);
+                    }
+                    None => graph.unresolved.push(UnresolvedInclude {
+                        file: file.clone(),
+                        line: include.line,
+                        include: include.path.to_string(),
+                    }),
+                }
+            }
+            graph.edges.insert(file, included);
+        }
+
+        graph
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.edges.keys().map(PathBuf::as_path)
+    }
+
+    /// Files directly included by `file`
+    pub fn includes(&self, file: &Path) -> &[PathBuf] {
+        self.edges.get(file).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Files that directly include `file`
+    pub fn included_by(&self, file: &Path) -> Vec<&Path> {
+        self.edges
+            .iter()
+            .filter(|(_, includes)| includes.iter().any(|include| include == file))
+            .map(|(includer, _)| includer.as_path())
+            .collect()
+    }
+
+    pub fn unresolved(&self) -> &[UnresolvedInclude] {
+        &self.unresolved
+    }
+
+    /// Files that were included but could not be read or decoded
+    pub fn diagnostics(&self) -> &[SourceDiagnostic] {
+        &self.diagnostics
+    }
+
+    /// One line per include that couldn't be resolved
+    pub fn unresolved_report(&self) -> String {
+        self.unresolved
+            .iter()
+            .map(|unresolved| format!("{unresolved}\n"))
+            .collect()
+    }
+
+    /// Groups of files that include each other directly or indirectly, a file including itself
+    /// is a cycle of one
+    pub fn cycles(&self) -> Vec<Vec<&Path>> {
+        let files = self.files().collect::<Vec<_>>();
+        let index_of = files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (*file, index))
+            .collect::<HashMap<_, _>>();
+        let successors = files
+            .iter()
+            .map(|file| {
+                self.includes(file)
+                    .iter()
+                    .filter_map(|include| index_of.get(include.as_path()).copied())
+                    .collect()
+            })
+            .collect::<Vec<Vec<usize>>>();
+
+        let mut cycles = strongly_connected_components(&successors)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || successors[component[0]].contains(&component[0])
+            })
+            .map(|component| {
+                let mut cycle = component
+                    .into_iter()
+                    .map(|file| files[file])
+                    .collect::<Vec<_>>();
+                cycle.sort();
+                cycle
+            })
+            .collect::<Vec<_>>();
+        cycles.sort();
+
+        cycles
+    }
+}
+
+/// `#include` directives outside of comments. `text` should be preprocessed, so includes in
+/// inactive branches like `#if 0` are skipped.
+pub fn includes_of(text: &str) -> Vec<Include<'_>> {
+    let mut includes = vec![];
+    let mut in_comment = false;
+    for (i, line) in text.lines().enumerate() {
+        let code = match in_comment {
+            true => match line.find("*/") {
+                Some(end) => &line[end + 2..],
+                None => continue,
+            },
+            false => line,
+        };
+        if let Ok((_, (path, style))) = include_directive(code) {
+            includes.push(Include {
+                line: i + 1,
+                path,
+                style,
+            });
+        }
+        in_comment = ends_in_comment(code);
+    }
+
+    includes
+}
+
+/// Whether a block comment opened in `code` is still open at its end
+fn ends_in_comment(code: &str) -> bool {
+    let mut rest = code;
+    while let Some(start) = rest.find("/*") {
+        if rest[..start].contains("//") {
+            return false;
+        }
+        match rest[start + 2..].find("*/") {
+            Some(end) => rest = &rest[start + 2 + end + 2..],
+            None => return true,
+        }
+    }
+
+    false
+}
+
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::cpp::preprocessor::{PreprocessorConfig, preprocess};
+    use crate::parser::include::{
+        Include, IncludeGraph, IncludeResolver, IncludeStyle, includes_of,
+    };
+    use crate::parser::temp_project::TempProject;
+    use std::path::Path;
+
+    #[test]
+    fn include_lines() {
+        let text = r#"#pragma once
+#include "CoreMinimal.h"
+  # include <vector>
+// #include "Old.h"
+/* #include "Older.h"
+#include "Oldest.h" */ #include "Player.h"
+#if 0
+#include "Disabled.h"
+#endif
+"#;
+        let preprocessed = preprocess(text, &PreprocessorConfig::default());
+        let include = |line, path, style| Include { line, path, style };
+
+        assert_eq!(
+            includes_of(&preprocessed.text),
+            vec![
+                include(2, "CoreMinimal.h", IncludeStyle::Quoted),
+                include(3, "vector", IncludeStyle::Angled),
+                include(6, "Player.h", IncludeStyle::Quoted),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_against_search_dirs() {
+        let root = TempProject::new(
+            "resolve",
+            &[
+                ("Game/Public/Player.h", "#include \"Inventory/Item.h\""),
+                ("Game/Public/Inventory/Item.h", "#include \"ItemTypes.h\""),
+                ("Game/Public/Inventory/ItemTypes.h", ""),
+                ("Game/Classes/Legacy.h", ""),
+                ("Engine/Source/Runtime/Core/Public/CoreMinimal.h", ""),
+            ],
+        );
+        let resolver = IncludeResolver::default()
+            .module(&root.join("Game"))
+            .engine_root(&root.join("Engine"));
+        let from = root.join("Game/Public/Player.h");
+
+        let quoted = |include, from: &Path| resolver.resolve(include, IncludeStyle::Quoted, from);
+
+        assert_eq!(
+            quoted("Inventory/Item.h", &from),
+            Some(root.join("Game/Public/Inventory/Item.h"))
+        );
+        // next to the including file, but only for quoted includes
+        let item = root.join("Game/Public/Inventory/Item.h");
+        assert_eq!(
+            quoted("ItemTypes.h", &item),
+            Some(root.join("Game/Public/Inventory/ItemTypes.h"))
+        );
+        assert_eq!(
+            resolver.resolve("ItemTypes.h", IncludeStyle::Angled, &item),
+            None
+        );
+        assert_eq!(
+            quoted("Legacy.h", &from),
+            Some(root.join("Game/Classes/Legacy.h"))
+        );
+        assert_eq!(
+            resolver.resolve("CoreMinimal.h", IncludeStyle::Angled, &from),
+            Some(root.join("Engine/Source/Runtime/Core/Public/CoreMinimal.h"))
+        );
+        assert_eq!(quoted("Missing.h", &from), None);
+    }
+
+    #[test]
+    fn graph_with_cycles_and_unresolved_includes() {
+        let root = TempProject::new(
+            "graph",
+            &[
+                (
+                    "A.h",
+                    "#include \"B.h\"\n#include \"Missing.h\"\n#ifdef WITH_F\n#include \"F.h\"\n#endif",
+                ),
+                ("B.h", "#include \"C.h\""),
+                ("C.h", "#include \"A.h\"\n#include \"D.h\""),
+                ("D.h", "#include \"D.h\""),
+                ("E.h", "#include \"D.h\""),
+            ],
+        );
+        let file = |name: &str| root.join(name);
+
+        let graph = IncludeGraph::build(
+            [file("A.h"), file("E.h")],
+            &IncludeResolver::default(),
+            &PreprocessorConfig::default(),
+        );
+
+        assert_eq!(graph.files().count(), 5);
+        assert_eq!(graph.includes(&file("C.h")), [file("A.h"), file("D.h")]);
+        assert_eq!(
+            graph.included_by(&file("D.h")),
+            vec![
+                file("C.h").as_path(),
+                file("D.h").as_path(),
+                file("E.h").as_path()
+            ]
+        );
+        assert_eq!(
+            graph.cycles(),
+            vec![
+                vec![Path::new(&file("A.h")), &file("B.h"), &file("C.h")],
+                vec![Path::new(&file("D.h"))],
+            ]
+        );
+
+        assert_eq!(graph.unresolved().len(), 1);
+        assert_eq!(
+            graph.unresolved_report(),
+            format!("{}:2: cannot resolve `Missing.h`\n", file("A.h").display())
+        );
+        assert!(graph.diagnostics().is_empty());
+    }
+}
@@ -1,13 +1,19 @@
-﻿use nom::character::complete::{multispace0, one_of};
+﻿use nom::character::complete::{multispace0, one_of, satisfy};
+use nom::combinator::not;
 use nom::error::ParseError;
 use nom::{IResult, Parser};
-use nom::bytes::complete::{escaped, take_while1};
-use nom::sequence::delimited;
+use nom::bytes::complete::{escaped, tag, take_while1};
+use nom::sequence::{delimited, terminated};
 
+pub mod compile_commands;
 pub mod cpp;
 
 pub mod ue;
 pub mod generic;
+pub mod include;
+pub mod source;
+#[cfg(test)]
+pub(crate) mod temp_project;
 
 pub fn ws<'a, O, E: ParseError<&'a str>, F>(inner: F) -> impl Parser<&'a str, Output=O, Error=E>
 where
@@ -16,6 +22,16 @@ where
     delimited(multispace0, inner, multispace0)
 }
 
+/// Matches `kw` only if it is not immediately followed by another identifier character, so
+/// `int` does not match the beginning of `int32`.
+pub fn keyword<'a, E: ParseError<&'a str>>(
+    kw: &'static str,
+) -> impl Parser<&'a str, Output = &'a str, Error = E> {
+    terminated(
+        tag(kw),
+        not(satisfy(|c: char| c.is_alphanumeric() || c == '_')),
+    )
+}
 
 fn is_ident_char(c: char) -> bool {
     c.is_alphanumeric() || c == '_' || c == '-'
@@ -50,9 +66,6 @@ where
         }
 
         if let Some(e) = errors.last() {
-            for (i, error) in errors.iter().enumerate() {
-                eprintln!("alt branch {}: {:?}", i, error);
-            }
             Err(nom::Err::Error(e.clone()))
         } else {
             Err(nom::Err::Error(E::from_error_kind(input, nom::error::ErrorKind::Alt)))
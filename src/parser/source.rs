@@ -0,0 +1,305 @@
+﻿use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Encoding a source file was saved with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl SourceEncoding {
+    /// Detects the encoding from the byte order mark. Files without one are UTF-16 if most of
+    /// their first bytes are zero on one side, which is the case for ASCII text saved as UTF-16.
+    pub fn detect(bytes: &[u8]) -> SourceEncoding {
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => return SourceEncoding::Utf8Bom,
+            [0xFF, 0xFE, ..] => return SourceEncoding::Utf16Le,
+            [0xFE, 0xFF, ..] => return SourceEncoding::Utf16Be,
+            _ => {}
+        }
+
+        let sample = &bytes[..bytes.len().min(512)];
+        let zeros_at = |parity| {
+            sample
+                .iter()
+                .skip(parity)
+                .step_by(2)
+                .filter(|b| **b == 0)
+                .count()
+        };
+        let half = sample.len() / 2;
+        match (zeros_at(0), zeros_at(1)) {
+            (_, odd) if half > 0 && odd * 2 > half => SourceEncoding::Utf16Le,
+            (even, _) if half > 0 && even * 2 > half => SourceEncoding::Utf16Be,
+            _ => SourceEncoding::Utf8,
+        }
+    }
+
+    /// Length of the byte order mark at the start of `bytes`
+    fn bom_len(&self, bytes: &[u8]) -> usize {
+        match (self, bytes) {
+            (SourceEncoding::Utf8Bom, _) => 3,
+            (SourceEncoding::Utf16Le, [0xFF, 0xFE, ..]) => 2,
+            (SourceEncoding::Utf16Be, [0xFE, 0xFF, ..]) => 2,
+            _ => 0,
+        }
+    }
+}
+
+/// Why a file could not be loaded, offsets are bytes in the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceError {
+    Io(String),
+    InvalidUtf8 {
+        offset: usize,
+    },
+    InvalidUtf16 {
+        offset: usize,
+    },
+    /// UTF-16 file with an odd number of bytes
+    TruncatedUtf16,
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::Io(message) => write!(f, "{message}"),
+            SourceError::InvalidUtf8 { offset } => write!(f, "invalid UTF-8 at byte {offset}"),
+            SourceError::InvalidUtf16 { offset } => {
+                write!(f, "unpaired UTF-16 surrogate at byte {offset}")
+            }
+            SourceError::TruncatedUtf16 => write!(f, "UTF-16 file ends in the middle of a unit"),
+        }
+    }
+}
+
+/// A file that could not be loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceDiagnostic {
+    pub path: PathBuf,
+    pub error: SourceError,
+}
+
+impl fmt::Display for SourceDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+/// Decoded text with `\n` line endings and no byte order mark, ready for the parsers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceText {
+    pub encoding: SourceEncoding,
+    pub text: String,
+    bom_len: usize,
+    /// `(text offset, file offset)` wherever the distance between both changes
+    offsets: Vec<(usize, usize)>,
+}
+
+impl SourceText {
+    pub fn decode(bytes: &[u8]) -> Result<SourceText, SourceError> {
+        let encoding = SourceEncoding::detect(bytes);
+        let bom_len = encoding.bom_len(bytes);
+        let body = &bytes[bom_len..];
+
+        let mut decoded = SourceText {
+            encoding,
+            text: String::with_capacity(body.len()),
+            bom_len,
+            offsets: vec![],
+        };
+        match encoding {
+            SourceEncoding::Utf8 | SourceEncoding::Utf8Bom => {
+                let text = std::str::from_utf8(body).map_err(|e| SourceError::InvalidUtf8 {
+                    offset: bom_len + e.valid_up_to(),
+                })?;
+                for (i, c) in text.char_indices() {
+                    decoded.push(c, bom_len + i, c.len_utf8());
+                }
+            }
+            SourceEncoding::Utf16Le | SourceEncoding::Utf16Be => {
+                if !body.len().is_multiple_of(2) {
+                    return Err(SourceError::TruncatedUtf16);
+                }
+                let units = body.chunks_exact(2).map(|unit| match encoding {
+                    SourceEncoding::Utf16Le => u16::from_le_bytes([unit[0], unit[1]]),
+                    _ => u16::from_be_bytes([unit[0], unit[1]]),
+                });
+
+                let mut offset = bom_len;
+                for c in char::decode_utf16(units) {
+                    let c = c.map_err(|_| SourceError::InvalidUtf16 { offset })?;
+                    let len = c.len_utf16() * 2;
+                    decoded.push(c, offset, len);
+                    offset += len;
+                }
+            }
+        }
+        decoded.finish_line();
+
+        Ok(decoded)
+    }
+
+    /// Reads and decodes `path`, failures are reported as diagnostics for that file
+    pub fn load(path: &Path) -> Result<SourceText, SourceDiagnostic> {
+        let diagnostic = |error| SourceDiagnostic {
+            path: path.to_path_buf(),
+            error,
+        };
+        let bytes = std::fs::read(path).map_err(|e| diagnostic(SourceError::Io(e.to_string())))?;
+
+        SourceText::decode(&bytes).map_err(diagnostic)
+    }
+
+    /// Appends `c` found at `offset` in the file. `\r\n` and a lone `\r` become `\n`.
+    fn push(&mut self, c: char, offset: usize, len: usize) {
+        if self.text.ends_with('\r') {
+            self.text.pop();
+            self.text.push('\n');
+            if c == '\n' {
+                return;
+            }
+        }
+
+        self.checkpoint(self.text.len(), offset);
+        self.text.push(c);
+        // UTF-16 characters have a different length than in UTF-8
+        if c.len_utf8() != len {
+            self.checkpoint(self.text.len(), offset + len);
+        }
+    }
+
+    fn checkpoint(&mut self, text: usize, original: usize) {
+        if self.original_offset(text) == original {
+            return;
+        }
+        match self.offsets.last_mut() {
+            Some(last) if last.0 == text => last.1 = original,
+            _ => self.offsets.push((text, original)),
+        }
+    }
+
+    fn finish_line(&mut self) {
+        if self.text.ends_with('\r') {
+            self.text.pop();
+            self.text.push('\n');
+        }
+    }
+
+    /// Byte offset in the file of the byte at `offset` in `text`
+    pub fn original_offset(&self, offset: usize) -> usize {
+        let index = self.offsets.partition_point(|(text, _)| *text <= offset);
+        match index {
+            0 => self.bom_len + offset,
+            _ => {
+                let (text, original) = self.offsets[index - 1];
+                original + (offset - text)
+            }
+        }
+    }
+
+    /// File range of `range` in `text`, e.g. to map [`crate::parser::cpp::method::CppFunction::body_span`]
+    /// back to the file
+    pub fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        self.original_offset(range.start)..self.original_offset(range.end)
+    }
+}
+
+/// Loads all `paths`, files that can't be read or decoded end up in the diagnostics
+pub fn load_sources<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+) -> (Vec<(PathBuf, SourceText)>, Vec<SourceDiagnostic>) {
+    let mut sources = vec![];
+    let mut diagnostics = vec![];
+    for path in paths {
+        let path = path.as_ref();
+        match SourceText::load(path) {
+            Ok(source) => sources.push((path.to_path_buf(), source)),
+            Err(diagnostic) => diagnostics.push(diagnostic),
+        }
+    }
+
+    (sources, diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::source::{SourceEncoding, SourceError, SourceText, load_sources};
+    use std::path::PathBuf;
+
+    fn utf16(text: &str, little_endian: bool, bom: bool) -> Vec<u8> {
+        let mut bytes = vec![];
+        let units = bom.then_some(0xFEFF).into_iter().chain(text.encode_utf16());
+        for unit in units {
+            match little_endian {
+                true => bytes.extend(unit.to_le_bytes()),
+                false => bytes.extend(unit.to_be_bytes()),
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn detects_encodings() {
+        let text = "// Übersicht\r\nint32 A;";
+        for (bytes, encoding) in [
+            (text.as_bytes().to_vec(), SourceEncoding::Utf8),
+            (
+                [&[0xEF, 0xBB, 0xBF], text.as_bytes()].concat(),
+                SourceEncoding::Utf8Bom,
+            ),
+            (utf16(text, true, true), SourceEncoding::Utf16Le),
+            (utf16(text, false, true), SourceEncoding::Utf16Be),
+            (utf16(text, true, false), SourceEncoding::Utf16Le),
+        ] {
+            let source = SourceText::decode(&bytes).unwrap();
+            assert_eq!(source.encoding, encoding);
+            assert_eq!(source.text, "// Übersicht\nint32 A;");
+        }
+    }
+
+    #[test]
+    fn offsets_point_into_the_file() {
+        let text = "/// Ä\r\nint32 A;\rint32 B;";
+
+        let source = SourceText::decode(&utf16(text, true, true)).unwrap();
+        assert_eq!(source.text, "/// Ä\nint32 A;\nint32 B;");
+        let a = source.text.find('A').unwrap();
+        let b = source.text.find('B').unwrap();
+        // BOM + 13 units before `A`, the line break is two units
+        assert_eq!(source.original_offset(a), 2 + 13 * 2);
+        assert_eq!(source.original_offset(b), 2 + 22 * 2);
+
+        let bytes = [&[0xEF, 0xBB, 0xBF], text.as_bytes()].concat();
+        let source = SourceText::decode(&bytes).unwrap();
+        let a = source.text.find('A').unwrap();
+        assert_eq!(&bytes[source.original_offset(a)..][..1], b"A");
+        let range = source.original_range(a..a + 1);
+        assert_eq!(&bytes[range], b"A");
+    }
+
+    #[test]
+    fn undecodable_files_are_reported() {
+        assert_eq!(
+            SourceText::decode(b"int32 A;\xFF"),
+            Err(SourceError::InvalidUtf8 { offset: 8 })
+        );
+        assert_eq!(
+            SourceText::decode(&[0xFF, 0xFE, b'A', 0, 0x00, 0xD8, b'B', 0]),
+            Err(SourceError::InvalidUtf16 { offset: 4 })
+        );
+        assert_eq!(
+            SourceText::decode(&[0xFF, 0xFE, b'A']),
+            Err(SourceError::TruncatedUtf16)
+        );
+
+        let (sources, diagnostics) = load_sources([PathBuf::from("does/not/exist.h")]);
+        assert!(sources.is_empty());
+        assert_eq!(diagnostics[0].path, PathBuf::from("does/not/exist.h"));
+        assert!(matches!(diagnostics[0].error, SourceError::Io(_)));
+    }
+}
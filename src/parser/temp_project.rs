@@ -0,0 +1,39 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// Files written to a fresh directory below the system temp dir for tests. The directory is
+/// removed again when the project is dropped.
+pub(crate) struct TempProject {
+    root: PathBuf,
+}
+
+impl TempProject {
+    /// `name` has to be unique among the tests running at the same time
+    pub(crate) fn new(name: &str, files: &[(&str, &str)]) -> TempProject {
+        let root = std::env::temp_dir().join(format!("uedoc-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for (path, content) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        TempProject {
+            root: std::fs::canonicalize(root).unwrap(),
+        }
+    }
+}
+
+impl Deref for TempProject {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl Drop for TempProject {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
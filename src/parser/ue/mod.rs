@@ -1,3 +1,5 @@
 ﻿pub mod uproperty;
 pub mod ufunction;
-pub mod uclass;
\ No newline at end of file
+pub mod uclass;
+pub mod project;
+pub mod module_rules;
\ No newline at end of file
@@ -0,0 +1,291 @@
+use crate::parser::source::{SourceDiagnostic, SourceText};
+use crate::parser::{keyword, ws};
+use crate::types::Parsable;
+use nom::branch::alt;
+use nom::bytes::complete::{escaped_transform, is_not, tag, take_until, take_while1};
+use nom::character::complete::{anychar, char, multispace0, multispace1, not_line_ending};
+use nom::combinator::{map, opt, recognize, value};
+use nom::multi::{many0, separated_list0};
+use nom::sequence::{delimited, preceded, terminated};
+use nom::{IResult, Parser};
+use nom_language::error::VerboseError;
+use std::path::Path;
+
+/// Dependencies, include paths and definitions from a `<Module>.Build.cs`. Only string literals
+/// are picked up, so paths built with `Path.Combine(...)` are missing. Lists inside `if` blocks
+/// are added as if the condition was true.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ModuleRules {
+    /// Name of the class deriving from `ModuleRules`
+    pub name: String,
+    pub public_dependencies: Vec<String>,
+    pub private_dependencies: Vec<String>,
+    pub dynamically_loaded: Vec<String>,
+    pub public_include_paths: Vec<String>,
+    pub private_include_paths: Vec<String>,
+    pub public_definitions: Vec<String>,
+    pub private_definitions: Vec<String>,
+}
+
+impl ModuleRules {
+    /// `None` if there is no such file. Anything the parser doesn't understand is skipped, so
+    /// only reading and decoding the file fails.
+    pub fn load(path: &Path) -> Result<Option<ModuleRules>, SourceDiagnostic> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let source = SourceText::load(path)?;
+        let (_, rules) = ModuleRules::parse(&source.text).unwrap_or_default();
+
+        Ok(Some(rules))
+    }
+
+    fn list(&mut self, name: &str) -> Option<&mut Vec<String>> {
+        match name {
+            "PublicDependencyModuleNames" => Some(&mut self.public_dependencies),
+            "PrivateDependencyModuleNames" => Some(&mut self.private_dependencies),
+            "DynamicallyLoadedModuleNames" => Some(&mut self.dynamically_loaded),
+            "PublicIncludePaths" => Some(&mut self.public_include_paths),
+            "PrivateIncludePaths" => Some(&mut self.private_include_paths),
+            "PublicDefinitions" => Some(&mut self.public_definitions),
+            "PrivateDefinitions" => Some(&mut self.private_definitions),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum RulesItem<'a> {
+    Class(&'a str),
+    Add(&'a str, Vec<String>),
+    Skip,
+}
+
+impl<'a> Parsable<'a> for ModuleRules {
+    fn parse(input: &'a str) -> IResult<&'a str, Self, VerboseError<&'a str>> {
+        let mut rules = ModuleRules::default();
+
+        let mut input = input;
+        while !input.is_empty() {
+            let (rest, item) = rules_item(input)?;
+            match item {
+                RulesItem::Class(name) if rules.name.is_empty() => rules.name = name.to_string(),
+                RulesItem::Add(list, values) => {
+                    if let Some(list) = rules.list(list) {
+                        list.extend(values);
+                    }
+                }
+                _ => {}
+            }
+            input = rest;
+        }
+
+        Ok(("", rules))
+    }
+}
+
+/// Everything that isn't a rules class or a list addition is skipped a token at a time
+fn rules_item(input: &str) -> IResult<&str, RulesItem<'_>, VerboseError<&str>> {
+    alt((
+        map(module_rules_class, RulesItem::Class),
+        map(list_addition, |(list, values)| RulesItem::Add(list, values)),
+        value(RulesItem::Skip, comment),
+        value(RulesItem::Skip, string_literal),
+        value(RulesItem::Skip, identifier),
+        value(RulesItem::Skip, anychar),
+    ))
+    .parse(input)
+}
+
+/// `public class Shooter : ModuleRules`
+fn module_rules_class(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    let (input, _) = terminated(keyword("class"), multispace0).parse(input)?;
+    let (input, name) = identifier(input)?;
+    let (input, _) = (ws(char(':')), keyword("ModuleRules")).parse(input)?;
+
+    Ok((input, name))
+}
+
+/// `PublicDependencyModuleNames.Add("Core")` or
+/// `PublicDependencyModuleNames.AddRange(new string[] { "Core", "Engine" })`
+fn list_addition(input: &str) -> IResult<&str, (&str, Vec<String>), VerboseError<&str>> {
+    let (input, list) = identifier(input)?;
+    let (input, _) = cs_ws(char('.')).parse(input)?;
+    let (input, values) = alt((
+        preceded(
+            (keyword("AddRange"), cs_ws(char('('))),
+            terminated(collection, cs_ws(char(')'))),
+        ),
+        preceded(
+            (keyword("Add"), cs_ws(char('('))),
+            terminated(map(string_literal, |value| vec![value]), cs_ws(char(')'))),
+        ),
+    ))
+    .parse(input)?;
+
+    Ok((input, (list, values)))
+}
+
+/// `new string[] { ... }`, `new[] { ... }` or `new List<string>() { ... }`
+fn collection(input: &str) -> IResult<&str, Vec<String>, VerboseError<&str>> {
+    let (input, _) = (
+        keyword("new"),
+        trivia,
+        opt(alt((tag("string"), tag("List<string>")))),
+        opt(cs_ws(tag("[]"))),
+        opt(cs_ws(tag("()"))),
+    )
+        .parse(input)?;
+
+    delimited(
+        cs_ws(char('{')),
+        terminated(
+            separated_list0(cs_ws(char(',')), string_literal),
+            opt(cs_ws(char(','))),
+        ),
+        preceded(trivia, char('}')),
+    )
+    .parse(input)
+}
+
+/// `"Core"` with C# escapes or the verbatim form `@"C:\Path"`
+fn string_literal(input: &str) -> IResult<&str, String, VerboseError<&str>> {
+    alt((
+        preceded(
+            char('@'),
+            delimited(char('"'), map(take_until("\""), str::to_string), char('"')),
+        ),
+        delimited(
+            char('"'),
+            map(
+                opt(escaped_transform(
+                    is_not("\\\""),
+                    '\\',
+                    alt((
+                        value("\\", char('\\')),
+                        value("\"", char('"')),
+                        value("\n", char('n')),
+                        value("\t", char('t')),
+                    )),
+                )),
+                Option::unwrap_or_default,
+            ),
+            char('"'),
+        ),
+    ))
+    .parse(input)
+}
+
+fn comment(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    alt((
+        recognize((tag("//"), not_line_ending)),
+        recognize((tag("/*"), take_until("*/"), tag("*/"))),
+    ))
+    .parse(input)
+}
+
+/// Whitespace and comments between tokens
+fn trivia(input: &str) -> IResult<&str, (), VerboseError<&str>> {
+    value((), many0(alt((multispace1, comment)))).parse(input)
+}
+
+fn cs_ws<'a, O, F>(inner: F) -> impl Parser<&'a str, Output = O, Error = VerboseError<&'a str>>
+where
+    F: Parser<&'a str, Output = O, Error = VerboseError<&'a str>>,
+{
+    delimited(trivia, inner, trivia)
+}
+
+fn identifier(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::temp_project::TempProject;
+    use crate::parser::ue::module_rules::ModuleRules;
+    use crate::types::Parsable;
+
+    #[test]
+    fn common_build_cs_patterns() {
+        let input = r#"// Copyright Epic Games, Inc. All Rights Reserved.
+
+using UnrealBuildTool;
+
+public class Shooter : ModuleRules
+{
+    public Shooter(ReadOnlyTargetRules Target) : base(Target)
+    {
+        PCHUsage = PCHUsageMode.UseExplicitOrSharedPCHs;
+
+        PublicDependencyModuleNames.AddRange(new string[] { "Core", "CoreUObject", "Engine" });
+        PrivateDependencyModuleNames.AddRange(
+            new string[]
+            {
+                "Slate",
+                "SlateCore", // UI
+            }
+        );
+        // PrivateDependencyModuleNames.Add("Disabled");
+        PublicIncludePaths.Add(@"Shooter\Public");
+        PrivateIncludePaths.Add(Path.Combine(ModuleDirectory, "Private"));
+        PublicDefinitions.Add("WITH_SHOOTER_CHEATS=1");
+        PrivateDefinitions.AddRange(new[] { "SHOOTER_NAME=\"Shooter\"" });
+
+        if (Target.bBuildEditor)
+        {
+            PrivateDependencyModuleNames.Add("UnrealEd");
+        }
+
+        DynamicallyLoadedModuleNames.AddRange(new List<string>() { "OnlineSubsystemSteam" });
+    }
+}
+"#;
+
+        assert_eq!(
+            ModuleRules::parse(input),
+            Ok((
+                "",
+                ModuleRules {
+                    name: "Shooter".to_string(),
+                    public_dependencies: vec![
+                        "Core".to_string(),
+                        "CoreUObject".to_string(),
+                        "Engine".to_string()
+                    ],
+                    private_dependencies: vec![
+                        "Slate".to_string(),
+                        "SlateCore".to_string(),
+                        "UnrealEd".to_string()
+                    ],
+                    dynamically_loaded: vec!["OnlineSubsystemSteam".to_string()],
+                    public_include_paths: vec![r"Shooter\Public".to_string()],
+                    private_include_paths: vec![],
+                    public_definitions: vec!["WITH_SHOOTER_CHEATS=1".to_string()],
+                    private_definitions: vec!["SHOOTER_NAME=\"Shooter\"".to_string()],
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn load_missing_and_unreadable() {
+        let root = TempProject::new(
+            "module-rules",
+            &[
+                ("Game/Game.Build.cs", "public class Game : ModuleRules {}"),
+                ("Broken/Broken.Build.cs/README", ""),
+            ],
+        );
+
+        let rules = ModuleRules::load(&root.join("Game/Game.Build.cs")).unwrap();
+        assert_eq!(rules.map(|rules| rules.name), Some("Game".to_string()));
+        assert_eq!(
+            ModuleRules::load(&root.join("Missing/Missing.Build.cs")),
+            Ok(None)
+        );
+
+        let diagnostic = ModuleRules::load(&root.join("Broken/Broken.Build.cs")).unwrap_err();
+        assert_eq!(diagnostic.path, root.join("Broken/Broken.Build.cs"));
+    }
+}
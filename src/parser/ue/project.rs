@@ -0,0 +1,1000 @@
+use crate::parser::cpp::cenum::CppEnum;
+use crate::parser::cpp::class::CppClass;
+use crate::parser::cpp::comment::CppComment;
+use crate::parser::cpp::header::CppHeader;
+use crate::parser::cpp::linkage::CppLinkageBlock;
+use crate::parser::cpp::member::CppMember;
+use crate::parser::cpp::method::{CppFunction, FunctionKind};
+use crate::parser::cpp::namespace::CppNamespace;
+use crate::parser::cpp::preprocessor::{PreprocessedSource, PreprocessorConfig, preprocess};
+use crate::parser::cpp::using::CppUsing;
+use crate::parser::generic::class::{ClassKind, CppParentClass, InheritanceVisibility};
+use crate::parser::generic::ignore::IgnoreRules;
+use crate::parser::generic::method::PostParamQualifier;
+use crate::parser::source::{SourceDiagnostic, SourceText};
+use crate::parser::ue::module_rules::ModuleRules;
+use crate::parser::ue::uclass::UClass;
+use nom_language::error::convert_error;
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// `Type` of a module in a `.uproject` or `.uplugin`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ModuleType {
+    Runtime,
+    RuntimeNoCommandlet,
+    RuntimeAndProgram,
+    CookedOnly,
+    UncookedOnly,
+    Developer,
+    DeveloperTool,
+    Editor,
+    EditorNoCommandlet,
+    EditorAndProgram,
+    Program,
+    ServerOnly,
+    ClientOnly,
+    ClientOnlyNoCommandlet,
+    #[serde(other)]
+    Unknown,
+}
+
+/// `LoadingPhase` of a module, `Default` if the descriptor doesn't name one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum LoadingPhase {
+    EarliestPossible,
+    PostConfigInit,
+    PostSplashScreen,
+    PreEarlyLoadingScreen,
+    PreLoadingScreen,
+    PreDefault,
+    #[default]
+    Default,
+    PostDefault,
+    PostEngineInit,
+    None,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ModuleDescriptor {
+    name: String,
+    #[serde(rename = "Type")]
+    kind: ModuleType,
+    #[serde(default)]
+    loading_phase: LoadingPhase,
+}
+
+/// The part of `.uproject` and `.uplugin` files the loader needs
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Descriptor {
+    #[serde(default)]
+    modules: Vec<ModuleDescriptor>,
+}
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Io(PathBuf, std::io::Error),
+    Json(PathBuf, serde_json::Error),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            ProjectError::Json(path, e) => write!(f, "{}: invalid descriptor: {e}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+/// A module listed in a project or plugin descriptor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnrealModule {
+    pub name: String,
+    pub kind: ModuleType,
+    pub loading_phase: LoadingPhase,
+    /// Name of the plugin the module belongs to, `None` for project modules
+    pub plugin: Option<String>,
+    /// `Source/<Module>`
+    pub source_dir: PathBuf,
+    pub public_dir: Option<PathBuf>,
+    pub private_dir: Option<PathBuf>,
+    pub classes_dir: Option<PathBuf>,
+    /// Contents of `Source/<Module>/<Module>.Build.cs` if there is one
+    pub rules: Option<ModuleRules>,
+    /// Why the `Build.cs` couldn't be read, `rules` is `None` then
+    pub rules_diagnostic: Option<SourceDiagnostic>,
+}
+
+impl UnrealModule {
+    fn new(descriptor: ModuleDescriptor, plugin: Option<String>, root: &Path) -> UnrealModule {
+        let source_dir = root.join("Source").join(&descriptor.name);
+        let existing = |name: &str| Some(source_dir.join(name)).filter(|dir| dir.is_dir());
+        let (rules, rules_diagnostic) =
+            match ModuleRules::load(&source_dir.join(format!("{}.Build.cs", descriptor.name))) {
+                Ok(rules) => (rules, None),
+                Err(diagnostic) => (None, Some(diagnostic)),
+            };
+
+        UnrealModule {
+            public_dir: existing("Public"),
+            private_dir: existing("Private"),
+            classes_dir: existing("Classes"),
+            rules,
+            rules_diagnostic,
+            name: descriptor.name,
+            kind: descriptor.kind,
+            loading_phase: descriptor.loading_phase,
+            plugin,
+            source_dir,
+        }
+    }
+
+    /// Headers in `Public/`, `Classes/` and `Private/`, sorted within each folder
+    pub fn headers(&self) -> Vec<PathBuf> {
+        let mut headers = vec![];
+        for dir in [&self.public_dir, &self.classes_dir, &self.private_dir]
+            .into_iter()
+            .flatten()
+        {
+            let start = headers.len();
+            collect_headers(dir, &mut headers);
+            headers[start..].sort();
+        }
+
+        headers
+    }
+}
+
+/// A project with the modules of its own and of the plugins in its `Plugins/` folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnrealProject {
+    pub name: String,
+    pub root: PathBuf,
+    pub modules: Vec<UnrealModule>,
+}
+
+impl UnrealProject {
+    pub fn load(uproject: &Path) -> Result<UnrealProject, ProjectError> {
+        let root = uproject.parent().unwrap_or(Path::new("")).to_path_buf();
+        let name = file_stem(uproject);
+
+        let mut modules = read_descriptor(uproject)?
+            .modules
+            .into_iter()
+            .map(|module| UnrealModule::new(module, None, &root))
+            .collect::<Vec<_>>();
+
+        let mut plugins = vec![];
+        find_plugins(&root.join("Plugins"), &mut plugins);
+        plugins.sort();
+        for uplugin in plugins {
+            let plugin_root = uplugin.parent().unwrap_or(Path::new(""));
+            let plugin = file_stem(&uplugin);
+            modules.extend(
+                read_descriptor(&uplugin)?
+                    .modules
+                    .into_iter()
+                    .map(|module| UnrealModule::new(module, Some(plugin.clone()), plugin_root)),
+            );
+        }
+
+        Ok(UnrealProject {
+            name,
+            root,
+            modules,
+        })
+    }
+
+    /// Parses the headers of every module with the Unreal class parser. The public and private
+    /// definitions of a module's rules are added to `config`, which should expand API macros
+    /// like `CORE_API`, e.g. with `MacroTable::unreal()`. Reflection macros the parser doesn't
+    /// read and the generated body macros are skipped in addition to `ignore_rules`.
+    pub fn parse_modules(
+        &self,
+        config: &PreprocessorConfig,
+        ignore_rules: &IgnoreRules,
+    ) -> Vec<ModuleEntities> {
+        let rules = reflection_rules().extend(ignore_rules.clone());
+
+        self.modules
+            .iter()
+            .map(|module| {
+                let definitions = module.rules.iter().flat_map(|rules| {
+                    rules
+                        .public_definitions
+                        .iter()
+                        .chain(&rules.private_definitions)
+                });
+                let config = config.clone().definitions(definitions.map(String::as_str));
+
+                ModuleEntities {
+                    module: module.name.clone(),
+                    headers: module
+                        .headers()
+                        .into_iter()
+                        .map(|path| parse_header(path, &config, &rules))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Module → headers → entities, the tree the documentation is organized by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleEntities {
+    pub module: String,
+    pub headers: Vec<HeaderEntities>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderEntities {
+    pub path: PathBuf,
+    pub entities: Vec<Entity>,
+    /// Why the header couldn't be loaded or parsed, `entities` is empty then
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityKind {
+    Namespace,
+    Class,
+    Struct,
+    Union,
+    Enum,
+    Function,
+    Variable,
+    Alias,
+    /// Using-declaration like `using UE::Math::FVector;` or using-directive
+    Using,
+    Macro,
+}
+
+/// A named declaration, classes and namespaces have the declarations inside them as children
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entity {
+    pub kind: EntityKind,
+    pub name: String,
+    /// Declaration without body and initializers, e.g. `void Fire(float Power) const` or
+    /// `class AWeapon : public AActor`
+    pub signature: Option<String>,
+    /// Doc comment of the declaration
+    pub comment: Option<String>,
+    /// Arguments of the reflection macro as written, e.g. `(EditAnywhere, meta = (ClampMin = 0))`
+    /// of a `UPROPERTY`
+    pub specifiers: Option<String>,
+    /// Conditions of the `#if` branches the declaration is in, outermost first
+    pub conditions: Vec<String>,
+    pub children: Vec<Entity>,
+}
+
+impl Entity {
+    /// Whether the declaration only exists in editor builds, e.g. in `#if WITH_EDITOR`
+    pub fn is_editor_only(&self) -> bool {
+        self.conditions
+            .iter()
+            .any(|condition| matches!(condition.as_str(), "WITH_EDITOR" | "WITH_EDITORONLY_DATA"))
+    }
+
+    /// `name` locates the declaration in `source`, it has to be a slice of the preprocessed text
+    fn leaf(kind: EntityKind, name: &str, source: &PreprocessedSource) -> Entity {
+        Entity {
+            kind,
+            name: name.to_string(),
+            signature: None,
+            comment: None,
+            specifiers: None,
+            conditions: source
+                .conditions_of(name)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            children: vec![],
+        }
+    }
+
+    /// Enum names are owned and can't be located in the source, enums have no conditions
+    fn enumeration(e: &CppEnum, source: &PreprocessedSource) -> Entity {
+        Entity {
+            name: enum_name(&e.name),
+            comment: doc_comment(&e.comment),
+            // the keyword is a slice of the source even if the enum has no name
+            ..Entity::leaf(EntityKind::Enum, e.keyword, source)
+        }
+    }
+
+    fn function(
+        function: &CppFunction,
+        specifiers: Option<&str>,
+        source: &PreprocessedSource,
+    ) -> Entity {
+        Entity {
+            signature: Some(function_signature(function)),
+            comment: doc_comment(&function.comment),
+            specifiers: specifiers.map(str::to_string),
+            ..Entity::leaf(EntityKind::Function, function.name, source)
+        }
+    }
+
+    fn variable(
+        variable: &CppMember,
+        specifiers: Option<&str>,
+        source: &PreprocessedSource,
+    ) -> Entity {
+        Entity {
+            signature: Some(format!("{} {}", variable.ctype, variable.name)),
+            comment: doc_comment(&variable.comment),
+            specifiers: specifiers.map(str::to_string),
+            ..Entity::leaf(EntityKind::Variable, variable.name, source)
+        }
+    }
+
+    fn class(class: &UClass, source: &PreprocessedSource) -> Entity {
+        let mut children = vec![];
+        for visibility in VISIBILITIES {
+            let classes = class.inner_classes.get(&visibility).into_iter().flatten();
+            children.extend(classes.map(|inner| Entity::class(inner, source)));
+            let enums = class.enums.get(&visibility).into_iter().flatten();
+            children.extend(enums.map(|e| Entity::enumeration(e, source)));
+            let aliases = class.aliases.get(&visibility).into_iter().flatten();
+            children
+                .extend(aliases.map(|alias| Entity::leaf(EntityKind::Alias, alias.name, source)));
+            let methods = class.methods.get(&visibility).into_iter().flatten();
+            children.extend(methods.map(|method| {
+                let specifiers = method.annotation.0.first().copied();
+                Entity::function(&method.function, specifiers, source)
+            }));
+            let properties = class.members.get(&visibility).into_iter().flatten();
+            children.extend(properties.map(|property| {
+                let specifiers = property.annotation.0.first().copied();
+                Entity::variable(&property.member, specifiers, source)
+            }));
+            // members of unnamed structs and unions belong to the enclosing class
+            let aggregates = class
+                .anonymous_aggregates
+                .get(&visibility)
+                .into_iter()
+                .flatten();
+            children
+                .extend(aggregates.flat_map(|aggregate| Entity::class(aggregate, source).children));
+        }
+
+        Entity {
+            signature: Some(class_signature(&class.kind, class.name, &class.parents)),
+            specifiers: class
+                .annotation
+                .0
+                .first()
+                .map(|specifiers| specifiers.to_string()),
+            children,
+            ..Entity::leaf(class_kind(&class.kind), class.name, source)
+        }
+    }
+
+    /// Classes in namespaces, which can't be reflected
+    fn cpp_class(class: &CppClass, source: &PreprocessedSource) -> Entity {
+        let mut children = vec![];
+        for visibility in VISIBILITIES {
+            let classes = class.inner_classes.get(&visibility).into_iter().flatten();
+            children.extend(classes.map(|inner| Entity::cpp_class(inner, source)));
+            let enums = class.enums.get(&visibility).into_iter().flatten();
+            children.extend(enums.map(|e| Entity::enumeration(e, source)));
+            let aliases = class.aliases.get(&visibility).into_iter().flatten();
+            children
+                .extend(aliases.map(|alias| Entity::leaf(EntityKind::Alias, alias.name, source)));
+            let methods = class.methods.get(&visibility).into_iter().flatten();
+            children.extend(methods.map(|method| Entity::function(method, None, source)));
+            let members = class.members.get(&visibility).into_iter().flatten();
+            children.extend(members.map(|member| Entity::variable(member, None, source)));
+            // members of unnamed structs and unions belong to the enclosing class
+            let aggregates = class
+                .anonymous_aggregates
+                .get(&visibility)
+                .into_iter()
+                .flatten();
+            children.extend(
+                aggregates.flat_map(|aggregate| Entity::cpp_class(aggregate, source).children),
+            );
+        }
+
+        Entity {
+            signature: Some(class_signature(&class.kind, class.name, &class.parents)),
+            children,
+            ..Entity::leaf(class_kind(&class.kind), class.name, source)
+        }
+    }
+
+    /// Named after the last part of the path, e.g. `Math` for `using namespace UE::Math;`
+    fn using(using: &CppUsing, source: &PreprocessedSource) -> Entity {
+        let (keyword, path) = match using {
+            CppUsing::Declaration(path) => ("using", path),
+            CppUsing::Directive(path) => ("using namespace", path),
+        };
+
+        Entity {
+            signature: Some(format!("{keyword} {}", path.join("::"))),
+            ..Entity::leaf(EntityKind::Using, path.last().unwrap_or(&""), source)
+        }
+    }
+
+    fn namespace(namespace: &CppNamespace, source: &PreprocessedSource) -> Entity {
+        let mut children = vec![];
+        children.extend(
+            namespace
+                .namespaces
+                .iter()
+                .map(|inner| Entity::namespace(inner, source)),
+        );
+        children.extend(
+            namespace
+                .classes
+                .iter()
+                .map(|class| Entity::cpp_class(class, source)),
+        );
+        children.extend(
+            namespace
+                .enums
+                .iter()
+                .map(|e| Entity::enumeration(e, source)),
+        );
+        children.extend(
+            namespace
+                .aliases
+                .iter()
+                .map(|alias| Entity::leaf(EntityKind::Alias, alias.name, source)),
+        );
+        children.extend(
+            namespace
+                .usings
+                .iter()
+                .map(|using| Entity::using(using, source)),
+        );
+        children.extend(
+            namespace
+                .functions
+                .iter()
+                .map(|function| Entity::function(function, None, source)),
+        );
+        children.extend(
+            namespace
+                .variables
+                .iter()
+                .map(|variable| Entity::variable(variable, None, source)),
+        );
+        children.extend(
+            namespace
+                .linkage_blocks
+                .iter()
+                .flat_map(|block| Entity::linkage_block(block, source)),
+        );
+
+        Entity {
+            children,
+            ..Entity::leaf(EntityKind::Namespace, namespace.name, source)
+        }
+    }
+
+    /// `extern "C"` doesn't open a scope, the declarations are listed with the enclosing ones and
+    /// functions and variables keep the linkage in their signature
+    fn linkage_block(block: &CppLinkageBlock, source: &PreprocessedSource) -> Vec<Entity> {
+        let linkage = |entity: Entity| Entity {
+            signature: entity
+                .signature
+                .map(|signature| format!("extern \"{}\" {signature}", block.linkage)),
+            ..entity
+        };
+
+        let mut entities = vec![];
+        entities.extend(
+            block
+                .namespaces
+                .iter()
+                .map(|namespace| Entity::namespace(namespace, source)),
+        );
+        entities.extend(
+            block
+                .classes
+                .iter()
+                .map(|class| Entity::cpp_class(class, source)),
+        );
+        entities.extend(block.enums.iter().map(|e| Entity::enumeration(e, source)));
+        entities.extend(
+            block
+                .aliases
+                .iter()
+                .map(|alias| Entity::leaf(EntityKind::Alias, alias.name, source)),
+        );
+        entities.extend(
+            block
+                .functions
+                .iter()
+                .map(|function| linkage(Entity::function(function, None, source))),
+        );
+        entities.extend(
+            block
+                .variables
+                .iter()
+                .map(|variable| linkage(Entity::variable(variable, None, source))),
+        );
+        entities.extend(
+            block
+                .linkage_blocks
+                .iter()
+                .flat_map(|block| Entity::linkage_block(block, source)),
+        );
+
+        entities
+    }
+
+    fn header(header: &CppHeader<UClass>, source: &PreprocessedSource) -> Vec<Entity> {
+        let mut entities = vec![];
+        entities.extend(
+            header
+                .namespaces()
+                .iter()
+                .map(|namespace| Entity::namespace(namespace, source)),
+        );
+        entities.extend(
+            header
+                .classes()
+                .iter()
+                .map(|class| Entity::class(class, source)),
+        );
+        entities.extend(
+            header
+                .enums()
+                .iter()
+                .map(|e| Entity::enumeration(e, source)),
+        );
+        entities.extend(
+            header
+                .aliases()
+                .iter()
+                .map(|alias| Entity::leaf(EntityKind::Alias, alias.name, source)),
+        );
+        entities.extend(
+            header
+                .usings()
+                .iter()
+                .map(|using| Entity::using(using, source)),
+        );
+        entities.extend(
+            header
+                .functions()
+                .iter()
+                .map(|function| Entity::function(function, None, source)),
+        );
+        entities.extend(
+            header
+                .declarations()
+                .iter()
+                .map(|variable| Entity::variable(variable, None, source)),
+        );
+        entities.extend(
+            header
+                .linkage_blocks()
+                .iter()
+                .flat_map(|block| Entity::linkage_block(block, source)),
+        );
+        entities.extend(header.macros().iter().map(|define| Entity {
+            comment: doc_comment(&define.comment),
+            ..Entity::leaf(EntityKind::Macro, define.name, source)
+        }));
+
+        entities
+    }
+}
+
+const VISIBILITIES: [InheritanceVisibility; 3] = [
+    InheritanceVisibility::Public,
+    InheritanceVisibility::Protected,
+    InheritanceVisibility::Private,
+];
+
+fn class_kind(kind: &ClassKind) -> EntityKind {
+    match kind {
+        ClassKind::Class => EntityKind::Class,
+        ClassKind::Struct => EntityKind::Struct,
+        ClassKind::Union => EntityKind::Union,
+    }
+}
+
+/// `class AWeapon : public AActor`
+fn class_signature(kind: &ClassKind, name: &str, parents: &[CppParentClass]) -> String {
+    let keyword = match kind {
+        ClassKind::Class => "class",
+        ClassKind::Struct => "struct",
+        ClassKind::Union => "union",
+    };
+    let parents = parents
+        .iter()
+        .map(|parent| {
+            let visibility = match parent.visibility {
+                InheritanceVisibility::Public => "public ",
+                InheritanceVisibility::Protected => "protected ",
+                InheritanceVisibility::Private => "private ",
+                InheritanceVisibility::Virtual => "virtual ",
+                InheritanceVisibility::Empty => "",
+            };
+            format!("{visibility}{}", parent.name)
+        })
+        .collect::<Vec<_>>();
+
+    match parents.is_empty() {
+        true => format!("{keyword} {name}"),
+        false => format!("{keyword} {name} : {}", parents.join(", ")),
+    }
+}
+
+/// `float Damage(float Base, bool bCritical = false) const`
+fn function_signature(function: &CppFunction) -> String {
+    let params = function
+        .params
+        .iter()
+        .map(|param| {
+            let declaration = match param.name {
+                Some(name) => format!("{} {name}", param.ctype),
+                None => param.ctype.to_string(),
+            };
+            match &param.default_value {
+                Some(default_value) => format!("{declaration} = {default_value}"),
+                None => declaration,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // the parser drops `void`, only constructors, destructors and conversions have no return type
+    let mut signature = match (&function.return_type, &function.kind) {
+        (Some(return_type), _) => format!("{return_type} {}({params})", function.name),
+        (
+            None,
+            FunctionKind::Constructor
+            | FunctionKind::CopyConstructor
+            | FunctionKind::MoveConstructor
+            | FunctionKind::Destructor
+            | FunctionKind::ConversionOperator,
+        ) => format!("{}({params})", function.name),
+        (None, _) => format!("void {}({params})", function.name),
+    };
+    if function
+        .post_param_qualifiers
+        .contains(&PostParamQualifier::Const)
+    {
+        signature.push_str(" const");
+    }
+
+    signature
+}
+
+fn doc_comment(comment: &Option<CppComment>) -> Option<String> {
+    comment
+        .as_ref()
+        .map(|comment| comment.comment.trim().to_string())
+}
+
+fn enum_name(name: &Option<String>) -> String {
+    name.clone().unwrap_or_default()
+}
+
+/// Reflection markup the Unreal class parser doesn't read, UHT reads it but the C++ compiler
+/// doesn't
+fn reflection_rules() -> IgnoreRules {
+    ["UENUM", "UDELEGATE"]
+        .into_iter()
+        .fold(IgnoreRules::unreal(), IgnoreRules::macro_call)
+}
+
+fn parse_header(path: PathBuf, config: &PreprocessorConfig, rules: &IgnoreRules) -> HeaderEntities {
+    let source = match SourceText::load(&path) {
+        Ok(source) => source,
+        Err(diagnostic) => {
+            return HeaderEntities {
+                path,
+                entities: vec![],
+                error: Some(diagnostic.error.to_string()),
+            };
+        }
+    };
+
+    let preprocessed = preprocess(&source.text, config);
+    let (entities, error) = match CppHeader::<UClass>::parse_with_rules(&preprocessed.text, rules) {
+        Ok((_, header)) => (Entity::header(&header, &preprocessed), None),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+            (vec![], Some(convert_error(preprocessed.text.as_str(), e)))
+        }
+        Err(nom::Err::Incomplete(_)) => (vec![], Some("incomplete input".to_string())),
+    };
+
+    HeaderEntities {
+        path,
+        entities,
+        error,
+    }
+}
+
+fn read_descriptor(path: &Path) -> Result<Descriptor, ProjectError> {
+    let source = SourceText::load(path).map_err(|d| {
+        ProjectError::Io(
+            path.to_path_buf(),
+            std::io::Error::other(d.error.to_string()),
+        )
+    })?;
+
+    serde_json::from_str(&source.text).map_err(|e| ProjectError::Json(path.to_path_buf(), e))
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn find_plugins(dir: &Path, plugins: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+        if path.is_dir() {
+            find_plugins(&path, plugins);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "uplugin")
+        {
+            plugins.push(path);
+        }
+    }
+}
+
+fn collect_headers(dir: &Path, headers: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_headers(&path, headers);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "h" || extension == "hpp")
+        {
+            headers.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::cpp::macro_table::MacroTable;
+    use crate::parser::cpp::preprocessor::PreprocessorConfig;
+    use crate::parser::generic::ignore::IgnoreRules;
+    use crate::parser::temp_project::TempProject;
+    use crate::parser::ue::project::{Entity, EntityKind, LoadingPhase, ModuleType, UnrealProject};
+
+    fn leaf(kind: EntityKind, name: &str) -> Entity {
+        Entity {
+            kind,
+            name: name.to_string(),
+            signature: None,
+            comment: None,
+            specifiers: None,
+            conditions: vec![],
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn modules_headers_and_entities() {
+        let root = TempProject::new(
+            "project",
+            &[
+                (
+                    "Shooter.uproject",
+                    r#"{
+                    "FileVersion": 3,
+                    "EngineAssociation": "5.4",
+                    "Modules": [
+                        { "Name": "Shooter", "Type": "Runtime", "LoadingPhase": "Default" },
+                        { "Name": "ShooterEditor", "Type": "Editor", "LoadingPhase": "PostEngineInit" }
+                    ]
+                }"#,
+                ),
+                (
+                    "Source/Shooter/Public/Weapon.h",
+                    r#"#pragma once
+#include "CoreMinimal.h"
+
+UCLASS()
+class SHOOTER_API AWeapon : public AActor
+{
+    GENERATED_BODY()
+public:
+    /** Shoots once */
+    UFUNCTION(BlueprintCallable)
+    FORCEINLINE void Fire(float Power = 1.0f) const {}
+
+    UPROPERTY(EditAnywhere)
+    int32 Ammo;
+
+#if WITH_EDITOR
+    UPROPERTY(VisibleAnywhere)
+    int32 EditorAmmo;
+#endif
+};
+"#,
+                ),
+                ("Source/Shooter/Private/Weapon.cpp", ""),
+                (
+                    "Source/Shooter/Shooter.Build.cs",
+                    r#"public class Shooter : ModuleRules
+{
+    public Shooter(ReadOnlyTargetRules Target) : base(Target)
+    {
+        PublicDependencyModuleNames.AddRange(new string[] { "Core", "Engine" });
+        PrivateDependencyModuleNames.Add("LootCore");
+        PublicDefinitions.Add("WITH_EDITOR=1");
+    }
+}"#,
+                ),
+                (
+                    "Source/Shooter/Private/WeaponMath.h",
+                    "#define MAX_AMMO 30\n",
+                ),
+                (
+                    "Source/Shooter/Private/WeaponTrace.h",
+                    "#ifndef WEAPON_TRACE_H\n#define WEAPON_TRACE_H\n#if !UE_BUILD_SHIPPING\nvoid DrawTrace();\nenum ETraceMode { Line, Sphere };\n#endif\nusing namespace UE::Math;\nvoid Trace();\n#endif\n",
+                ),
+                ("Source/ShooterEditor/Public/Broken.h", "class {"),
+                (
+                    "Plugins/Runtime/Loot/Loot.uplugin",
+                    r#"{ "FriendlyName": "Loot", "Modules": [ { "Name": "LootCore", "Type": "Runtime" } ] }"#,
+                ),
+                (
+                    "Plugins/Runtime/Loot/Source/LootCore/Classes/LootTable.h",
+                    "namespace Loot { struct FLootTable { union { int32 Seed; float Weight; }; }; extern \"C\" int32 LootSeed(); }",
+                ),
+            ],
+        );
+
+        let project = UnrealProject::load(&root.join("Shooter.uproject")).unwrap();
+        assert_eq!(project.name, "Shooter");
+        assert_eq!(
+            project
+                .modules
+                .iter()
+                .map(|module| (
+                    module.name.as_str(),
+                    module.kind,
+                    module.loading_phase,
+                    module.plugin.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Shooter", ModuleType::Runtime, LoadingPhase::Default, None),
+                (
+                    "ShooterEditor",
+                    ModuleType::Editor,
+                    LoadingPhase::PostEngineInit,
+                    None
+                ),
+                (
+                    "LootCore",
+                    ModuleType::Runtime,
+                    LoadingPhase::Default,
+                    Some("Loot")
+                ),
+            ]
+        );
+        let shooter = &project.modules[0];
+        let rules = shooter.rules.as_ref().unwrap();
+        assert_eq!(rules.public_dependencies, vec!["Core", "Engine"]);
+        assert_eq!(rules.private_dependencies, vec!["LootCore"]);
+        assert_eq!(project.modules[1].rules, None);
+        assert_eq!(project.modules[1].rules_diagnostic, None);
+        assert_eq!(shooter.public_dir, Some(root.join("Source/Shooter/Public")));
+        assert_eq!(shooter.classes_dir, None);
+        assert_eq!(
+            shooter.headers(),
+            vec![
+                root.join("Source/Shooter/Public/Weapon.h"),
+                root.join("Source/Shooter/Private/WeaponMath.h"),
+                root.join("Source/Shooter/Private/WeaponTrace.h"),
+            ]
+        );
+
+        let config = PreprocessorConfig::default().macros(MacroTable::unreal());
+        let modules = project.parse_modules(&config, &IgnoreRules::default());
+        let weapon = &modules[0].headers[0];
+        assert_eq!(weapon.error, None);
+        assert_eq!(
+            weapon.entities,
+            vec![Entity {
+                signature: Some("class AWeapon : public AActor".to_string()),
+                specifiers: Some("()".to_string()),
+                children: vec![
+                    Entity {
+                        signature: Some("void Fire(float Power = 1.0f) const".to_string()),
+                        comment: Some("Shoots once".to_string()),
+                        specifiers: Some("(BlueprintCallable)".to_string()),
+                        ..leaf(EntityKind::Function, "Fire")
+                    },
+                    Entity {
+                        signature: Some("int32 Ammo".to_string()),
+                        specifiers: Some("(EditAnywhere)".to_string()),
+                        ..leaf(EntityKind::Variable, "Ammo")
+                    },
+                    Entity {
+                        signature: Some("int32 EditorAmmo".to_string()),
+                        specifiers: Some("(VisibleAnywhere)".to_string()),
+                        conditions: vec!["WITH_EDITOR".to_string()],
+                        ..leaf(EntityKind::Variable, "EditorAmmo")
+                    },
+                ],
+                ..leaf(EntityKind::Class, "AWeapon")
+            }]
+        );
+        assert!(weapon.entities[0].children[2].is_editor_only());
+        assert_eq!(
+            modules[0].headers[1].entities,
+            vec![leaf(EntityKind::Macro, "MAX_AMMO")]
+        );
+
+        let trace = &modules[0].headers[2].entities;
+        assert_eq!(
+            trace
+                .iter()
+                .map(|entity| (entity.name.as_str(), entity.conditions.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("ETraceMode", vec!["!UE_BUILD_SHIPPING".to_string()]),
+                ("Math", vec![]),
+                ("DrawTrace", vec!["!UE_BUILD_SHIPPING".to_string()]),
+                ("Trace", vec![]),
+            ]
+        );
+        assert!(!trace[2].is_editor_only());
+        assert_eq!(trace[1].kind, EntityKind::Using);
+        assert_eq!(
+            trace[1].signature.as_deref(),
+            Some("using namespace UE::Math")
+        );
+
+        let broken = &modules[1].headers[0];
+        assert!(broken.entities.is_empty());
+        assert!(broken.error.is_some());
+
+        assert_eq!(modules[2].module, "LootCore");
+        assert_eq!(
+            modules[2].headers[0].entities,
+            vec![Entity {
+                children: vec![
+                    Entity {
+                        signature: Some("struct FLootTable".to_string()),
+                        children: vec![
+                            Entity {
+                                signature: Some("int32 Seed".to_string()),
+                                ..leaf(EntityKind::Variable, "Seed")
+                            },
+                            Entity {
+                                signature: Some("float Weight".to_string()),
+                                ..leaf(EntityKind::Variable, "Weight")
+                            },
+                        ],
+                        ..leaf(EntityKind::Struct, "FLootTable")
+                    },
+                    Entity {
+                        signature: Some("extern \"C\" int32 LootSeed()".to_string()),
+                        ..leaf(EntityKind::Function, "LootSeed")
+                    },
+                ],
+                ..leaf(EntityKind::Namespace, "Loot")
+            }]
+        );
+    }
+}
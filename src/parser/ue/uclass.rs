@@ -1,22 +1,36 @@
-﻿use crate::parser::cpp::comment::CppComment;
+﻿use crate::parser::cpp::alias::CppAlias;
+use crate::parser::cpp::attribute::{Attributed, CppAttribute, parenthesized};
+use crate::parser::cpp::cenum::CppEnum;
+use crate::parser::cpp::comment::CppComment;
+use crate::parser::cpp::ctype::CType;
+use crate::parser::cpp::using::CppUsing;
 use crate::parser::generic::annotation::Annotation;
-use crate::parser::generic::class::{Class, CppParentClass, InheritanceVisibility};
-use crate::parser::ue::ufunction::{UFunction};
+use crate::parser::generic::class::{
+    Class, ClassDefinition, ClassKind, CppParentClass, InheritanceVisibility,
+};
+use crate::parser::ue::ufunction::UFunction;
 use crate::parser::ue::uproperty::{UProperty, UPropertyAnnotation};
-use nom::bytes::complete::{tag, take_till};
+use nom::branch::alt;
+use nom::bytes::complete::tag;
 use nom::character::complete::multispace0;
+use nom::combinator::recognize;
 use nom::sequence::preceded;
 use nom::{IResult, Parser};
 use nom_language::error::VerboseError;
 use std::collections::HashMap;
 
+/// Specifiers of `UCLASS(...)`, `USTRUCT(...)` or `UINTERFACE(...)` including the parentheses
 #[derive(Debug, PartialEq, Default, Clone)]
-pub struct UClassAnnotation<'a>(Vec<&'a str>);
+pub struct UClassAnnotation<'a>(pub Vec<&'a str>);
 
 impl<'a> Annotation<'a> for UClassAnnotation<'a> {
     fn parse(input: &'a str) -> IResult<&'a str, Self, VerboseError<&'a str>> {
         // TODO fill me with life
-        let (input, properties) = preceded(tag("UCLASS"), take_till(|c| c == '\n')).parse(input)?;
+        let (input, properties) = preceded(
+            alt((tag("UCLASS"), tag("USTRUCT"), tag("UINTERFACE"))),
+            preceded(multispace0, recognize(parenthesized)),
+        )
+        .parse(input)?;
         let (input, _) = multispace0(input)?;
 
         Ok((input, Self(vec![properties])))
@@ -26,11 +40,21 @@ impl<'a> Annotation<'a> for UClassAnnotation<'a> {
 #[derive(Debug, PartialEq)]
 pub struct UClass<'a> {
     pub name: &'a str,
+    pub kind: ClassKind,
     pub api: Option<&'a str>,
     pub parents: Vec<CppParentClass<'a>>,
     pub methods: HashMap<InheritanceVisibility, Vec<UFunction<'a>>>,
     pub members: HashMap<InheritanceVisibility, Vec<UProperty<'a>>>,
     pub inner_classes: HashMap<InheritanceVisibility, Vec<UClass<'a>>>,
+    /// Unnamed structs and unions whose members belong to the enclosing class
+    pub anonymous_aggregates: HashMap<InheritanceVisibility, Vec<UClass<'a>>>,
+    pub aliases: HashMap<InheritanceVisibility, Vec<CppAlias<'a>>>,
+    pub enums: HashMap<InheritanceVisibility, Vec<CppEnum<'a>>>,
+    /// Using-declarations like `using Super::BeginPlay;` and using-directives
+    pub usings: HashMap<InheritanceVisibility, Vec<CppUsing<'a>>>,
+    /// Classes declared with `friend class`
+    pub friends: Vec<CType<'a>>,
+    pub attributes: Vec<CppAttribute<'a>>,
     pub annotation: UClassAnnotation<'a>,
 }
 
@@ -38,16 +62,29 @@ impl Default for UClass<'_> {
     fn default() -> Self {
         Self {
             name: "",
+            kind: ClassKind::Class,
             api: None,
             parents: vec![],
             methods: HashMap::from([]),
             members: HashMap::from([]),
             inner_classes: HashMap::from([]),
+            anonymous_aggregates: HashMap::from([]),
+            aliases: HashMap::from([]),
+            enums: HashMap::from([]),
+            usings: HashMap::from([]),
+            friends: vec![],
+            attributes: vec![],
             annotation: Default::default(),
         }
     }
 }
 
+impl<'a> Attributed<'a> for UClass<'a> {
+    fn attributes(&self) -> &[CppAttribute<'a>] {
+        &self.attributes
+    }
+}
+
 impl<'a> Class<'a> for UClass<'a> {
     type ClassAnnotation = UClassAnnotation<'a>;
     type MemberAnnotation = UPropertyAnnotation<'a>;
@@ -55,53 +92,46 @@ impl<'a> Class<'a> for UClass<'a> {
     type Method = UFunction<'a>;
     type Member = UProperty<'a>;
 
-    fn class(
-        name: &'a str,
-        api: Option<&'a str>,
-        parents: Vec<CppParentClass<'a>>,
-        methods: HashMap<InheritanceVisibility, Vec<Self::Method>>,
-        members: HashMap<InheritanceVisibility, Vec<Self::Member>>,
-        inner_classes: HashMap<InheritanceVisibility, Vec<Self>>,
-        annotation: Option<Vec<Self::ClassAnnotation>>,
-    ) -> Self
+    fn class(definition: ClassDefinition<'a, Self>) -> Self
     where
         Self: 'a + Sized,
     {
-        let default = UClassAnnotation(vec![]); // this now lives long enough
-        let annotation = annotation
-            .unwrap_or_default()
-            .get(0)
-            .unwrap_or(&default) // now `&default` has a valid lifetime
-            .clone();
-
         Self {
-            name,
-            api,
-            parents,
-            methods,
-            members,
-            inner_classes,
-            annotation,
+            name: definition.name,
+            kind: definition.kind,
+            api: definition.api,
+            parents: definition.parents,
+            methods: definition.methods,
+            members: definition.members,
+            inner_classes: definition.inner_classes,
+            anonymous_aggregates: definition.anonymous_aggregates,
+            aliases: definition.aliases,
+            enums: definition.enums,
+            usings: definition.usings,
+            friends: definition.friends,
+            attributes: definition.attributes,
+            annotation: definition.annotations.into_iter().next().unwrap_or_default(),
         }
     }
+
+    fn name(&self) -> &'a str {
+        self.name
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::parser::cpp::comment::CppComment;
     use crate::parser::cpp::ctype::CType;
-    use crate::parser::cpp::method::{CppFunction, CppMethodParam};
+    use crate::parser::cpp::macro_table::MacroTable;
+    use crate::parser::cpp::method::{CppFunction, CppMethodParam, FunctionKind};
     use crate::parser::generic::class::InheritanceVisibility::{Protected, Public};
-    use crate::parser::generic::class::{CppParentClass, parse_class};
+    use crate::parser::generic::class::{ClassKind, CppParentClass, parse_class};
+    use crate::parser::generic::ignore::IgnoreRules;
     use crate::parser::generic::method::CppStorageQualifier::Virtual;
     use crate::parser::generic::method::PostParamQualifier::Override;
     use crate::parser::ue::uclass::{UClass, UClassAnnotation};
     use crate::parser::ue::ufunction::{UFunction, UFunctionAnnotation};
-    use nom::bytes::complete::tag;
-    use nom::character::complete::multispace0;
-    use nom::sequence::preceded;
-    use nom::{IResult, Parser};
-    use nom_language::error::VerboseError;
     use std::collections::HashMap;
 
     #[test]
@@ -120,6 +150,7 @@ protected:
 
         let expected = UClass {
             name: "AClass",
+            kind: ClassKind::Class,
             api: Some("COMMON_API"),
             parents: vec![CppParentClass {
                 name: CType::Path(vec!["AActor"]),
@@ -133,15 +164,17 @@ protected:
                             name: "AClass",
                             params: vec![CppMethodParam {
                                 name: Some("ObjectInitializer"),
-                                ctype: CType::Const(Box::from(CType::Reference(Box::from(
+                                ctype: CType::Reference(Box::from(CType::Const(Box::from(
                                     CType::Path(vec!["FObjectInitializer"]),
                                 )))),
                                 default_value: None,
+                                attributes: vec![],
                             }],
                             comment: Some(CppComment {
                                 comment: "Sets default values for this character's properties"
                                     .to_string(),
                             }),
+                            kind: FunctionKind::Constructor,
                             ..Default::default()
                         },
                         annotation: UFunctionAnnotation(vec![]),
@@ -165,14 +198,18 @@ protected:
             ]),
             members: Default::default(),
             inner_classes: Default::default(),
+            anonymous_aggregates: Default::default(),
+            aliases: Default::default(),
+            enums: Default::default(),
+            usings: Default::default(),
+            friends: Default::default(),
+            attributes: Default::default(),
             annotation: UClassAnnotation(vec!["()"]),
         };
 
-        let ignore_generate_body = |input| -> IResult<&str, &str, VerboseError<&str>> {
-            preceded(tag("GENERATED_BODY()"), multispace0).parse(input)
-        };
+        let input = MacroTable::unreal().expand(input);
         assert_eq!(
-            parse_class(input, &vec![ignore_generate_body]),
+            parse_class(&input, &IgnoreRules::unreal()),
             Ok(("", expected))
         );
     }
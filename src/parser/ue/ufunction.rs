@@ -1,24 +1,28 @@
-﻿use crate::parser::cpp::comment::CppComment;
-use crate::parser::cpp::ctype::CType;
+use crate::parser::cpp::comment::CppComment;
 use crate::parser::generic::annotation::Annotation;
 
-use crate::parser::cpp::method::{CppFunction, CppMethodParam};
-use crate::parser::generic::method::{
-    CppStorageQualifier, Method, PostParamQualifier, SpecialMember,
-};
-use nom::bytes::complete::{tag, take_till};
+use crate::parser::cpp::attribute::parenthesized;
+use crate::parser::cpp::method::CppFunction;
+use crate::parser::generic::method::{Method, MethodDefinition};
+use nom::bytes::complete::tag;
+use nom::character::complete::multispace0;
+use nom::combinator::recognize;
 use nom::sequence::preceded;
 use nom::{IResult, Parser};
 use nom_language::error::VerboseError;
 
+/// Specifiers and metadata of `UFUNCTION(...)` including the parentheses
 #[derive(Debug, PartialEq, Default, Clone)]
 pub struct UFunctionAnnotation<'a>(pub Vec<&'a str>);
 
 impl<'a> Annotation<'a> for UFunctionAnnotation<'a> {
     fn parse(input: &'a str) -> IResult<&'a str, Self, VerboseError<&'a str>> {
         // TODO fill me with life
-        let (input, properties) =
-            preceded(tag("UFUNCTION"), take_till(|c| c == '\n')).parse(input)?;
+        let (input, properties) = preceded(
+            tag("UFUNCTION"),
+            preceded(multispace0, recognize(parenthesized)),
+        )
+        .parse(input)?;
 
         Ok((input, Self(vec![properties])))
     }
@@ -34,39 +38,31 @@ impl<'a> Method<'a> for UFunction<'a> {
     type MethodAnnotation = UFunctionAnnotation<'a>;
     type Comment = CppComment;
 
-    fn method(
-        name: &'a str,
-        return_type: Option<CType<'a>>,
-        template_params: Vec<CType<'a>>,
-        params: Vec<CppMethodParam<'a>>,
-        storage_qualifiers: Vec<CppStorageQualifier>,
-        post_param_qualifiers: Vec<PostParamQualifier>,
-        special: Option<SpecialMember>,
-        comment: Option<CppComment>,
-        annotations: Vec<UFunctionAnnotation<'a>>,
-    ) -> Self
-    where
-        UFunctionAnnotation<'a>: Annotation<'a> + 'a,
-        CppComment: From<String>,
-        Self: 'a,
-    {
-        let annotation = annotations.get(0).cloned().unwrap_or_default();
+    fn method(definition: MethodDefinition<'a, Self>) -> Self {
+        let annotation = definition.annotations.first().cloned().unwrap_or_default();
 
         UFunction {
-            function: CppFunction::method(
-                name,
-                return_type,
-                template_params,
-                params,
-                storage_qualifiers,
-                post_param_qualifiers,
-                special,
-                comment,
-                vec![],
-            ),
+            function: CppFunction::method(MethodDefinition {
+                name: definition.name,
+                return_type: definition.return_type,
+                template_params: definition.template_params,
+                params: definition.params,
+                storage_qualifiers: definition.storage_qualifiers,
+                post_param_qualifiers: definition.post_param_qualifiers,
+                special: definition.special,
+                initializers: definition.initializers,
+                body: definition.body,
+                comment: definition.comment,
+                attributes: definition.attributes,
+                annotations: vec![],
+            }),
             annotation,
         }
     }
+
+    fn declared_in(&mut self, class_name: &str) {
+        self.function.declared_in(class_name);
+    }
 }
 
 /*
@@ -1,22 +1,31 @@
-﻿use crate::parser::cpp::comment::CppComment;
+﻿use crate::parser::cpp::attribute::CppAttribute;
+use crate::parser::cpp::comment::CppComment;
 use crate::parser::cpp::ctype::CType;
+use crate::parser::cpp::expression::CppExpression;
 use crate::parser::cpp::member::{CppMember, CppMemberModifier};
 use crate::parser::generic::annotation::Annotation;
 use crate::parser::generic::member::Member;
 
-use nom::bytes::complete::{tag, take_till};
+use crate::parser::cpp::attribute::parenthesized;
+use nom::bytes::complete::tag;
+use nom::character::complete::multispace0;
+use nom::combinator::recognize;
 use nom::sequence::preceded;
 use nom::{IResult, Parser};
 use nom_language::error::VerboseError;
 
+/// Specifiers and metadata of `UPROPERTY(...)` including the parentheses
 #[derive(Debug, PartialEq, Default, Clone)]
-pub struct UPropertyAnnotation<'a>(Vec<&'a str>);
+pub struct UPropertyAnnotation<'a>(pub Vec<&'a str>);
 
 impl<'a> Annotation<'a> for UPropertyAnnotation<'a> {
     fn parse(input: &'a str) -> IResult<&'a str, Self, VerboseError<&'a str>> {
         // TODO fill me with life
-        let (input, properties) =
-            preceded(tag("UPROPERTY"), take_till(|c| c == '\n')).parse(input)?;
+        let (input, properties) = preceded(
+            tag("UPROPERTY"),
+            preceded(multispace0, recognize(parenthesized)),
+        )
+        .parse(input)?;
 
         Ok((input, Self(vec![properties])))
     }
@@ -35,15 +44,24 @@ impl<'a> Member<'a> for UProperty<'a> {
     fn member(
         name: &'a str,
         ctype: CType<'a>,
-        default_value: Option<CType<'a>>,
+        default_value: Option<CppExpression<'a>>,
         comment: Option<CppComment>,
         modifiers: Vec<CppMemberModifier>,
+        attributes: Vec<CppAttribute<'a>>,
         annotations: Vec<UPropertyAnnotation<'a>>,
     ) -> UProperty<'a> {
-        let annotation = annotations.get(0).cloned().unwrap_or_default();
+        let annotation = annotations.first().cloned().unwrap_or_default();
 
         UProperty {
-            member: CppMember::member(name, ctype, default_value, comment, modifiers, vec![]),
+            member: CppMember::member(
+                name,
+                ctype,
+                default_value,
+                comment,
+                modifiers,
+                attributes,
+                vec![],
+            ),
             annotation,
         }
     }
@@ -72,6 +90,7 @@ mod test {
                     default_value: None,
                     comment: None,
                     modifiers: vec![],
+                    attributes: vec![],
                 },
                 annotation: UPropertyAnnotation(vec!["(EditAnywhere, Meta = (Bitmask))"]),
             },